@@ -501,7 +501,8 @@ export async function activate(ctx) {
     const attempts = ports.map(async (port) => {
       const url = `ws://${HOST}:${port}`;
       try {
-        const id = await ctx.ws.open(url, { Origin: ORIGIN }, 750);
+        // Pinged so a connection lost over sleep/resume closes and the reconnect logic kicks in.
+        const id = await ctx.ws.open(url, { Origin: ORIGIN }, 750, { pingIntervalMs: 5000 });
         return { id, port };
       } catch (e) {
         if (port === PORT_START) {
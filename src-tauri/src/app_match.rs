@@ -0,0 +1,286 @@
+use crate::model::{BindingTarget, SessionInfo};
+use std::path::Path;
+
+/// Whether a session belongs to the application a target names. Used both by
+/// the audio backends when changing an application and by the app when reading
+/// its state back, so volume, mute and feedback all find the same sessions.
+///
+/// `target_name` must already be lowercased. Matches the executable's path stem,
+/// the process name with or without `.exe`, the display name, and the
+/// humanized labels shown for sessions without a display name. A target that is
+/// a full path only matches that exact executable.
+pub fn application_matches(
+    target_name: &str,
+    process_path: Option<&str>,
+    process_name: Option<&str>,
+    display_name: Option<&str>,
+) -> bool {
+    let target_name = target_name.trim();
+    let matches = |label: &str| label.trim().to_lowercase() == target_name;
+    if is_path(target_name) {
+        return process_path.is_some_and(matches);
+    }
+
+    if let Some(path) = process_path {
+        if Path::new(path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(matches)
+        {
+            return true;
+        }
+    }
+    if let Some(name) = process_name {
+        if matches(strip_exe(name)) {
+            return true;
+        }
+    }
+    if display_name.is_some_and(matches) {
+        return true;
+    }
+    if process_path
+        .and_then(friendly_process_label)
+        .is_some_and(|label| matches(&label))
+    {
+        return true;
+    }
+    process_name.is_some_and(|name| matches(&humanize_label(name)))
+}
+
+/// "Discord.EXE" -> "Discord". Windows reports the extension in any case.
+fn strip_exe(name: &str) -> &str {
+    match name.len().checked_sub(4) {
+        Some(stem) if name.is_char_boundary(stem) && name[stem..].eq_ignore_ascii_case(".exe") => {
+            &name[..stem]
+        }
+        _ => name,
+    }
+}
+
+fn is_path(target_name: &str) -> bool {
+    target_name.contains(['/', '\\'])
+}
+
+/// Runs `apply` (a backend call taking an application name) against the exact
+/// executable `path` first, then against `name` if that fails, e.g. because no
+/// running app has that path on this machine.
+pub fn with_application<T>(
+    name: &str,
+    path: Option<&str>,
+    apply: impl Fn(&str) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    if let Some(result) = path.and_then(|path| apply(path).ok()) {
+        return Ok(result);
+    }
+    apply(name)
+}
+
+/// Runs `apply` for every member of an application group, skipping members it
+/// fails for (usually because they aren't running). Fails only when it worked
+/// for none of them.
+pub fn for_each_application(
+    names: &[String],
+    apply: impl Fn(&str) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut result = Err(anyhow::anyhow!("No application in the group is running"));
+    for name in names {
+        if apply(name).is_ok() {
+            result = Ok(());
+        }
+    }
+    result
+}
+
+/// Finds the session an Application target refers to, preferring an exact
+/// executable path match.
+pub fn find_application_target<'a>(
+    sessions: &'a [SessionInfo],
+    name: &str,
+    path: Option<&str>,
+) -> Option<&'a SessionInfo> {
+    path.and_then(|path| find_application_session(sessions, path))
+        .or_else(|| find_application_session(sessions, name))
+}
+
+/// Finds the session an Application target name refers to.
+pub fn find_application_session<'a>(
+    sessions: &'a [SessionInfo],
+    name: &str,
+) -> Option<&'a SessionInfo> {
+    let target_name = name.to_lowercase();
+    sessions.iter().find(|session| {
+        application_matches(
+            &target_name,
+            session.process_path.as_deref(),
+            session.process_name.as_deref(),
+            Some(&session.display_name),
+        )
+    })
+}
+
+/// A session id without its process id, which changes every time the app
+/// starts. Ids look like `[device|]pid:identifier`; sessions that had no
+/// identifier (`pid:N`) have no stable key.
+pub fn stable_session_key(session_id: &str) -> Option<String> {
+    let strip_pid = |id: &str| {
+        let (pid, identifier) = id.split_once(':')?;
+        (!pid.is_empty() && pid.bytes().all(|byte| byte.is_ascii_digit()))
+            .then(|| identifier.to_string())
+    };
+    strip_pid(session_id).or_else(|| {
+        let (device_id, inner) = session_id.split_once('|')?;
+        strip_pid(inner).map(|identifier| format!("{}|{}", device_id, identifier))
+    })
+}
+
+/// Finds the live session a Session target refers to: the exact id while the
+/// app keeps running, otherwise the session with the same stable key (or, for
+/// ids without one, the same executable path).
+pub fn find_session_target<'a>(
+    sessions: &'a [SessionInfo],
+    session_id: &str,
+    path: Option<&str>,
+) -> Option<&'a SessionInfo> {
+    if let Some(session) = sessions.iter().find(|session| session.id == session_id) {
+        return Some(session);
+    }
+    let same_path = |session: &SessionInfo| {
+        session
+            .process_path
+            .as_deref()
+            .zip(path)
+            .is_some_and(|(session_path, path)| session_path.eq_ignore_ascii_case(path))
+    };
+    match stable_session_key(session_id) {
+        Some(key) => sessions.iter().find(|session| {
+            (path.is_none() || same_path(session))
+                && stable_session_key(&session.id).as_deref() == Some(key.as_str())
+        }),
+        None => path.and_then(|_| sessions.iter().find(|session| same_path(session))),
+    }
+}
+
+/// The session a Master, Session or Application target resolves to. Other
+/// targets aren't a single session in the list.
+pub fn resolve_target_session<'a>(
+    sessions: &'a [SessionInfo],
+    target: &BindingTarget,
+) -> Option<&'a SessionInfo> {
+    match target {
+        BindingTarget::Master => sessions.iter().find(|session| session.is_master),
+        BindingTarget::Session { session_id, path } => {
+            find_session_target(sessions, session_id, path.as_deref())
+        }
+        BindingTarget::Application { name, path } => {
+            find_application_target(sessions, name, path.as_deref())
+        }
+        _ => None,
+    }
+}
+
+/// "my_game-launcher.exe" -> "My Game Launcher".
+pub fn friendly_process_label(path: &str) -> Option<String> {
+    let stem = Path::new(path).file_stem()?.to_string_lossy();
+    let label = humanize_label(&stem);
+    if label.is_empty() {
+        None
+    } else {
+        Some(label)
+    }
+}
+
+pub fn humanize_label(label: &str) -> String {
+    let cleaned = label.replace(['_', '-'], " ");
+    cleaned
+        .split_whitespace()
+        .map(humanize_word)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn humanize_word(word: &str) -> String {
+    if word.chars().any(|ch| ch.is_uppercase()) {
+        return word.to_string();
+    }
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => format!("{}{}", first.to_uppercase(), chars.as_str().to_lowercase()),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATH: &str = "C:/Program Files/Discord/Discord.exe";
+
+    #[test]
+    fn matches_path_stem() {
+        assert!(application_matches("discord", Some(PATH), None, None));
+        assert!(!application_matches("slack", Some(PATH), None, None));
+    }
+
+    #[test]
+    fn matches_process_name_with_or_without_exe() {
+        assert!(application_matches(
+            "discord",
+            None,
+            Some("Discord.exe"),
+            None
+        ));
+        assert!(application_matches(
+            "discord",
+            None,
+            Some("Discord.EXE"),
+            None
+        ));
+        assert!(application_matches("discord", None, Some("discord"), None));
+        assert!(application_matches(
+            "discord.exe",
+            None,
+            Some("Discord.exe"),
+            None
+        ));
+        assert!(!application_matches(
+            "disc",
+            None,
+            Some("Discord.exe"),
+            None
+        ));
+    }
+
+    #[test]
+    fn matches_display_name() {
+        assert!(application_matches(
+            "spotify premium",
+            None,
+            Some("Spotify.exe"),
+            Some("Spotify Premium")
+        ));
+    }
+
+    #[test]
+    fn full_path_target_matches_only_that_executable() {
+        let target = PATH.to_lowercase();
+        assert!(application_matches(
+            &target,
+            Some(PATH),
+            Some("Discord.exe"),
+            None
+        ));
+        assert!(!application_matches(
+            &target,
+            Some("D:/Discord/Discord.exe"),
+            Some("Discord.exe"),
+            Some("Discord")
+        ));
+    }
+
+    #[test]
+    fn strip_exe_keeps_other_names() {
+        assert_eq!(strip_exe("game.Exe"), "game");
+        assert_eq!(strip_exe("exe"), "exe");
+        assert_eq!(strip_exe("notes.txt"), "notes.txt");
+    }
+}
@@ -1,3 +1,6 @@
+use crate::audio::SessionFilter;
+use crate::logging::LogLevel;
+use crate::model::{BindingAction, BindingTarget, ButtonMode};
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use std::{fs, path::PathBuf};
@@ -8,6 +11,98 @@ pub struct AppSettings {
     pub start_in_tray: bool,
     pub minimize_to_tray: bool,
     pub exit_to_tray: bool,
+    /// Focus bindings only control the foreground app's loudest session
+    /// instead of every session the app owns.
+    #[serde(default)]
+    pub focus_primary_session_only: bool,
+    /// Incoming MIDI is ignored for this long after a device connects.
+    #[serde(default)]
+    pub startup_grace_ms: u64,
+    #[serde(default)]
+    pub midi_heartbeats: Vec<MidiHeartbeat>,
+    #[serde(default)]
+    pub log_level: LogLevel,
+    /// Two presses closer than this trigger a binding's double-tap action.
+    #[serde(default = "default_double_tap_ms")]
+    pub double_tap_ms: u64,
+    /// How long MIDI learn watches an unclassified control before committing it.
+    #[serde(default = "default_learn_window_ms")]
+    pub learn_window_ms: u64,
+    /// System-wide keyboard shortcuts, registered even while MIDIMaster is unfocused.
+    #[serde(default)]
+    pub hotkeys: Vec<Hotkey>,
+    /// Extra plugin catalogs fetched alongside the official store.
+    #[serde(default)]
+    pub store_catalog_urls: Vec<String>,
+    /// Sessions never listed, matched against process and display names.
+    #[serde(default = "default_hidden_sessions")]
+    pub hidden_sessions: Vec<String>,
+    /// Lists every session, bypassing `hidden_sessions` and the system-process heuristics.
+    #[serde(default)]
+    pub show_hidden_sessions: bool,
+}
+
+impl AppSettings {
+    pub fn session_filter(&self) -> SessionFilter {
+        SessionFilter {
+            blocked_names: self.hidden_sessions.clone(),
+            show_hidden: self.show_hidden_sessions,
+        }
+    }
+}
+
+fn default_double_tap_ms() -> u64 {
+    300
+}
+
+fn default_learn_window_ms() -> u64 {
+    150
+}
+
+fn default_hidden_sessions() -> Vec<String> {
+    [
+        "audiosrv",
+        "audiodg",
+        "msedgewebview2",
+        "system sounds",
+        "midimaster",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+/// Keep-alive sent to an output that has been quiet for `interval_ms`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MidiHeartbeat {
+    pub output_device_id: String,
+    pub interval_ms: u64,
+    /// Raw MIDI bytes; empty sends Active Sensing (0xFE).
+    #[serde(default)]
+    pub message: Vec<u8>,
+}
+
+/// A global keyboard shortcut and what it triggers.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Hotkey {
+    /// Accelerator such as `Ctrl+Alt+KeyM` or `F13`.
+    pub shortcut: String,
+    pub action: HotkeyAction,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum HotkeyAction {
+    /// Runs a button action on a target exactly as a MIDI button bound to it would.
+    Binding {
+        target: BindingTarget,
+        action: BindingAction,
+        #[serde(default)]
+        button_mode: ButtonMode,
+    },
+    LoadProfile {
+        name: String,
+    },
 }
 
 impl Default for AppSettings {
@@ -17,6 +112,16 @@ impl Default for AppSettings {
             start_in_tray: false,
             minimize_to_tray: false,
             exit_to_tray: false,
+            focus_primary_session_only: false,
+            startup_grace_ms: 0,
+            midi_heartbeats: Vec::new(),
+            log_level: LogLevel::default(),
+            double_tap_ms: default_double_tap_ms(),
+            learn_window_ms: default_learn_window_ms(),
+            hotkeys: Vec::new(),
+            store_catalog_urls: Vec::new(),
+            hidden_sessions: default_hidden_sessions(),
+            show_hidden_sessions: false,
         }
     }
 }
@@ -0,0 +1,517 @@
+use crate::app_match::application_matches;
+use crate::audio::{
+    stereo_balance_gains, AudioBackend, AudioChanges, SessionFilter, COMMUNICATIONS_DEVICE_ID,
+    DEFAULT_DEVICE_ID,
+};
+use crate::model::{PlaybackDeviceInfo, SessionInfo};
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+
+/// PulseAudio's 100% volume (`PA_VOLUME_NORM`).
+const VOLUME_NORM: f32 = 65536.0;
+
+static AUDIO_CHANGES: AudioChanges = AudioChanges::new();
+static SUBSCRIBED: OnceLock<bool> = OnceLock::new();
+
+/// Talks to PulseAudio (or PipeWire through pipewire-pulse) via `pactl`.
+pub struct LinuxAudioBackend {
+    session_filter: Mutex<SessionFilter>,
+}
+
+impl LinuxAudioBackend {
+    pub fn new() -> Self {
+        Self {
+            session_filter: Mutex::new(SessionFilter::default()),
+        }
+    }
+
+    fn is_hidden(&self, input: &SinkInput) -> bool {
+        let Ok(filter) = self.session_filter.lock() else {
+            return false;
+        };
+        !filter.show_hidden
+            && filter
+                .blocked_names
+                .iter()
+                .any(|name| input_matches_application(input, &name.to_lowercase()))
+    }
+}
+
+#[derive(Deserialize)]
+struct ServerInfo {
+    #[serde(default)]
+    default_sink_name: String,
+    #[serde(default)]
+    default_source_name: String,
+}
+
+#[derive(Deserialize)]
+struct ChannelVolume {
+    value: u32,
+}
+
+#[derive(Deserialize)]
+struct SinkInput {
+    index: u32,
+    #[serde(default)]
+    mute: bool,
+    #[serde(default)]
+    channel_map: String,
+    #[serde(default)]
+    volume: HashMap<String, ChannelVolume>,
+    #[serde(default)]
+    properties: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct Device {
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    mute: bool,
+    #[serde(default)]
+    channel_map: String,
+    #[serde(default)]
+    volume: HashMap<String, ChannelVolume>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Flow {
+    Sink,
+    Source,
+}
+
+impl Flow {
+    fn noun(self) -> &'static str {
+        match self {
+            Flow::Sink => "sink",
+            Flow::Source => "source",
+        }
+    }
+}
+
+/// Per-channel raw volumes in `channel_map` order.
+fn channel_values(channel_map: &str, volume: &HashMap<String, ChannelVolume>) -> Vec<u32> {
+    let values: Vec<u32> = channel_map
+        .split(',')
+        .filter_map(|channel| volume.get(channel.trim()))
+        .map(|channel| channel.value)
+        .collect();
+    if values.is_empty() {
+        volume.values().map(|channel| channel.value).collect()
+    } else {
+        values
+    }
+}
+
+/// Overall level as a 0..1 scalar; like Windows, the loudest channel sets it.
+fn level(values: &[u32]) -> f32 {
+    let max = values.iter().copied().max().unwrap_or(0);
+    (max as f32 / VOLUME_NORM).clamp(0.0, 1.0)
+}
+
+fn raw_volume(volume: f32) -> u32 {
+    (volume.clamp(0.0, 1.0) * VOLUME_NORM).round() as u32
+}
+
+/// Rescales every channel so the loudest lands on `volume`, keeping the balance.
+fn scaled_channels(values: &[u32], volume: f32) -> Vec<String> {
+    let target = raw_volume(volume);
+    let max = values.iter().copied().max().unwrap_or(0);
+    if values.is_empty() || max == 0 {
+        return vec![target.to_string()];
+    }
+    values
+        .iter()
+        .map(|value| ((*value as u64 * target as u64) / max as u64).to_string())
+        .collect()
+}
+
+fn pactl(args: &[&str]) -> Result<String> {
+    let output = Command::new("pactl")
+        .args(args)
+        .output()
+        .context("Failed running pactl")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "pactl {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn pactl_json<T: DeserializeOwned>(args: &[&str]) -> Result<T> {
+    let mut full = vec!["--format=json"];
+    full.extend_from_slice(args);
+    let output = pactl(&full)?;
+    serde_json::from_str(&output).context("Failed parsing pactl output")
+}
+
+fn server_info() -> Result<ServerInfo> {
+    pactl_json(&["info"])
+}
+
+fn sink_inputs() -> Result<Vec<SinkInput>> {
+    pactl_json(&["list", "sink-inputs"])
+}
+
+fn devices(flow: Flow) -> Result<Vec<Device>> {
+    let devices: Vec<Device> = pactl_json(&["list", &format!("{}s", flow.noun())])?;
+    // Monitor sources mirror sinks and are not real inputs.
+    Ok(devices
+        .into_iter()
+        .filter(|device| flow == Flow::Sink || !device.name.ends_with(".monitor"))
+        .collect())
+}
+
+fn parse_device_target(device_id: &str) -> (Flow, &str) {
+    if let Some(raw) = device_id.strip_prefix("recording:") {
+        return (Flow::Source, raw);
+    }
+    if let Some(raw) = device_id.strip_prefix("playback:") {
+        return (Flow::Sink, raw);
+    }
+    (Flow::Sink, device_id)
+}
+
+/// Resolves a device target to its flow and PulseAudio name. An empty id means the
+/// default sink; PulseAudio has no separate communications role, so that id maps
+/// to the flow's default like `DEFAULT_DEVICE_ID` does.
+fn resolve_device(device_id: &str) -> Result<(Flow, Device)> {
+    let (flow, name) = parse_device_target(device_id);
+    let name = match (flow, name) {
+        (Flow::Sink, "" | COMMUNICATIONS_DEVICE_ID | DEFAULT_DEVICE_ID) => {
+            server_info()?.default_sink_name
+        }
+        (Flow::Source, COMMUNICATIONS_DEVICE_ID | DEFAULT_DEVICE_ID) => {
+            server_info()?.default_source_name
+        }
+        _ => name.to_string(),
+    };
+    devices(flow)?
+        .into_iter()
+        .find(|device| device.name == name)
+        .map(|device| (flow, device))
+        .ok_or_else(|| anyhow!("Device not found"))
+}
+
+fn set_device_channels(flow: Flow, name: &str, channels: &[String]) -> Result<()> {
+    let command = format!("set-{}-volume", flow.noun());
+    let mut args = vec![command.as_str(), name];
+    args.extend(channels.iter().map(String::as_str));
+    pactl(&args).map(|_| ())
+}
+
+fn set_device_mute(flow: Flow, name: &str, muted: bool) -> Result<()> {
+    let command = format!("set-{}-mute", flow.noun());
+    pactl(&[&command, name, if muted { "1" } else { "0" }]).map(|_| ())
+}
+
+/// Follows `pactl subscribe` on a background thread, marking `AUDIO_CHANGES` for
+/// sink, source and server events. Client events are ignored because every
+/// `pactl` call made by this backend produces a pair of them.
+fn subscribe_changes() -> Result<()> {
+    let mut child = Command::new("pactl")
+        .arg("subscribe")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to run pactl")?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| anyhow!("pactl subscribe has no output"))?;
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else { break };
+            if [" on sink", " on source", " on server"]
+                .iter()
+                .any(|facility| line.contains(facility))
+            {
+                AUDIO_CHANGES.mark();
+            }
+        }
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+fn property<'a>(input: &'a SinkInput, key: &str) -> Option<&'a str> {
+    input
+        .properties
+        .get(key)
+        .and_then(|value| value.as_str())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn process_path(input: &SinkInput) -> Option<String> {
+    let pid = property(input, "application.process.id")?;
+    std::fs::read_link(format!("/proc/{}/exe", pid))
+        .ok()
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+fn session_info(input: &SinkInput) -> SessionInfo {
+    let process_name = property(input, "application.process.binary").map(str::to_string);
+    let display_name = property(input, "application.name")
+        .or(process_name.as_deref())
+        .or_else(|| property(input, "media.name"))
+        .unwrap_or("Unknown")
+        .to_string();
+    SessionInfo {
+        id: input.index.to_string(),
+        display_name,
+        process_name,
+        process_path: process_path(input),
+        icon_data: None,
+        volume: level(&channel_values(&input.channel_map, &input.volume)),
+        is_muted: input.mute,
+        is_master: false,
+        alias: None,
+    }
+}
+
+/// `target_name` must already be lowercased.
+fn input_matches_application(input: &SinkInput, target_name: &str) -> bool {
+    application_matches(
+        target_name,
+        process_path(input).as_deref(),
+        property(input, "application.process.binary"),
+        property(input, "application.name"),
+    )
+}
+
+fn application_inputs(name: &str) -> Result<Vec<SinkInput>> {
+    let target_name = name.to_lowercase();
+    let inputs: Vec<SinkInput> = sink_inputs()?
+        .into_iter()
+        .filter(|input| input_matches_application(input, &target_name))
+        .collect();
+    if inputs.is_empty() {
+        return Err(anyhow!("Application not found"));
+    }
+    Ok(inputs)
+}
+
+fn set_input_volume(input: &SinkInput, volume: f32) -> Result<()> {
+    let index = input.index.to_string();
+    let channels = scaled_channels(&channel_values(&input.channel_map, &input.volume), volume);
+    let mut args = vec!["set-sink-input-volume", index.as_str()];
+    args.extend(channels.iter().map(String::as_str));
+    pactl(&args).map(|_| ())
+}
+
+fn set_input_mute(input: &SinkInput, muted: bool) -> Result<()> {
+    pactl(&[
+        "set-sink-input-mute",
+        &input.index.to_string(),
+        if muted { "1" } else { "0" },
+    ])
+    .map(|_| ())
+}
+
+fn find_input(session_id: &str) -> Result<SinkInput> {
+    sink_inputs()?
+        .into_iter()
+        .find(|input| input.index.to_string() == session_id)
+        .ok_or_else(|| anyhow!("Session not found"))
+}
+
+fn device_info(device: Device, default_name: &str) -> PlaybackDeviceInfo {
+    let volume = level(&channel_values(&device.channel_map, &device.volume));
+    let display_name = if device.description.is_empty() {
+        device.name.clone()
+    } else {
+        device.description
+    };
+    let is_default = device.name == default_name;
+    PlaybackDeviceInfo {
+        is_default,
+        is_comms_default: is_default,
+        id: device.name,
+        display_name,
+        icon_data: None,
+        volume,
+        is_muted: device.mute,
+        alias: None,
+    }
+}
+
+impl AudioBackend for LinuxAudioBackend {
+    fn list_sessions(&self) -> Result<Vec<SessionInfo>> {
+        let (_, master) = resolve_device("")?;
+        let mut sessions = vec![SessionInfo {
+            id: "master".to_string(),
+            display_name: "Master".to_string(),
+            process_name: None,
+            process_path: None,
+            icon_data: None,
+            volume: level(&channel_values(&master.channel_map, &master.volume)),
+            is_muted: master.mute,
+            is_master: true,
+            alias: None,
+        }];
+        sessions.extend(
+            sink_inputs()?
+                .iter()
+                .filter(|input| !self.is_hidden(input))
+                .map(session_info),
+        );
+        Ok(sessions)
+    }
+
+    fn list_playback_devices(&self) -> Result<Vec<PlaybackDeviceInfo>> {
+        let default_name = server_info()?.default_sink_name;
+        Ok(devices(Flow::Sink)?
+            .into_iter()
+            .map(|device| device_info(device, &default_name))
+            .collect())
+    }
+
+    fn list_recording_devices(&self) -> Result<Vec<PlaybackDeviceInfo>> {
+        let default_name = server_info()?.default_source_name;
+        Ok(devices(Flow::Source)?
+            .into_iter()
+            .map(|device| device_info(device, &default_name))
+            .collect())
+    }
+
+    fn set_master_volume(&self, volume: f32) -> Result<()> {
+        self.set_device_volume("", volume)
+    }
+
+    fn set_session_volume(&self, session_id: &str, volume: f32) -> Result<()> {
+        set_input_volume(&find_input(session_id)?, volume)
+    }
+
+    fn set_device_volume(&self, device_id: &str, volume: f32) -> Result<()> {
+        let (flow, device) = resolve_device(device_id)?;
+        let channels =
+            scaled_channels(&channel_values(&device.channel_map, &device.volume), volume);
+        set_device_channels(flow, &device.name, &channels)
+    }
+
+    fn set_channel_volume(&self, device_id: &str, channel: u32, volume: f32) -> Result<()> {
+        let (flow, device) = resolve_device(device_id)?;
+        let mut values = channel_values(&device.channel_map, &device.volume);
+        let slot = values
+            .get_mut(channel as usize)
+            .ok_or_else(|| anyhow!("Channel {} out of range", channel))?;
+        *slot = raw_volume(volume);
+        let channels: Vec<String> = values.iter().map(u32::to_string).collect();
+        set_device_channels(flow, &device.name, &channels)
+    }
+
+    fn set_balance(&self, device_id: &str, balance: f32) -> Result<()> {
+        let (flow, device) = resolve_device(device_id)?;
+        let values = channel_values(&device.channel_map, &device.volume);
+        if values.len() != 2 {
+            return Err(anyhow!("Device is not stereo"));
+        }
+        let level = level(&values);
+        let (left, right) = stereo_balance_gains(balance);
+        let channels = [
+            raw_volume(level * left).to_string(),
+            raw_volume(level * right).to_string(),
+        ];
+        set_device_channels(flow, &device.name, &channels)
+    }
+
+    fn set_focused_session_volume(&self, _volume: f32, _all_sessions: bool) -> Result<()> {
+        Err(anyhow!("Focused application is not available on Linux"))
+    }
+
+    fn set_application_volume(&self, name: &str, volume: f32) -> Result<()> {
+        for input in application_inputs(name)? {
+            set_input_volume(&input, volume)?;
+        }
+        Ok(())
+    }
+
+    fn focused_session(&self) -> Result<Option<SessionInfo>> {
+        Ok(None)
+    }
+
+    fn foreground_process_path(&self) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn session_peak(&self, _session_id: &str) -> Result<f32> {
+        Err(anyhow!("Peak metering is not available on Linux"))
+    }
+
+    fn master_peak(&self) -> Result<f32> {
+        Err(anyhow!("Peak metering is not available on Linux"))
+    }
+
+    fn set_application_output_device(&self, name: &str, device_id: &str) -> Result<()> {
+        let (_, sink) = resolve_device(device_id)?;
+        for input in application_inputs(name)? {
+            pactl(&["move-sink-input", &input.index.to_string(), &sink.name])?;
+        }
+        Ok(())
+    }
+
+    fn set_default_device(&self, device_id: &str) -> Result<()> {
+        let (flow, device) = resolve_device(device_id)?;
+        let command = format!("set-default-{}", flow.noun());
+        pactl(&[&command, &device.name]).map(|_| ())
+    }
+
+    fn system_sounds_session(&self) -> Result<Option<SessionInfo>> {
+        Ok(None)
+    }
+
+    fn set_system_sounds_volume(&self, _volume: f32) -> Result<()> {
+        Err(anyhow!("System sounds are not available on Linux"))
+    }
+
+    fn set_system_sounds_mute(&self, _muted: bool) -> Result<()> {
+        Err(anyhow!("System sounds are not available on Linux"))
+    }
+
+    fn set_master_mute(&self, muted: bool) -> Result<()> {
+        self.set_device_mute("", muted)
+    }
+
+    fn set_session_mute(&self, session_id: &str, muted: bool) -> Result<()> {
+        set_input_mute(&find_input(session_id)?, muted)
+    }
+
+    fn set_focused_session_mute(&self, _muted: bool, _all_sessions: bool) -> Result<()> {
+        Err(anyhow!("Focused application is not available on Linux"))
+    }
+
+    fn set_application_mute(&self, name: &str, muted: bool) -> Result<()> {
+        for input in application_inputs(name)? {
+            set_input_mute(&input, muted)?;
+        }
+        Ok(())
+    }
+
+    fn set_device_mute(&self, device_id: &str, muted: bool) -> Result<()> {
+        let (flow, device) = resolve_device(device_id)?;
+        set_device_mute(flow, &device.name, muted)
+    }
+
+    fn set_session_filter(&self, filter: SessionFilter) {
+        if let Ok(mut current) = self.session_filter.lock() {
+            *current = filter;
+        }
+    }
+
+    fn changes(&self) -> Option<&'static AudioChanges> {
+        SUBSCRIBED
+            .get_or_init(|| subscribe_changes().is_ok())
+            .then_some(&AUDIO_CHANGES)
+    }
+}
@@ -1,4 +1,6 @@
 use crate::model::SessionInfo;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Notify;
 
 pub trait AudioBackend: Send + Sync {
     fn list_sessions(&self) -> anyhow::Result<Vec<SessionInfo>>;
@@ -7,20 +9,112 @@ pub trait AudioBackend: Send + Sync {
     fn set_master_volume(&self, volume: f32) -> anyhow::Result<()>;
     fn set_session_volume(&self, session_id: &str, volume: f32) -> anyhow::Result<()>;
     fn set_device_volume(&self, device_id: &str, volume: f32) -> anyhow::Result<()>;
-    fn set_focused_session_volume(&self, volume: f32) -> anyhow::Result<()>;
+    /// Sets a single channel's level on a device; an empty `device_id` is the default output.
+    fn set_channel_volume(&self, device_id: &str, channel: u32, volume: f32) -> anyhow::Result<()>;
+    /// Pans a stereo device between -1.0 (left) and 1.0 (right) without changing
+    /// its overall level; an empty `device_id` is the default output.
+    fn set_balance(&self, device_id: &str, balance: f32) -> anyhow::Result<()>;
+    /// When `all_sessions` is false only the foreground app's loudest session is changed.
+    fn set_focused_session_volume(&self, volume: f32, all_sessions: bool) -> anyhow::Result<()>;
     fn set_application_volume(&self, name: &str, volume: f32) -> anyhow::Result<()>;
     fn focused_session(&self) -> anyhow::Result<Option<SessionInfo>>;
+    /// Executable path of the app that owns the foreground window. `None` when
+    /// nothing (or MIDIMaster itself) has focus.
+    fn foreground_process_path(&self) -> anyhow::Result<Option<String>>;
+    /// Current peak level (0..1) of a session's audio.
+    fn session_peak(&self, session_id: &str) -> anyhow::Result<f32>;
+    /// Current peak level (0..1) of the default output.
+    fn master_peak(&self) -> anyhow::Result<f32>;
+    /// Routes an app to a specific output device; an empty `device_id` restores the default.
+    fn set_application_output_device(&self, name: &str, device_id: &str) -> anyhow::Result<()>;
+    /// Makes `device_id` the system default for its flow (all roles).
+    fn set_default_device(&self, device_id: &str) -> anyhow::Result<()>;
+    /// The system notification-sound session, which `list_sessions` leaves out.
+    fn system_sounds_session(&self) -> anyhow::Result<Option<SessionInfo>>;
+    fn set_system_sounds_volume(&self, volume: f32) -> anyhow::Result<()>;
+    fn set_system_sounds_mute(&self, muted: bool) -> anyhow::Result<()>;
 
     // Mute methods
     fn set_master_mute(&self, muted: bool) -> anyhow::Result<()>;
     fn set_session_mute(&self, session_id: &str, muted: bool) -> anyhow::Result<()>;
-    fn set_focused_session_mute(&self, muted: bool) -> anyhow::Result<()>;
+    fn set_focused_session_mute(&self, muted: bool, all_sessions: bool) -> anyhow::Result<()>;
     fn set_application_mute(&self, name: &str, muted: bool) -> anyhow::Result<()>;
     fn set_device_mute(&self, device_id: &str, muted: bool) -> anyhow::Result<()>;
+
+    /// Replaces the rules `list_sessions` and `focused_session` use to hide sessions.
+    /// Backends that list every session ignore it.
+    fn set_session_filter(&self, _filter: SessionFilter) {}
+
+    /// Change signal for backends that push volume/session/device notifications.
+    /// `None` means the caller has to poll.
+    fn changes(&self) -> Option<&'static AudioChanges> {
+        None
+    }
+}
+
+/// Sessions the backend leaves out of its listings.
+#[derive(Clone, Debug, Default)]
+pub struct SessionFilter {
+    /// Process, executable or display names (case-insensitive, `.exe` optional).
+    pub blocked_names: Vec<String>,
+    /// Lists everything, including blocked names, svchost and nameless processes.
+    pub show_hidden: bool,
+}
+
+/// Set by backend notification callbacks whenever volumes, mutes, sessions or the
+/// default device change outside of a read.
+pub struct AudioChanges {
+    dirty: AtomicBool,
+    notify: Notify,
+}
+
+impl AudioChanges {
+    pub const fn new() -> Self {
+        Self {
+            dirty: AtomicBool::new(true),
+            notify: Notify::const_new(),
+        }
+    }
+
+    pub fn mark(&self) {
+        self.dirty.store(true, Ordering::Release);
+        self.notify.notify_one();
+    }
+
+    /// Returns whether anything changed since the last call.
+    pub fn take(&self) -> bool {
+        self.dirty.swap(false, Ordering::AcqRel)
+    }
+
+    pub async fn changed(&self) {
+        self.notify.notified().await;
+    }
+}
+
+impl Default for AudioChanges {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Raw device id (after the `playback:`/`recording:` prefix) that always refers to
+/// the current default Communications endpoint of that flow.
+pub const COMMUNICATIONS_DEVICE_ID: &str = "communications";
+
+/// Raw device id that always refers to the current default endpoint of that flow.
+pub const DEFAULT_DEVICE_ID: &str = "default";
+
+/// Left and right channel gains for a -1.0..1.0 balance; the centred side stays at full level.
+pub fn stereo_balance_gains(balance: f32) -> (f32, f32) {
+    let balance = balance.clamp(-1.0, 1.0);
+    ((1.0 - balance).min(1.0), (1.0 + balance).min(1.0))
 }
 
 #[cfg(target_os = "windows")]
 pub mod windows;
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(target_os = "linux")]
+pub mod linux;
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
 pub mod unsupported;
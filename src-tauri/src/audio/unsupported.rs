@@ -39,7 +39,15 @@ impl AudioBackend for UnsupportedAudioBackend {
         Err(anyhow!("Audio backend not implemented on this OS"))
     }
 
-    fn set_focused_session_volume(&self, _volume: f32) -> Result<()> {
+    fn set_channel_volume(&self, _device_id: &str, _channel: u32, _volume: f32) -> Result<()> {
+        Err(anyhow!("Audio backend not implemented on this OS"))
+    }
+
+    fn set_balance(&self, _device_id: &str, _balance: f32) -> Result<()> {
+        Err(anyhow!("Audio backend not implemented on this OS"))
+    }
+
+    fn set_focused_session_volume(&self, _volume: f32, _all_sessions: bool) -> Result<()> {
         Err(anyhow!("Audio backend not implemented on this OS"))
     }
 
@@ -51,11 +59,43 @@ impl AudioBackend for UnsupportedAudioBackend {
         Ok(None)
     }
 
+    fn foreground_process_path(&self) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn session_peak(&self, _session_id: &str) -> Result<f32> {
+        Err(anyhow!("Audio backend not implemented on this OS"))
+    }
+
+    fn master_peak(&self) -> Result<f32> {
+        Err(anyhow!("Audio backend not implemented on this OS"))
+    }
+
+    fn set_application_output_device(&self, _name: &str, _device_id: &str) -> Result<()> {
+        Err(anyhow!("Audio backend not implemented on this OS"))
+    }
+
+    fn set_default_device(&self, _device_id: &str) -> Result<()> {
+        Err(anyhow!("Audio backend not implemented on this OS"))
+    }
+
+    fn system_sounds_session(&self) -> Result<Option<SessionInfo>> {
+        Ok(None)
+    }
+
+    fn set_system_sounds_volume(&self, _volume: f32) -> Result<()> {
+        Err(anyhow!("Audio backend not implemented on this OS"))
+    }
+
+    fn set_system_sounds_mute(&self, _muted: bool) -> Result<()> {
+        Err(anyhow!("Audio backend not implemented on this OS"))
+    }
+
     fn set_master_mute(&self, _muted: bool) -> Result<()> {
         Err(anyhow!("Audio backend not implemented on this OS"))
     }
 
-    fn set_focused_session_mute(&self, _muted: bool) -> Result<()> {
+    fn set_focused_session_mute(&self, _muted: bool, _all_sessions: bool) -> Result<()> {
         Err(anyhow!("Audio backend not implemented on this OS"))
     }
 
@@ -1,4 +1,7 @@
-use crate::audio::AudioBackend;
+use crate::app_match::{application_matches, friendly_process_label, humanize_label};
+use crate::audio::{
+    AudioBackend, AudioChanges, SessionFilter, COMMUNICATIONS_DEVICE_ID, DEFAULT_DEVICE_ID,
+};
 use crate::model::{PlaybackDeviceInfo, SessionInfo};
 use anyhow::{anyhow, Result};
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
@@ -9,28 +12,42 @@ use std::collections::{HashMap, HashSet};
 use std::ffi::{OsStr, OsString};
 use std::mem::size_of;
 use std::os::windows::ffi::{OsStrExt, OsStringExt};
-use std::path::Path;
-use windows::core::{Interface, PCWSTR, PWSTR};
-use windows::Win32::Foundation::{CloseHandle, PROPERTYKEY, RPC_E_CHANGED_MODE};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use windows::core::{implement, IInspectable, Interface, Ref, BOOL, GUID, HSTRING, PCWSTR, PWSTR};
+use windows::Win32::Foundation::{CloseHandle, FILETIME, PROPERTYKEY, RPC_E_CHANGED_MODE, S_OK};
 use windows::Win32::Graphics::Gdi::{
     DeleteObject, GetDC, GetDIBits, GetObjectW, ReleaseDC, BITMAP, BITMAPINFO, BITMAPINFOHEADER,
     BI_RGB, DIB_RGB_COLORS,
 };
-use windows::Win32::Media::Audio::Endpoints::IAudioEndpointVolume;
+use windows::Win32::Media::Audio::Endpoints::{
+    IAudioEndpointVolume, IAudioEndpointVolumeCallback, IAudioEndpointVolumeCallback_Impl,
+    IAudioMeterInformation,
+};
 use windows::Win32::Media::Audio::{
-    eCapture, eMultimedia, eRender, EDataFlow, IAudioSessionControl2, IAudioSessionManager2,
-    IMMDevice, IMMDeviceEnumerator, ISimpleAudioVolume, MMDeviceEnumerator, DEVICE_STATE_ACTIVE,
+    eCapture, eCommunications, eConsole, eMultimedia, eRender, AudioSessionDisconnectReason,
+    AudioSessionState, EDataFlow, ERole, IAudioSessionControl, IAudioSessionControl2,
+    IAudioSessionEvents, IAudioSessionEvents_Impl, IAudioSessionManager2,
+    IAudioSessionNotification, IAudioSessionNotification_Impl, IMMDevice, IMMDeviceEnumerator,
+    IMMNotificationClient, IMMNotificationClient_Impl, ISimpleAudioVolume, MMDeviceEnumerator,
+    AUDIO_VOLUME_NOTIFICATION_DATA, DEVICE_STATE, DEVICE_STATE_ACTIVE,
 };
 use windows::Win32::System::Com::StructuredStorage::{
     PropVariantClear, PropVariantToStringAlloc, PROPVARIANT,
 };
 use windows::Win32::System::Com::{
-    CoCreateInstance, CoInitializeEx, CoTaskMemFree, CoUninitialize, CLSCTX_ALL,
-    COINIT_MULTITHREADED, STGM_READ,
+    CoCreateInstance, CoIncrementMTAUsage, CoInitializeEx, CoTaskMemFree, CoUninitialize,
+    CLSCTX_ALL, COINIT_MULTITHREADED, STGM_READ,
+};
+use windows::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
 };
 use windows::Win32::System::Threading::{
-    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+    GetProcessTimes, OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+    PROCESS_QUERY_LIMITED_INFORMATION,
 };
+use windows::Win32::System::WinRT::RoGetActivationFactory;
 use windows::Win32::UI::Shell::ExtractIconExW;
 use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
 use windows::Win32::UI::WindowsAndMessaging::{
@@ -47,11 +64,28 @@ const PKEY_DEVICE_CLASS_ICON_PATH: PROPERTYKEY = PROPERTYKEY {
     pid: 12,
 };
 
-pub struct WindowsAudioBackend;
+pub struct WindowsAudioBackend {
+    icon_cache_dir: Option<PathBuf>,
+    session_filter: Mutex<SessionFilter>,
+}
 
 impl WindowsAudioBackend {
-    pub fn new() -> Self {
-        Self
+    pub fn new(icon_cache_dir: Option<PathBuf>) -> Self {
+        Self {
+            icon_cache_dir,
+            session_filter: Mutex::new(SessionFilter::default()),
+        }
+    }
+
+    fn icon_cache(&self) -> IconCache {
+        IconCache::new(self.icon_cache_dir.clone())
+    }
+
+    fn session_filter(&self) -> SessionFilter {
+        self.session_filter
+            .lock()
+            .map(|filter| filter.clone())
+            .unwrap_or_default()
     }
 }
 
@@ -74,21 +108,28 @@ impl AudioBackend for WindowsAudioBackend {
             volume: master_volume,
             is_muted: master_muted,
             is_master: true,
+            alias: None,
         }];
 
         let mut seen_ids = HashSet::new();
-        let mut icon_cache = HashMap::new();
+        let mut icon_cache = self.icon_cache();
+        let filter = self.session_filter();
         for (device, device_id) in enumerate_active_devices(&enumerator, eRender)? {
             let default_id = default_device_id.as_deref();
             let _ = collect_device_sessions(
                 &device,
                 &device_id,
                 default_id,
+                &filter,
                 &mut sessions,
                 &mut seen_ids,
                 &mut icon_cache,
             );
         }
+        let _ = with_com_cache(|cache| {
+            cache.retain_session_events(&seen_ids);
+            Ok(())
+        });
 
         Ok(sessions)
     }
@@ -98,7 +139,14 @@ impl AudioBackend for WindowsAudioBackend {
         let enumerator = get_device_enumerator()?;
         let default_device = get_default_device_from_flow(&enumerator, eRender)?;
         let default_id = device_id_string(&default_device);
-        list_devices_for_flow(&enumerator, eRender, default_id)
+        let comms_id = default_device_id_for_role(&enumerator, eRender, eCommunications);
+        list_devices_for_flow(
+            &enumerator,
+            eRender,
+            default_id,
+            comms_id,
+            &mut self.icon_cache(),
+        )
     }
 
     fn list_recording_devices(&self) -> Result<Vec<PlaybackDeviceInfo>> {
@@ -106,130 +154,189 @@ impl AudioBackend for WindowsAudioBackend {
         let enumerator = get_device_enumerator()?;
         let default_device = get_default_device_from_flow(&enumerator, eCapture)?;
         let default_id = device_id_string(&default_device);
-        list_devices_for_flow(&enumerator, eCapture, default_id)
+        let comms_id = default_device_id_for_role(&enumerator, eCapture, eCommunications);
+        list_devices_for_flow(
+            &enumerator,
+            eCapture,
+            default_id,
+            comms_id,
+            &mut self.icon_cache(),
+        )
     }
 
     fn set_master_volume(&self, volume: f32) -> Result<()> {
-        let _com = init_com()?;
-        let device = get_default_device()?;
-        let endpoint = get_endpoint_volume(&device)?;
-        let clamped = volume.clamp(0.0, 1.0);
-        unsafe { endpoint.SetMasterVolumeLevelScalar(clamped, std::ptr::null()) }?;
-        Ok(())
+        retry_transient(|| {
+            let _com = init_com()?;
+            let device = get_default_device()?;
+            let endpoint = get_endpoint_volume(&device)?;
+            let clamped = volume.clamp(0.0, 1.0);
+            unsafe { endpoint.SetMasterVolumeLevelScalar(clamped, std::ptr::null()) }?;
+            Ok(())
+        })
     }
 
     fn set_session_volume(&self, session_id: &str, volume: f32) -> Result<()> {
-        let _com = init_com()?;
-        let enumerator = get_device_enumerator()?;
-        let target_volume = volume.clamp(0.0, 1.0);
-        let (device_hint, target_id) = split_session_id(session_id);
-        let devices = enumerate_active_devices(&enumerator, eRender)?;
-
-        if let Some(device_id) = device_hint {
-            if let Some((device, _)) = devices.iter().find(|(_, id)| id == device_id) {
-                if set_session_volume_on_device(device, target_id, target_volume)? {
-                    return Ok(());
+        retry_transient(|| {
+            let _com = init_com()?;
+            let enumerator = get_device_enumerator()?;
+            let target_volume = volume.clamp(0.0, 1.0);
+            let (device_hint, target_id) = split_session_id(session_id);
+            let devices = enumerate_active_devices(&enumerator, eRender)?;
+
+            if let Some(device_id) = device_hint {
+                if let Some((device, _)) = devices.iter().find(|(_, id)| id == device_id) {
+                    if set_session_volume_on_device(device, target_id, target_volume)? {
+                        return Ok(());
+                    }
                 }
+                return Err(anyhow!("Session not found"));
             }
-            return Err(anyhow!("Session not found"));
-        }
 
-        let default_device = get_default_device_from(&enumerator)?;
-        if set_session_volume_on_device(&default_device, target_id, target_volume)? {
-            return Ok(());
-        }
-
-        for (device, _device_id) in devices {
-            if set_session_volume_on_device(&device, target_id, target_volume)? {
+            let default_device = get_default_device_from(&enumerator)?;
+            if set_session_volume_on_device(&default_device, target_id, target_volume)? {
                 return Ok(());
             }
-        }
 
-        Err(anyhow!("Session not found"))
+            for (device, _device_id) in devices {
+                if set_session_volume_on_device(&device, target_id, target_volume)? {
+                    return Ok(());
+                }
+            }
+
+            Err(anyhow!("Session not found"))
+        })
     }
 
     fn set_device_volume(&self, device_id: &str, volume: f32) -> Result<()> {
-        let _com = init_com()?;
-        let enumerator = get_device_enumerator()?;
-        let target_volume = volume.clamp(0.0, 1.0);
-        let (flow, raw_id) = parse_device_target(device_id);
+        retry_transient(|| {
+            let _com = init_com()?;
+            let enumerator = get_device_enumerator()?;
+            let target_volume = volume.clamp(0.0, 1.0);
+            let (flow, raw_id) = resolve_device_target(&enumerator, device_id)?;
+
+            for (device, id) in enumerate_active_devices(&enumerator, flow)? {
+                if id == raw_id {
+                    let endpoint = get_endpoint_volume(&device)?;
+                    unsafe {
+                        endpoint.SetMasterVolumeLevelScalar(target_volume, std::ptr::null())
+                    }?;
+                    return Ok(());
+                }
+            }
 
-        for (device, id) in enumerate_active_devices(&enumerator, flow)? {
-            if id == raw_id {
-                let endpoint = get_endpoint_volume(&device)?;
-                unsafe { endpoint.SetMasterVolumeLevelScalar(target_volume, std::ptr::null()) }?;
-                return Ok(());
+            Err(anyhow!("Device not found"))
+        })
+    }
+
+    fn set_channel_volume(&self, device_id: &str, channel: u32, volume: f32) -> Result<()> {
+        retry_transient(|| {
+            let _com = init_com()?;
+            let endpoint = endpoint_volume_for_target(device_id)?;
+            let channels = unsafe { endpoint.GetChannelCount() }?;
+            if channel >= channels {
+                return Err(anyhow!("Channel {} out of range", channel));
             }
-        }
+            unsafe {
+                endpoint.SetChannelVolumeLevelScalar(
+                    channel,
+                    volume.clamp(0.0, 1.0),
+                    std::ptr::null(),
+                )
+            }?;
+            Ok(())
+        })
+    }
 
-        Err(anyhow!("Device not found"))
+    fn set_balance(&self, device_id: &str, balance: f32) -> Result<()> {
+        retry_transient(|| {
+            let _com = init_com()?;
+            let endpoint = endpoint_volume_for_target(device_id)?;
+            let channels = unsafe { endpoint.GetChannelCount() }?;
+            if channels < 2 {
+                return Err(anyhow!("Device is not stereo"));
+            }
+            // The master scalar tracks the loudest channel, so scaling from it keeps
+            // the overall level where it was.
+            let level = unsafe { endpoint.GetMasterVolumeLevelScalar() }?;
+            let (left, right) = crate::audio::stereo_balance_gains(balance);
+            unsafe {
+                endpoint.SetChannelVolumeLevelScalar(0, level * left, std::ptr::null())?;
+                endpoint.SetChannelVolumeLevelScalar(1, level * right, std::ptr::null())?;
+            }
+            Ok(())
+        })
     }
 
-    fn set_focused_session_volume(&self, volume: f32) -> Result<()> {
-        let _com = init_com()?;
-        let process_id =
-            foreground_process_id().ok_or_else(|| anyhow!("No focused application"))?;
-        let process_path = query_process_path(process_id);
-        let enumerator = get_device_enumerator()?;
-        let target_volume = volume.clamp(0.0, 1.0);
-        let mut updated = false;
+    fn set_focused_session_volume(&self, volume: f32, all_sessions: bool) -> Result<()> {
+        retry_transient(|| {
+            let _com = init_com()?;
+            let enumerator = get_device_enumerator()?;
+            let focused = FocusedProcess::foreground(&enumerator)?
+                .ok_or_else(|| anyhow!("No focused application"))?;
+            let target_volume = volume.clamp(0.0, 1.0);
 
-        for (device, _id) in enumerate_active_devices(&enumerator, eRender)? {
-            if set_session_volume_for_process(
-                &device,
-                process_id,
-                process_path.as_deref(),
-                target_volume,
-            )? {
-                updated = true;
+            if !all_sessions {
+                let simple = loudest_session_for_process(&enumerator, &focused)?
+                    .ok_or_else(|| anyhow!("Focused session not found"))?;
+                unsafe { simple.SetMasterVolume(target_volume, std::ptr::null()) }?;
+                return Ok(());
             }
-        }
 
-        if updated {
-            Ok(())
-        } else {
-            Err(anyhow!("Focused session not found"))
-        }
+            let mut updated = false;
+
+            for (device, _id) in enumerate_active_devices(&enumerator, eRender)? {
+                if set_session_volume_for_process(&device, &focused, target_volume)? {
+                    updated = true;
+                }
+            }
+
+            if updated {
+                Ok(())
+            } else {
+                Err(anyhow!("Focused session not found"))
+            }
+        })
     }
 
     fn set_application_volume(&self, name: &str, volume: f32) -> Result<()> {
-        let _com = init_com()?;
-        let enumerator = get_device_enumerator()?;
-        let target_volume = volume.clamp(0.0, 1.0);
-        let mut updated = false;
-
-        for (device, _id) in enumerate_active_devices(&enumerator, eRender)? {
-            if set_session_volume_by_name(&device, name, target_volume)? {
-                updated = true;
+        retry_transient(|| {
+            let _com = init_com()?;
+            let enumerator = get_device_enumerator()?;
+            let target_volume = volume.clamp(0.0, 1.0);
+            let mut updated = false;
+
+            for (device, _id) in enumerate_active_devices(&enumerator, eRender)? {
+                if set_session_volume_by_name(&device, name, target_volume)? {
+                    updated = true;
+                }
             }
-        }
 
-        if updated {
-            Ok(())
-        } else {
-            Err(anyhow!("Application not found"))
-        }
+            if updated {
+                Ok(())
+            } else {
+                Err(anyhow!("Application not found"))
+            }
+        })
     }
 
     fn focused_session(&self) -> Result<Option<SessionInfo>> {
         let _com = init_com()?;
-        let process_id = match foreground_process_id() {
-            Some(process_id) => process_id,
-            None => return Ok(None),
-        };
-        let process_path = query_process_path(process_id);
         let enumerator = get_device_enumerator()?;
+        let Some(focused) = FocusedProcess::foreground(&enumerator)? else {
+            return Ok(None);
+        };
         let default_device = get_default_device_from(&enumerator)?;
         let default_device_id = device_id_string(&default_device);
-        let mut icon_cache = HashMap::new();
+        let mut icon_cache = self.icon_cache();
+        let filter = self.session_filter();
 
         for (device, device_id) in enumerate_active_devices(&enumerator, eRender)? {
             if let Some(session) = session_info_for_process(
                 &device,
                 &device_id,
                 default_device_id.as_deref(),
-                process_id,
-                process_path.as_deref(),
+                &filter,
+                &focused,
                 &mut icon_cache,
             )? {
                 return Ok(Some(session));
@@ -239,102 +346,543 @@ impl AudioBackend for WindowsAudioBackend {
         Ok(None)
     }
 
-    fn set_master_mute(&self, muted: bool) -> Result<()> {
-        let _com = init_com()?;
-        let device = get_default_device()?;
-        let endpoint = get_endpoint_volume(&device)?;
-        unsafe { endpoint.SetMute(muted, std::ptr::null()) }?;
-        Ok(())
+    fn foreground_process_path(&self) -> Result<Option<String>> {
+        Ok(foreground_process_id()
+            .filter(|process_id| *process_id != std::process::id())
+            .and_then(query_process_path))
     }
 
-    fn set_focused_session_mute(&self, muted: bool) -> Result<()> {
+    fn session_peak(&self, session_id: &str) -> Result<f32> {
         let _com = init_com()?;
-        let process_id =
-            foreground_process_id().ok_or_else(|| anyhow!("No focused application"))?;
-        let process_path = query_process_path(process_id);
         let enumerator = get_device_enumerator()?;
-        let mut updated = false;
+        let (device_hint, target_id) = split_session_id(session_id);
 
-        for (device, _id) in enumerate_active_devices(&enumerator, eRender)? {
-            if set_session_mute_for_process(&device, process_id, process_path.as_deref(), muted)? {
-                updated = true;
+        for (device, device_id) in enumerate_active_devices(&enumerator, eRender)? {
+            if device_hint.is_some_and(|hint| hint != device_id) {
+                continue;
+            }
+            if let Some(peak) = session_peak_on_device(&device, target_id)? {
+                return Ok(peak);
             }
         }
 
-        if updated {
-            Ok(())
-        } else {
-            Err(anyhow!("Focused session not found"))
-        }
+        Err(anyhow!("Session not found"))
     }
 
-    fn set_application_mute(&self, name: &str, muted: bool) -> Result<()> {
+    fn master_peak(&self) -> Result<f32> {
+        let _com = init_com()?;
+        let device = get_default_device()?;
+        let meter: IAudioMeterInformation = unsafe { device.Activate(CLSCTX_ALL, None) }?;
+        Ok(unsafe { meter.GetPeakValue() }?)
+    }
+
+    fn set_application_output_device(&self, name: &str, device_id: &str) -> Result<()> {
         let _com = init_com()?;
         let enumerator = get_device_enumerator()?;
-        let mut updated = false;
+        let (flow, raw_id) = parse_device_target(device_id);
+        let factory = audio_policy_config_factory()?;
 
+        let mut process_ids = HashSet::new();
         for (device, _id) in enumerate_active_devices(&enumerator, eRender)? {
-            if set_session_mute_by_name(&device, name, muted)? {
-                updated = true;
-            }
+            process_ids.extend(application_process_ids(&device, name)?);
+        }
+        if process_ids.is_empty() {
+            return Err(anyhow!("Application not found"));
         }
 
-        if updated {
-            Ok(())
+        // An empty id clears the per-app override so the app follows the system default
+        let endpoint = if raw_id.is_empty() {
+            HSTRING::new()
         } else {
-            Err(anyhow!("Application not found"))
+            HSTRING::from(device_interface_path(raw_id, flow))
+        };
+        for process_id in process_ids {
+            for role in [eConsole, eMultimedia] {
+                unsafe {
+                    factory.SetPersistedDefaultAudioEndpoint(
+                        process_id,
+                        flow,
+                        role,
+                        std::mem::transmute_copy(&endpoint),
+                    )
+                }
+                .ok()?;
+            }
         }
+        Ok(())
     }
 
-    fn set_device_mute(&self, device_id: &str, muted: bool) -> Result<()> {
+    fn set_default_device(&self, device_id: &str) -> Result<()> {
+        retry_transient(|| {
+            let _com = init_com()?;
+            let enumerator = get_device_enumerator()?;
+            let (flow, raw_id) = parse_device_target(device_id);
+            if !enumerate_active_devices(&enumerator, flow)?
+                .iter()
+                .any(|(_, id)| id == raw_id)
+            {
+                return Err(anyhow!("Device not found"));
+            }
+
+            let policy: IPolicyConfig =
+                unsafe { CoCreateInstance(&CLSID_POLICY_CONFIG_CLIENT, None, CLSCTX_ALL) }
+                    .map_err(|err| {
+                        anyhow!("Changing the default device is not available: {}", err)
+                    })?;
+            let wide = to_wide_string(raw_id);
+            for role in [eConsole, eMultimedia, eCommunications] {
+                unsafe { policy.SetDefaultEndpoint(PCWSTR(wide.as_ptr()), role) }.ok()?;
+            }
+            Ok(())
+        })
+    }
+
+    fn system_sounds_session(&self) -> Result<Option<SessionInfo>> {
         let _com = init_com()?;
-        let enumerator = get_device_enumerator()?;
-        let (flow, raw_id) = parse_device_target(device_id);
+        let Some((control2, simple)) = find_system_sounds_session(&get_default_device()?)? else {
+            return Ok(None);
+        };
+        Ok(Some(SessionInfo {
+            id: session_identifier(&control2, 0).unwrap_or_else(|| "pid:0".to_string()),
+            display_name: "System Sounds".to_string(),
+            process_name: None,
+            process_path: None,
+            icon_data: None,
+            volume: unsafe { simple.GetMasterVolume() }?,
+            is_muted: unsafe { simple.GetMute() }?.as_bool(),
+            is_master: false,
+            alias: None,
+        }))
+    }
+
+    fn set_system_sounds_volume(&self, volume: f32) -> Result<()> {
+        retry_transient(|| {
+            let _com = init_com()?;
+            let (_, simple) = find_system_sounds_session(&get_default_device()?)?
+                .ok_or_else(|| anyhow!("System sounds session not found"))?;
+            unsafe { simple.SetMasterVolume(volume.clamp(0.0, 1.0), std::ptr::null()) }?;
+            Ok(())
+        })
+    }
 
-        for (device, id) in enumerate_active_devices(&enumerator, flow)? {
-            if id == raw_id {
-                let endpoint = get_endpoint_volume(&device)?;
-                unsafe { endpoint.SetMute(muted, std::ptr::null()) }?;
+    fn set_system_sounds_mute(&self, muted: bool) -> Result<()> {
+        retry_transient(|| {
+            let _com = init_com()?;
+            let (_, simple) = find_system_sounds_session(&get_default_device()?)?
+                .ok_or_else(|| anyhow!("System sounds session not found"))?;
+            unsafe { simple.SetMute(muted, std::ptr::null()) }?;
+            Ok(())
+        })
+    }
+
+    fn set_master_mute(&self, muted: bool) -> Result<()> {
+        retry_transient(|| {
+            let _com = init_com()?;
+            let device = get_default_device()?;
+            let endpoint = get_endpoint_volume(&device)?;
+            unsafe { endpoint.SetMute(muted, std::ptr::null()) }?;
+            Ok(())
+        })
+    }
+
+    fn set_focused_session_mute(&self, muted: bool, all_sessions: bool) -> Result<()> {
+        retry_transient(|| {
+            let _com = init_com()?;
+            let enumerator = get_device_enumerator()?;
+            let focused = FocusedProcess::foreground(&enumerator)?
+                .ok_or_else(|| anyhow!("No focused application"))?;
+
+            if !all_sessions {
+                let simple = loudest_session_for_process(&enumerator, &focused)?
+                    .ok_or_else(|| anyhow!("Focused session not found"))?;
+                unsafe { simple.SetMute(muted, std::ptr::null()) }?;
                 return Ok(());
             }
-        }
 
-        Err(anyhow!("Device not found"))
+            let mut updated = false;
+
+            for (device, _id) in enumerate_active_devices(&enumerator, eRender)? {
+                if set_session_mute_for_process(&device, &focused, muted)? {
+                    updated = true;
+                }
+            }
+
+            if updated {
+                Ok(())
+            } else {
+                Err(anyhow!("Focused session not found"))
+            }
+        })
+    }
+
+    fn set_application_mute(&self, name: &str, muted: bool) -> Result<()> {
+        retry_transient(|| {
+            let _com = init_com()?;
+            let enumerator = get_device_enumerator()?;
+            let mut updated = false;
+
+            for (device, _id) in enumerate_active_devices(&enumerator, eRender)? {
+                if set_session_mute_by_name(&device, name, muted)? {
+                    updated = true;
+                }
+            }
+
+            if updated {
+                Ok(())
+            } else {
+                Err(anyhow!("Application not found"))
+            }
+        })
+    }
+
+    fn set_device_mute(&self, device_id: &str, muted: bool) -> Result<()> {
+        retry_transient(|| {
+            let _com = init_com()?;
+            let enumerator = get_device_enumerator()?;
+            let (flow, raw_id) = resolve_device_target(&enumerator, device_id)?;
+
+            for (device, id) in enumerate_active_devices(&enumerator, flow)? {
+                if id == raw_id {
+                    let endpoint = get_endpoint_volume(&device)?;
+                    unsafe { endpoint.SetMute(muted, std::ptr::null()) }?;
+                    return Ok(());
+                }
+            }
+
+            Err(anyhow!("Device not found"))
+        })
     }
 
     fn set_session_mute(&self, session_id: &str, muted: bool) -> Result<()> {
-        let _com = init_com()?;
-        let enumerator = get_device_enumerator()?;
-        let (device_hint, target_id) = split_session_id(session_id);
-        let devices = enumerate_active_devices(&enumerator, eRender)?;
+        retry_transient(|| {
+            let _com = init_com()?;
+            let enumerator = get_device_enumerator()?;
+            let (device_hint, target_id) = split_session_id(session_id);
+            let devices = enumerate_active_devices(&enumerator, eRender)?;
+
+            if let Some(device_id) = device_hint {
+                if let Some((device, _)) = devices.iter().find(|(_, id)| id == device_id) {
+                    if set_session_mute_on_device(device, target_id, muted)? {
+                        return Ok(());
+                    }
+                }
+                return Err(anyhow!("Session not found"));
+            }
 
-        if let Some(device_id) = device_hint {
-            if let Some((device, _)) = devices.iter().find(|(_, id)| id == device_id) {
-                if set_session_mute_on_device(device, target_id, muted)? {
+            let default_device = get_default_device_from(&enumerator)?;
+            if set_session_mute_on_device(&default_device, target_id, muted)? {
+                return Ok(());
+            }
+
+            for (device, _device_id) in devices {
+                if set_session_mute_on_device(&device, target_id, muted)? {
                     return Ok(());
                 }
             }
-            return Err(anyhow!("Session not found"));
+
+            Err(anyhow!("Session not found"))
+        })
+    }
+
+    fn set_session_filter(&self, filter: SessionFilter) {
+        if let Ok(mut current) = self.session_filter.lock() {
+            *current = filter;
         }
+    }
 
-        let default_device = get_default_device_from(&enumerator)?;
-        if set_session_mute_on_device(&default_device, target_id, muted)? {
-            return Ok(());
+    fn changes(&self) -> Option<&'static AudioChanges> {
+        Some(&AUDIO_CHANGES)
+    }
+}
+
+// AUDCLNT_E_DEVICE_INVALIDATED, AUDCLNT_E_DEVICE_IN_USE, AUDCLNT_E_SERVICE_NOT_RUNNING,
+// RPC_E_CALL_REJECTED, RPC_E_SERVERCALL_RETRYLATER
+const TRANSIENT_HRESULTS: [u32; 5] = [
+    0x8889_0004,
+    0x8889_000A,
+    0x8889_0010,
+    0x8001_0001,
+    0x8001_010A,
+];
+const TRANSIENT_RETRY_ATTEMPTS: u32 = 3;
+const TRANSIENT_RETRY_BACKOFF_MS: u64 = 15;
+
+fn is_transient_error(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<windows::core::Error>()
+        .map(|err| TRANSIENT_HRESULTS.contains(&(err.code().0 as u32)))
+        .unwrap_or(false)
+}
+
+/// Retries an operation that failed with a transient audio/COM error, e.g. while
+/// the endpoint is being swapped during a device transition.
+fn retry_transient<T>(mut op: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Err(err) if attempt < TRANSIENT_RETRY_ATTEMPTS && is_transient_error(&err) => {
+                // The endpoint may have been swapped out from under a cached handle.
+                invalidate_com_cache();
+                std::thread::sleep(std::time::Duration::from_millis(
+                    TRANSIENT_RETRY_BACKOFF_MS * attempt as u64,
+                ));
+                attempt += 1;
+            }
+            result => return result,
         }
+    }
+}
 
-        for (device, _device_id) in devices {
-            if set_session_mute_on_device(&device, target_id, muted)? {
-                return Ok(());
+/// COM handles reused across calls so the feedback poll loop doesn't recreate the
+/// enumerator and re-enumerate every endpoint each tick. Core Audio objects are
+/// free-threaded, and the MTA is kept alive for the life of the process (see
+/// `ComCache::new`), so the cached handles stay valid on any thread.
+struct ComCache {
+    enumerator: IMMDeviceEnumerator,
+    _notifier: IMMNotificationClient,
+    devices: HashMap<i32, Vec<(IMMDevice, String)>>,
+    endpoint_volumes: HashMap<String, (IAudioEndpointVolume, IAudioEndpointVolumeCallback)>,
+    session_managers: HashMap<String, (IAudioSessionManager2, IAudioSessionNotification)>,
+    session_events: HashMap<String, (IAudioSessionControl, IAudioSessionEvents)>,
+}
+
+// SAFETY: every cached interface is a free-threaded Core Audio object living in the MTA.
+unsafe impl Send for ComCache {}
+
+static COM_CACHE: Mutex<Option<ComCache>> = Mutex::new(None);
+
+/// Set from the device notification callback; the cache drops its endpoint handles
+/// on the next call instead of inside the callback.
+static DEVICES_DIRTY: AtomicBool = AtomicBool::new(false);
+
+/// Marked from every notification callback below. Callbacks never take `COM_CACHE`:
+/// Core Audio may deliver them while a cache holder is blocked inside a COM call.
+static AUDIO_CHANGES: AudioChanges = AudioChanges::new();
+
+impl ComCache {
+    fn new() -> Result<Self> {
+        // Without this the MTA is torn down whenever the last calling thread
+        // uninitializes COM, taking the cached handles with it.
+        unsafe { CoIncrementMTAUsage() }?;
+        let enumerator: IMMDeviceEnumerator =
+            unsafe { CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL) }?;
+        let notifier: IMMNotificationClient = DeviceChangeNotifier.into();
+        unsafe { enumerator.RegisterEndpointNotificationCallback(&notifier) }?;
+        Ok(Self {
+            enumerator,
+            _notifier: notifier,
+            devices: HashMap::new(),
+            endpoint_volumes: HashMap::new(),
+            session_managers: HashMap::new(),
+            session_events: HashMap::new(),
+        })
+    }
+
+    fn invalidate(&mut self) {
+        self.devices.clear();
+        for (endpoint, callback) in self.endpoint_volumes.values() {
+            let _ = unsafe { endpoint.UnregisterControlChangeNotify(callback) };
+        }
+        self.endpoint_volumes.clear();
+        for (manager, notifier) in self.session_managers.values() {
+            let _ = unsafe { manager.UnregisterSessionNotification(notifier) };
+        }
+        self.session_managers.clear();
+        self.retain_session_events(&HashSet::new());
+    }
+
+    /// Drops the event subscriptions of sessions that have gone away.
+    fn retain_session_events(&mut self, live_ids: &HashSet<String>) {
+        self.session_events.retain(|session_id, (control, events)| {
+            let keep = live_ids.contains(session_id);
+            if !keep {
+                let _ = unsafe { control.UnregisterAudioSessionNotification(&*events) };
             }
+            keep
+        });
+    }
+}
+
+fn with_com_cache<T>(op: impl FnOnce(&mut ComCache) -> Result<T>) -> Result<T> {
+    let mut guard = COM_CACHE.lock().map_err(|_| anyhow!("Lock poisoned"))?;
+    if DEVICES_DIRTY.swap(false, Ordering::AcqRel) {
+        if let Some(cache) = guard.as_mut() {
+            cache.invalidate();
         }
+    }
+    let cache = match guard.as_mut() {
+        Some(cache) => cache,
+        None => guard.insert(ComCache::new()?),
+    };
+    op(cache)
+}
 
-        Err(anyhow!("Session not found"))
+fn invalidate_com_cache() {
+    DEVICES_DIRTY.store(true, Ordering::Release);
+    AUDIO_CHANGES.mark();
+}
+
+/// Subscribes to volume/mute/state events of a session the first time it is listed.
+fn watch_session(control: &IAudioSessionControl, session_id: &str) {
+    let _ = with_com_cache(|cache| {
+        if cache.session_events.contains_key(session_id) {
+            return Ok(());
+        }
+        let events: IAudioSessionEvents = SessionEventsNotifier.into();
+        unsafe { control.RegisterAudioSessionNotification(&events) }?;
+        cache
+            .session_events
+            .insert(session_id.to_string(), (control.clone(), events));
+        Ok(())
+    });
+}
+
+#[implement(IMMNotificationClient)]
+struct DeviceChangeNotifier;
+
+impl IMMNotificationClient_Impl for DeviceChangeNotifier_Impl {
+    fn OnDeviceStateChanged(
+        &self,
+        _device_id: &PCWSTR,
+        _state: DEVICE_STATE,
+    ) -> windows::core::Result<()> {
+        invalidate_com_cache();
+        Ok(())
+    }
+
+    fn OnDeviceAdded(&self, _device_id: &PCWSTR) -> windows::core::Result<()> {
+        invalidate_com_cache();
+        Ok(())
+    }
+
+    fn OnDeviceRemoved(&self, _device_id: &PCWSTR) -> windows::core::Result<()> {
+        invalidate_com_cache();
+        Ok(())
+    }
+
+    fn OnDefaultDeviceChanged(
+        &self,
+        _flow: EDataFlow,
+        _role: ERole,
+        _device_id: &PCWSTR,
+    ) -> windows::core::Result<()> {
+        AUDIO_CHANGES.mark();
+        Ok(())
+    }
+
+    fn OnPropertyValueChanged(
+        &self,
+        _device_id: &PCWSTR,
+        _key: &PROPERTYKEY,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+}
+
+#[implement(IAudioEndpointVolumeCallback)]
+struct EndpointVolumeNotifier;
+
+impl IAudioEndpointVolumeCallback_Impl for EndpointVolumeNotifier_Impl {
+    fn OnNotify(&self, _data: *mut AUDIO_VOLUME_NOTIFICATION_DATA) -> windows::core::Result<()> {
+        AUDIO_CHANGES.mark();
+        Ok(())
+    }
+}
+
+#[implement(IAudioSessionNotification)]
+struct SessionCreatedNotifier;
+
+impl IAudioSessionNotification_Impl for SessionCreatedNotifier_Impl {
+    fn OnSessionCreated(
+        &self,
+        _session: Ref<'_, IAudioSessionControl>,
+    ) -> windows::core::Result<()> {
+        // The next `list_sessions` picks the session up and subscribes to its events.
+        AUDIO_CHANGES.mark();
+        Ok(())
+    }
+}
+
+#[implement(IAudioSessionEvents)]
+struct SessionEventsNotifier;
+
+impl IAudioSessionEvents_Impl for SessionEventsNotifier_Impl {
+    fn OnDisplayNameChanged(
+        &self,
+        _name: &PCWSTR,
+        _context: *const GUID,
+    ) -> windows::core::Result<()> {
+        AUDIO_CHANGES.mark();
+        Ok(())
+    }
+
+    fn OnIconPathChanged(
+        &self,
+        _path: &PCWSTR,
+        _context: *const GUID,
+    ) -> windows::core::Result<()> {
+        AUDIO_CHANGES.mark();
+        Ok(())
+    }
+
+    fn OnSimpleVolumeChanged(
+        &self,
+        _volume: f32,
+        _muted: BOOL,
+        _context: *const GUID,
+    ) -> windows::core::Result<()> {
+        AUDIO_CHANGES.mark();
+        Ok(())
+    }
+
+    fn OnChannelVolumeChanged(
+        &self,
+        _channel_count: u32,
+        _volumes: *const f32,
+        _changed_channel: u32,
+        _context: *const GUID,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnGroupingParamChanged(
+        &self,
+        _grouping: *const GUID,
+        _context: *const GUID,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnStateChanged(&self, _state: AudioSessionState) -> windows::core::Result<()> {
+        AUDIO_CHANGES.mark();
+        Ok(())
+    }
+
+    fn OnSessionDisconnected(
+        &self,
+        _reason: AudioSessionDisconnectReason,
+    ) -> windows::core::Result<()> {
+        AUDIO_CHANGES.mark();
+        Ok(())
     }
 }
 
 fn enumerate_active_devices(
     enumerator: &IMMDeviceEnumerator,
     flow: EDataFlow,
+) -> Result<Vec<(IMMDevice, String)>> {
+    with_com_cache(|cache| {
+        if let Some(devices) = cache.devices.get(&flow.0) {
+            return Ok(devices.clone());
+        }
+        let devices = query_active_devices(enumerator, flow)?;
+        cache.devices.insert(flow.0, devices.clone());
+        Ok(devices)
+    })
+}
+
+fn query_active_devices(
+    enumerator: &IMMDeviceEnumerator,
+    flow: EDataFlow,
 ) -> Result<Vec<(IMMDevice, String)>> {
     let collection = unsafe { enumerator.EnumAudioEndpoints(flow, DEVICE_STATE_ACTIVE) }?;
     let count = unsafe { collection.GetCount() }?;
@@ -352,8 +900,9 @@ fn list_devices_for_flow(
     enumerator: &IMMDeviceEnumerator,
     flow: EDataFlow,
     default_id: Option<String>,
+    comms_id: Option<String>,
+    icon_cache: &mut IconCache,
 ) -> Result<Vec<PlaybackDeviceInfo>> {
-    let mut icon_cache = HashMap::new();
     let mut devices = Vec::new();
 
     for (device, device_id) in enumerate_active_devices(enumerator, flow)? {
@@ -362,7 +911,7 @@ fn list_devices_for_flow(
         let icon_path = get_device_property_string(&device, &PKEY_DEVICE_CLASS_ICON_PATH);
         let icon_data = icon_path
             .as_deref()
-            .and_then(|path| icon_data_for_icon_path(path, &mut icon_cache));
+            .and_then(|path| icon_data_for_icon_path(path, icon_cache));
         let endpoint = get_endpoint_volume(&device)?;
         let volume = unsafe { endpoint.GetMasterVolumeLevelScalar() }?;
         let is_muted = unsafe { endpoint.GetMute() }?.as_bool();
@@ -370,6 +919,7 @@ fn list_devices_for_flow(
             .as_ref()
             .map(|id| id == &device_id)
             .unwrap_or(false);
+        let is_comms_default = comms_id.as_deref() == Some(device_id.as_str());
 
         devices.push(PlaybackDeviceInfo {
             id: device_id,
@@ -378,12 +928,31 @@ fn list_devices_for_flow(
             volume,
             is_muted,
             is_default,
+            is_comms_default,
+            alias: None,
         });
     }
 
     Ok(devices)
 }
 
+/// Like `parse_device_target`, but resolves `COMMUNICATIONS_DEVICE_ID` and
+/// `DEFAULT_DEVICE_ID` to the flow's current Communications and default endpoint.
+fn resolve_device_target(
+    enumerator: &IMMDeviceEnumerator,
+    device_id: &str,
+) -> Result<(EDataFlow, String)> {
+    let (flow, raw_id) = parse_device_target(device_id);
+    let resolved = match raw_id {
+        COMMUNICATIONS_DEVICE_ID => default_device_id_for_role(enumerator, flow, eCommunications)
+            .ok_or_else(|| anyhow!("No communications device"))?,
+        DEFAULT_DEVICE_ID => default_device_id_for_role(enumerator, flow, eMultimedia)
+            .ok_or_else(|| anyhow!("No default device"))?,
+        _ => raw_id.to_string(),
+    };
+    Ok((flow, resolved))
+}
+
 fn parse_device_target(device_id: &str) -> (EDataFlow, &str) {
     if let Some(raw) = device_id.strip_prefix("recording:") {
         return (eCapture, raw);
@@ -398,9 +967,10 @@ fn collect_device_sessions(
     device: &IMMDevice,
     device_id: &str,
     default_device_id: Option<&str>,
+    filter: &SessionFilter,
     sessions: &mut Vec<SessionInfo>,
     seen_ids: &mut HashSet<String>,
-    icon_cache: &mut HashMap<String, Option<String>>,
+    icon_cache: &mut IconCache,
 ) -> Result<()> {
     let session_manager = get_session_manager(device)?;
     let enumerator = unsafe { session_manager.GetSessionEnumerator() }?;
@@ -423,6 +993,7 @@ fn collect_device_sessions(
         if !seen_ids.insert(session_id.clone()) {
             continue;
         }
+        watch_session(&control, &session_id);
 
         let display_name = unsafe { control2.GetDisplayName() }
             .ok()
@@ -447,6 +1018,7 @@ fn collect_device_sessions(
             .or_else(|| process_name.as_ref().map(|name| humanize_label(name)))
             .unwrap_or_else(|| "Unknown".to_string());
         if should_skip_session(
+            filter,
             process_id,
             &display_name,
             &process_name,
@@ -470,6 +1042,7 @@ fn collect_device_sessions(
             volume,
             is_muted,
             is_master: false,
+            alias: None,
         });
     }
 
@@ -480,9 +1053,9 @@ fn session_info_for_process(
     device: &IMMDevice,
     device_id: &str,
     default_device_id: Option<&str>,
-    process_id: u32,
-    process_path: Option<&str>,
-    icon_cache: &mut HashMap<String, Option<String>>,
+    filter: &SessionFilter,
+    focused: &FocusedProcess,
+    icon_cache: &mut IconCache,
 ) -> Result<Option<SessionInfo>> {
     let session_manager = get_session_manager(device)?;
     let enumerator = unsafe { session_manager.GetSessionEnumerator() }?;
@@ -494,20 +1067,7 @@ fn session_info_for_process(
         let simple: ISimpleAudioVolume = control.cast()?;
 
         let session_process_id = unsafe { control2.GetProcessId() }?;
-        let mut matches = session_process_id == process_id;
-
-        // Fallback: Check process path if PID mismatch
-        if !matches && process_path.is_some() && session_process_id != 0 {
-            if let Some(session_path) = query_process_path(session_process_id) {
-                if let Some(target_path) = process_path {
-                    if session_path == target_path {
-                        matches = true;
-                    }
-                }
-            }
-        }
-
-        if !matches {
+        if !focused.matches(session_process_id) {
             continue;
         }
 
@@ -542,6 +1102,7 @@ fn session_info_for_process(
             .or_else(|| process_name.as_ref().map(|name| humanize_label(name)))
             .unwrap_or_else(|| "Unknown".to_string());
         if should_skip_session(
+            filter,
             session_process_id,
             &display_name,
             &process_name,
@@ -565,37 +1126,85 @@ fn session_info_for_process(
             volume,
             is_muted,
             is_master: false,
+            alias: None,
         }));
     }
 
     Ok(None)
 }
 
-fn icon_data_for_path(
-    path: &str,
-    icon_cache: &mut HashMap<String, Option<String>>,
-) -> Option<String> {
-    let cache_key = format!("{}|0", path);
-    if let Some(cached) = icon_cache.get(&cache_key) {
-        return cached.clone();
-    }
-    let icon_data = extract_icon_data(path, 0);
-    icon_cache.insert(cache_key, icon_data.clone());
-    icon_data
+fn icon_data_for_path(path: &str, icon_cache: &mut IconCache) -> Option<String> {
+    icon_cache.get_or_extract(path, 0)
 }
 
-fn icon_data_for_icon_path(
-    icon_path: &str,
-    icon_cache: &mut HashMap<String, Option<String>>,
-) -> Option<String> {
+fn icon_data_for_icon_path(icon_path: &str, icon_cache: &mut IconCache) -> Option<String> {
     let (path, index) = parse_icon_location(icon_path)?;
-    let cache_key = format!("{}|{}", path, index);
-    if let Some(cached) = icon_cache.get(&cache_key) {
-        return cached.clone();
+    icon_cache.get_or_extract(&path, index)
+}
+
+/// Icon lookups for one enumeration, backed by a persistent on-disk cache.
+///
+/// Disk entries are keyed by (path, index, file mtime) so a changed executable
+/// gets re-extracted. An empty file records that the path has no icon.
+struct IconCache {
+    memory: HashMap<String, Option<String>>,
+    disk_dir: Option<PathBuf>,
+}
+
+impl IconCache {
+    fn new(disk_dir: Option<PathBuf>) -> Self {
+        Self {
+            memory: HashMap::new(),
+            disk_dir,
+        }
     }
-    let icon_data = extract_icon_data(&path, index);
-    icon_cache.insert(cache_key, icon_data.clone());
-    icon_data
+
+    fn get_or_extract(&mut self, path: &str, index: i32) -> Option<String> {
+        let cache_key = format!("{}|{}", path, index);
+        if let Some(cached) = self.memory.get(&cache_key) {
+            return cached.clone();
+        }
+
+        let disk_path = self.disk_entry_path(path, index);
+        let icon_data = match disk_path
+            .as_ref()
+            .and_then(|file| std::fs::read_to_string(file).ok())
+        {
+            Some(data) => Some(data).filter(|data| !data.is_empty()),
+            None => {
+                let icon_data = extract_icon_data(path, index);
+                if let Some(file) = &disk_path {
+                    let _ = std::fs::write(file, icon_data.as_deref().unwrap_or(""));
+                }
+                icon_data
+            }
+        };
+
+        self.memory.insert(cache_key, icon_data.clone());
+        icon_data
+    }
+
+    fn disk_entry_path(&self, path: &str, index: i32) -> Option<PathBuf> {
+        let dir = self.disk_dir.as_ref()?;
+        let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+        let mtime = modified
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_nanos();
+        std::fs::create_dir_all(dir).ok()?;
+        let key = format!("{}|{}|{}", path.to_lowercase(), index, mtime);
+        Some(dir.join(format!("{:016x}.b64", fnv1a_64(key.as_bytes()))))
+    }
+}
+
+// Stable across builds, unlike `DefaultHasher`, so cache file names survive upgrades.
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
 }
 
 fn parse_icon_location(value: &str) -> Option<(String, i32)> {
@@ -778,10 +1387,11 @@ fn to_wide_string(value: &str) -> Vec<u16> {
 }
 
 fn split_session_id(session_id: &str) -> (Option<&str>, &str) {
-    if let Some((device_id, inner)) = session_id.split_once('|') {
-        (Some(device_id), inner)
-    } else {
-        (None, session_id)
+    // Session identifiers contain a `|` themselves, so only a prefix without
+    // the `pid:` part is a device id.
+    match session_id.split_once('|') {
+        Some((device_id, inner)) if !device_id.contains(':') => (Some(device_id), inner),
+        _ => (None, session_id),
     }
 }
 
@@ -810,10 +1420,29 @@ fn set_session_volume_on_device(
     Ok(false)
 }
 
+fn session_peak_on_device(device: &IMMDevice, session_id: &str) -> Result<Option<f32>> {
+    let session_manager = get_session_manager(device)?;
+    let enumerator = unsafe { session_manager.GetSessionEnumerator() }?;
+    let count = unsafe { enumerator.GetCount() }?;
+
+    for index in 0..count {
+        let control = unsafe { enumerator.GetSession(index) }?;
+        let control2: IAudioSessionControl2 = control.cast()?;
+        let process_id = unsafe { control2.GetProcessId() }?;
+        let id = session_identifier(&control2, process_id)
+            .unwrap_or_else(|| format!("pid:{}", process_id));
+        if id == session_id {
+            let meter: IAudioMeterInformation = control.cast()?;
+            return Ok(Some(unsafe { meter.GetPeakValue() }?));
+        }
+    }
+
+    Ok(None)
+}
+
 fn set_session_volume_for_process(
     device: &IMMDevice,
-    process_id: u32,
-    process_path: Option<&str>,
+    focused: &FocusedProcess,
     volume: f32,
 ) -> Result<bool> {
     let session_manager = get_session_manager(device)?;
@@ -827,19 +1456,7 @@ fn set_session_volume_for_process(
         let simple: ISimpleAudioVolume = control.cast()?;
 
         let session_process_id = unsafe { control2.GetProcessId() }?;
-        let mut matches = session_process_id == process_id;
-
-        if !matches && process_path.is_some() && session_process_id != 0 {
-            if let Some(session_path) = query_process_path(session_process_id) {
-                if let Some(target_path) = process_path {
-                    if session_path == target_path {
-                        matches = true;
-                    }
-                }
-            }
-        }
-
-        if matches {
+        if focused.matches(session_process_id) {
             unsafe { simple.SetMasterVolume(volume, std::ptr::null()) }?;
             updated = true;
         }
@@ -848,10 +1465,52 @@ fn set_session_volume_for_process(
     Ok(updated)
 }
 
+/// Picks the session of a process with the highest current peak level.
+///
+/// Ties (e.g. everything silent) resolve to the first session found, so the
+/// choice stays stable while nothing is playing.
+fn loudest_session_for_process(
+    enumerator: &IMMDeviceEnumerator,
+    focused: &FocusedProcess,
+) -> Result<Option<ISimpleAudioVolume>> {
+    let mut loudest: Option<(ISimpleAudioVolume, f32)> = None;
+
+    for (device, _id) in enumerate_active_devices(enumerator, eRender)? {
+        let session_manager = get_session_manager(&device)?;
+        let sessions = unsafe { session_manager.GetSessionEnumerator() }?;
+        let count = unsafe { sessions.GetCount() }?;
+
+        for index in 0..count {
+            let control = unsafe { sessions.GetSession(index) }?;
+            let control2: IAudioSessionControl2 = control.cast()?;
+
+            let session_process_id = unsafe { control2.GetProcessId() }?;
+            if !focused.matches(session_process_id) {
+                continue;
+            }
+
+            let peak = control
+                .cast::<IAudioMeterInformation>()
+                .ok()
+                .and_then(|meter| unsafe { meter.GetPeakValue() }.ok())
+                .unwrap_or(0.0);
+            let is_louder = loudest
+                .as_ref()
+                .map(|(_, best)| peak > *best)
+                .unwrap_or(true);
+            if is_louder {
+                let simple: ISimpleAudioVolume = control.cast()?;
+                loudest = Some((simple, peak));
+            }
+        }
+    }
+
+    Ok(loudest.map(|(simple, _)| simple))
+}
+
 fn set_session_mute_for_process(
     device: &IMMDevice,
-    process_id: u32,
-    process_path: Option<&str>,
+    focused: &FocusedProcess,
     muted: bool,
 ) -> Result<bool> {
     let session_manager = get_session_manager(device)?;
@@ -865,19 +1524,7 @@ fn set_session_mute_for_process(
         let simple: ISimpleAudioVolume = control.cast()?;
 
         let session_process_id = unsafe { control2.GetProcessId() }?;
-        let mut matches = session_process_id == process_id;
-
-        if !matches && process_path.is_some() && session_process_id != 0 {
-            if let Some(session_path) = query_process_path(session_process_id) {
-                if let Some(target_path) = process_path {
-                    if session_path == target_path {
-                        matches = true;
-                    }
-                }
-            }
-        }
-
-        if matches {
+        if focused.matches(session_process_id) {
             unsafe { simple.SetMute(muted, std::ptr::null()) }?;
             updated = true;
         }
@@ -900,71 +1547,56 @@ fn set_session_volume_by_name(device: &IMMDevice, name: &str, volume: f32) -> Re
         let simple: ISimpleAudioVolume = control.cast()?;
 
         let process_id = unsafe { control2.GetProcessId() }?;
-        let process_path = query_process_path(process_id);
-        let process_name = process_path
-            .as_ref()
-            .and_then(|path| Path::new(path).file_name())
-            .and_then(|name| name.to_str())
-            .map(|name| name.to_string());
-
-        let display_name = unsafe { control2.GetDisplayName() }
-            .ok()
-            .and_then(pwstr_to_string)
-            .map(|n| n.trim().to_lowercase());
-
-        let mut matches = false;
-
-        if let Some(path) = &process_path {
-            if let Some(stem) = Path::new(&path).file_stem().and_then(|s| s.to_str()) {
-                if stem.to_lowercase() == target_name {
-                    matches = true;
-                }
-            }
-        }
-
-        if !matches {
-            if let Some(name) = &process_name {
-                let stem = name.strip_suffix(".exe").unwrap_or(&name);
-                if stem.to_lowercase() == target_name {
-                    matches = true;
-                }
-            }
-        }
-
-        if !matches {
-            if let Some(name) = display_name {
-                if name == target_name {
-                    matches = true;
-                }
-            }
+        if session_matches_application(&control2, process_id, &target_name) {
+            unsafe { simple.SetMasterVolume(volume, std::ptr::null()) }?;
+            updated = true;
         }
+    }
 
-        if !matches {
-            if let Some(path) = &process_path {
-                if let Some(friendly) = friendly_process_label(path) {
-                    if friendly.to_lowercase() == target_name {
-                        matches = true;
-                    }
-                }
-            }
-        }
+    Ok(updated)
+}
 
-        if !matches {
-            if let Some(name) = &process_name {
-                let humanized = humanize_label(name);
-                if humanized.to_lowercase() == target_name {
-                    matches = true;
-                }
-            }
-        }
+fn application_process_ids(device: &IMMDevice, name: &str) -> Result<Vec<u32>> {
+    let session_manager = get_session_manager(device)?;
+    let enumerator = unsafe { session_manager.GetSessionEnumerator() }?;
+    let count = unsafe { enumerator.GetCount() }?;
+    let target_name = name.to_lowercase();
+    let mut process_ids = Vec::new();
 
-        if matches {
-            unsafe { simple.SetMasterVolume(volume, std::ptr::null()) }?;
-            updated = true;
+    for index in 0..count {
+        let control = unsafe { enumerator.GetSession(index) }?;
+        let control2: IAudioSessionControl2 = control.cast()?;
+        let process_id = unsafe { control2.GetProcessId() }?;
+        if process_id != 0 && session_matches_application(&control2, process_id, &target_name) {
+            process_ids.push(process_id);
         }
     }
 
-    Ok(updated)
+    Ok(process_ids)
+}
+
+/// `target_name` must already be lowercased.
+fn session_matches_application(
+    control2: &IAudioSessionControl2,
+    process_id: u32,
+    target_name: &str,
+) -> bool {
+    let process_path = query_process_path(process_id);
+    let process_name = process_path
+        .as_ref()
+        .and_then(|path| Path::new(path).file_name())
+        .and_then(|name| name.to_str())
+        .map(|name| name.to_string());
+    let display_name = unsafe { control2.GetDisplayName() }
+        .ok()
+        .and_then(pwstr_to_string);
+
+    application_matches(
+        target_name,
+        process_path.as_deref(),
+        process_name.as_deref(),
+        display_name.as_deref(),
+    )
 }
 
 fn set_session_mute_on_device(device: &IMMDevice, session_id: &str, muted: bool) -> Result<bool> {
@@ -988,6 +1620,27 @@ fn set_session_mute_on_device(device: &IMMDevice, session_id: &str, muted: bool)
     Ok(false)
 }
 
+/// The default output's notification-sound session, which `should_skip_session`
+/// keeps out of the normal list.
+fn find_system_sounds_session(
+    device: &IMMDevice,
+) -> Result<Option<(IAudioSessionControl2, ISimpleAudioVolume)>> {
+    let session_manager = get_session_manager(device)?;
+    let enumerator = unsafe { session_manager.GetSessionEnumerator() }?;
+    let count = unsafe { enumerator.GetCount() }?;
+
+    for index in 0..count {
+        let control = unsafe { enumerator.GetSession(index) }?;
+        let control2: IAudioSessionControl2 = control.cast()?;
+        if unsafe { control2.IsSystemSoundsSession() } == S_OK {
+            let simple: ISimpleAudioVolume = control.cast()?;
+            return Ok(Some((control2, simple)));
+        }
+    }
+
+    Ok(None)
+}
+
 fn set_session_mute_by_name(device: &IMMDevice, name: &str, muted: bool) -> Result<bool> {
     let session_manager = get_session_manager(device)?;
     let enumerator = unsafe { session_manager.GetSessionEnumerator() }?;
@@ -1002,52 +1655,116 @@ fn set_session_mute_by_name(device: &IMMDevice, name: &str, muted: bool) -> Resu
         let simple: ISimpleAudioVolume = control.cast()?;
 
         let process_id = unsafe { control2.GetProcessId() }?;
-        let process_path = query_process_path(process_id);
-        let process_name = process_path
-            .as_ref()
-            .and_then(|path| Path::new(path).file_name())
-            .and_then(|name| name.to_str())
-            .map(|name| name.to_string());
+        if session_matches_application(&control2, process_id, &target_name) {
+            unsafe { simple.SetMute(muted, std::ptr::null()) }?;
+            updated = true;
+        }
+    }
 
-        let display_name = unsafe { control2.GetDisplayName() }
-            .ok()
-            .and_then(pwstr_to_string)
-            .map(|n| n.trim().to_lowercase());
+    Ok(updated)
+}
 
-        let mut matches = false;
+// Undocumented factory behind Settings > "App volume and device preferences".
+// Only the endpoint persistence calls are used; the other slots keep the
+// vtable layout intact and are never called.
+#[allow(non_snake_case)]
+mod policy_config {
+    use std::ffi::c_void;
+    use windows::core::{IUnknown, IUnknown_Vtbl, GUID, HRESULT, PCWSTR};
+    use windows::Win32::Media::Audio::{EDataFlow, ERole};
+
+    #[windows::core::interface("ab3d4648-e242-459f-b02f-541c70306324")]
+    pub unsafe trait IAudioPolicyConfigFactory: IUnknown {
+        fn __GetIids(&self) -> HRESULT;
+        fn __GetRuntimeClassName(&self) -> HRESULT;
+        fn __GetTrustLevel(&self) -> HRESULT;
+        fn __add_CtxVolumeChange(&self) -> HRESULT;
+        fn __remove_CtxVolumeChanged(&self) -> HRESULT;
+        fn __add_RingerVibrateStateChanged(&self) -> HRESULT;
+        fn __remove_RingerVibrateStateChange(&self) -> HRESULT;
+        fn __SetVolumeGroupGainForId(&self) -> HRESULT;
+        fn __GetVolumeGroupGainForId(&self) -> HRESULT;
+        fn __GetActiveVolumeGroupForEndpointId(&self) -> HRESULT;
+        fn __GetVolumeGroupsForEndpoint(&self) -> HRESULT;
+        fn __GetCurrentVolumeContext(&self) -> HRESULT;
+        fn __SetVolumeGroupMuteForId(&self) -> HRESULT;
+        fn __GetVolumeGroupMuteForId(&self) -> HRESULT;
+        fn __SetRingerVibrateState(&self) -> HRESULT;
+        fn __GetRingerVibrateState(&self) -> HRESULT;
+        fn __SetPreferredChatApplication(&self) -> HRESULT;
+        fn __ResetPreferredChatApplication(&self) -> HRESULT;
+        fn __GetPreferredChatApplication(&self) -> HRESULT;
+        fn __GetCurrentChatApplications(&self) -> HRESULT;
+        fn __add_ChatContextChanged(&self) -> HRESULT;
+        fn __remove_ChatContextChanged(&self) -> HRESULT;
+        pub fn SetPersistedDefaultAudioEndpoint(
+            &self,
+            process_id: u32,
+            flow: EDataFlow,
+            role: ERole,
+            device_id: *mut c_void,
+        ) -> HRESULT;
+        fn __GetPersistedDefaultAudioEndpoint(&self) -> HRESULT;
+        fn __ClearAllPersistedApplicationDefaultEndpoints(&self) -> HRESULT;
+    }
+
+    // Undocumented interface the Sound control panel uses to change the default endpoint.
+    #[windows::core::interface("f8679f50-850a-41cf-9c72-430f290290c8")]
+    pub unsafe trait IPolicyConfig: IUnknown {
+        fn __GetMixFormat(&self) -> HRESULT;
+        fn __GetDeviceFormat(&self) -> HRESULT;
+        fn __ResetDeviceFormat(&self) -> HRESULT;
+        fn __SetDeviceFormat(&self) -> HRESULT;
+        fn __GetProcessingPeriod(&self) -> HRESULT;
+        fn __SetProcessingPeriod(&self) -> HRESULT;
+        fn __GetShareMode(&self) -> HRESULT;
+        fn __SetShareMode(&self) -> HRESULT;
+        fn __GetPropertyValue(&self) -> HRESULT;
+        fn __SetPropertyValue(&self) -> HRESULT;
+        pub fn SetDefaultEndpoint(&self, device_id: PCWSTR, role: ERole) -> HRESULT;
+        fn __SetEndpointVisibility(&self) -> HRESULT;
+    }
+
+    pub const CLSID_POLICY_CONFIG_CLIENT: GUID =
+        GUID::from_u128(0x870af99c_171d_4f9e_af0d_e63df40c2bc9);
+}
 
-        if let Some(path) = &process_path {
-            if let Some(stem) = Path::new(&path).file_stem().and_then(|s| s.to_str()) {
-                if stem.to_lowercase() == target_name {
-                    matches = true;
-                }
-            }
-        }
+use policy_config::{IAudioPolicyConfigFactory, IPolicyConfig, CLSID_POLICY_CONFIG_CLIENT};
 
-        if !matches {
-            if let Some(name) = &process_name {
-                let stem = name.strip_suffix(".exe").unwrap_or(&name);
-                if stem.to_lowercase() == target_name {
-                    matches = true;
-                }
-            }
-        }
+// Windows 10 builds before 21H2 expose the same layout under an older IID.
+const AUDIO_POLICY_CONFIG_FACTORY_IID_LEGACY: GUID =
+    GUID::from_u128(0x2a59116d_6c4f_45e0_a74f_707e3fef9258);
 
-        if !matches {
-            if let Some(name) = display_name {
-                if name == target_name {
-                    matches = true;
-                }
-            }
-        }
+fn audio_policy_config_factory() -> Result<IAudioPolicyConfigFactory> {
+    let class_id = HSTRING::from("Windows.Media.Internal.AudioPolicyConfig");
+    let factory: IInspectable = unsafe { RoGetActivationFactory(&class_id) }
+        .map_err(|err| anyhow!("Per-app device routing is not available: {}", err))?;
 
-        if matches {
-            unsafe { simple.SetMute(muted, std::ptr::null()) }?;
-            updated = true;
+    for iid in [
+        IAudioPolicyConfigFactory::IID,
+        AUDIO_POLICY_CONFIG_FACTORY_IID_LEGACY,
+    ] {
+        let mut raw = std::ptr::null_mut();
+        if unsafe { factory.query(&iid, &mut raw) }.is_ok() && !raw.is_null() {
+            return Ok(unsafe { IAudioPolicyConfigFactory::from_raw(raw) });
         }
     }
 
-    Ok(updated)
+    Err(anyhow!(
+        "Per-app device routing is not supported on this Windows version"
+    ))
+}
+
+/// Builds the device interface path the policy factory expects from an MMDevice id.
+fn device_interface_path(device_id: &str, flow: EDataFlow) -> String {
+    const RENDER_INTERFACE: &str = "{e6327cad-dcec-4949-ae8a-991e976a79d2}";
+    const CAPTURE_INTERFACE: &str = "{2eef81be-33fa-4800-9670-1cd474972c3f}";
+    let interface = if flow == eCapture {
+        CAPTURE_INTERFACE
+    } else {
+        RENDER_INTERFACE
+    };
+    format!("\\\\?\\SWD#MMDEVAPI#{}#{}", device_id, interface)
 }
 
 fn foreground_process_id() -> Option<u32> {
@@ -1064,6 +1781,173 @@ fn foreground_process_id() -> Option<u32> {
     }
 }
 
+/// The foreground app's processes. A session belongs to it when it comes from
+/// the window's process or another instance of the same executable; failing
+/// that, any parent or child process counts, since many apps (Electron, games,
+/// launchers) play audio from a helper process.
+struct FocusedProcess {
+    process_id: u32,
+    process_path: Option<String>,
+    /// Only filled when no session matched the window's own process.
+    process_tree: HashSet<u32>,
+}
+
+impl FocusedProcess {
+    fn foreground(enumerator: &IMMDeviceEnumerator) -> Result<Option<Self>> {
+        let Some(process_id) = foreground_process_id() else {
+            return Ok(None);
+        };
+        let mut focused = Self {
+            process_id,
+            process_path: query_process_path(process_id),
+            process_tree: HashSet::new(),
+        };
+        let session_ids = session_process_ids(enumerator)?;
+        if !session_ids.iter().any(|id| focused.matches(*id)) {
+            focused.process_tree = process_tree(process_id);
+        }
+        Ok(Some(focused))
+    }
+
+    fn matches(&self, session_process_id: u32) -> bool {
+        if session_process_id == self.process_id || self.process_tree.contains(&session_process_id)
+        {
+            return true;
+        }
+        session_process_id != 0
+            && self.process_path.is_some()
+            && query_process_path(session_process_id) == self.process_path
+    }
+}
+
+fn session_process_ids(enumerator: &IMMDeviceEnumerator) -> Result<HashSet<u32>> {
+    let mut ids = HashSet::new();
+    for (device, _id) in enumerate_active_devices(enumerator, eRender)? {
+        let session_manager = get_session_manager(&device)?;
+        let sessions = unsafe { session_manager.GetSessionEnumerator() }?;
+        let count = unsafe { sessions.GetCount() }?;
+        for index in 0..count {
+            let control2: IAudioSessionControl2 = unsafe { sessions.GetSession(index) }?.cast()?;
+            ids.insert(unsafe { control2.GetProcessId() }?);
+        }
+    }
+    Ok(ids)
+}
+
+/// Shells, system services and game launchers parent unrelated apps; walking
+/// up stops before them.
+const PROCESS_TREE_ROOTS: &[&str] = &[
+    "explorer.exe",
+    "svchost.exe",
+    "services.exe",
+    "sihost.exe",
+    "wininit.exe",
+    "winlogon.exe",
+    "steam.exe",
+    "steamwebhelper.exe",
+    "EpicGamesLauncher.exe",
+    "GalaxyClient.exe",
+    "Battle.net.exe",
+    "EADesktop.exe",
+    "upc.exe",
+];
+
+/// `process_id`'s ancestors up to the shell or launcher, plus all of its descendants.
+///
+/// Parent ids are not cleared when the parent exits, so a recorded parent only
+/// counts if it was started before its child; otherwise the id has been reused
+/// by an unrelated process.
+fn process_tree(process_id: u32) -> HashSet<u32> {
+    let processes = process_snapshot();
+    let mut tree = HashSet::from([process_id]);
+
+    let start = process_start_time(process_id);
+
+    let mut current = process_id;
+    let mut current_start = start;
+    while let Some((parent, _)) = processes.get(&current) {
+        let Some((_, parent_exe)) = processes.get(parent) else {
+            break;
+        };
+        let is_root = PROCESS_TREE_ROOTS
+            .iter()
+            .any(|root| parent_exe.eq_ignore_ascii_case(root));
+        if *parent <= 4 || is_root {
+            break;
+        }
+        let parent_start = process_start_time(*parent);
+        if !started_before(parent_start, current_start) || !tree.insert(*parent) {
+            break;
+        }
+        current = *parent;
+        current_start = parent_start;
+    }
+
+    let mut frontier = vec![(process_id, start)];
+    while let Some((parent, parent_start)) = frontier.pop() {
+        for (child, (child_parent, _)) in &processes {
+            if *child_parent != parent || *child == parent || tree.contains(child) {
+                continue;
+            }
+            let child_start = process_start_time(*child);
+            if started_before(parent_start, child_start) {
+                tree.insert(*child);
+                frontier.push((*child, child_start));
+            }
+        }
+    }
+    tree
+}
+
+/// Whether a process started at `parent` can be the parent of one started at
+/// `child`. Unknown start times (e.g. access denied) are not trusted.
+fn started_before(parent: Option<u64>, child: Option<u64>) -> bool {
+    matches!((parent, child), (Some(parent), Some(child)) if parent <= child)
+}
+
+/// Creation time of `process_id` in 100ns ticks since 1601, if it can be queried.
+fn process_start_time(process_id: u32) -> Option<u64> {
+    let handle =
+        unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id) }.ok()?;
+    if handle.is_invalid() {
+        return None;
+    }
+    let mut creation = FILETIME::default();
+    let mut exit = FILETIME::default();
+    let mut kernel = FILETIME::default();
+    let mut user = FILETIME::default();
+    let result =
+        unsafe { GetProcessTimes(handle, &mut creation, &mut exit, &mut kernel, &mut user) };
+    let _ = unsafe { CloseHandle(handle) };
+    result.ok()?;
+    Some(((creation.dwHighDateTime as u64) << 32) | creation.dwLowDateTime as u64)
+}
+
+/// Every running process as `pid -> (parent pid, executable name)`.
+fn process_snapshot() -> HashMap<u32, (u32, String)> {
+    let mut processes = HashMap::new();
+    let Ok(snapshot) = (unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) }) else {
+        return processes;
+    };
+    let mut entry = PROCESSENTRY32W {
+        dwSize: size_of::<PROCESSENTRY32W>() as u32,
+        ..Default::default()
+    };
+    let mut next = unsafe { Process32FirstW(snapshot, &mut entry) };
+    while next.is_ok() {
+        let len = entry
+            .szExeFile
+            .iter()
+            .position(|c| *c == 0)
+            .unwrap_or(entry.szExeFile.len());
+        let exe = String::from_utf16_lossy(&entry.szExeFile[..len]);
+        processes.insert(entry.th32ProcessID, (entry.th32ParentProcessID, exe));
+        next = unsafe { Process32NextW(snapshot, &mut entry) };
+    }
+    let _ = unsafe { CloseHandle(snapshot) };
+    processes
+}
+
 struct ComGuard;
 
 impl Drop for ComGuard {
@@ -1081,9 +1965,7 @@ fn init_com() -> Result<Option<ComGuard>> {
 }
 
 fn get_device_enumerator() -> Result<IMMDeviceEnumerator> {
-    let enumerator: IMMDeviceEnumerator =
-        unsafe { CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL) }?;
-    Ok(enumerator)
+    with_com_cache(|cache| Ok(cache.enumerator.clone()))
 }
 
 fn get_default_device_from_flow(
@@ -1094,6 +1976,15 @@ fn get_default_device_from_flow(
     Ok(device)
 }
 
+fn default_device_id_for_role(
+    enumerator: &IMMDeviceEnumerator,
+    flow: EDataFlow,
+    role: ERole,
+) -> Option<String> {
+    let device = unsafe { enumerator.GetDefaultAudioEndpoint(flow, role) }.ok()?;
+    device_id_string(&device)
+}
+
 fn get_default_device_from(enumerator: &IMMDeviceEnumerator) -> Result<IMMDevice> {
     get_default_device_from_flow(enumerator, eRender)
 }
@@ -1106,15 +1997,58 @@ fn get_default_device() -> Result<IMMDevice> {
 fn get_endpoint_volume(
     device: &windows::Win32::Media::Audio::IMMDevice,
 ) -> Result<IAudioEndpointVolume> {
-    let endpoint: IAudioEndpointVolume = unsafe { device.Activate(CLSCTX_ALL, None) }?;
-    Ok(endpoint)
+    let Some(device_id) = device_id_string(device) else {
+        return Ok(unsafe { device.Activate(CLSCTX_ALL, None) }?);
+    };
+    with_com_cache(|cache| {
+        if let Some((endpoint, _)) = cache.endpoint_volumes.get(&device_id) {
+            return Ok(endpoint.clone());
+        }
+        let endpoint: IAudioEndpointVolume = unsafe { device.Activate(CLSCTX_ALL, None) }?;
+        let callback: IAudioEndpointVolumeCallback = EndpointVolumeNotifier.into();
+        unsafe { endpoint.RegisterControlChangeNotify(&callback) }?;
+        cache
+            .endpoint_volumes
+            .insert(device_id, (endpoint.clone(), callback));
+        Ok(endpoint)
+    })
+}
+
+/// Endpoint volume for a `playback:`/`recording:` device id, or the default output when empty.
+fn endpoint_volume_for_target(device_id: &str) -> Result<IAudioEndpointVolume> {
+    let enumerator = get_device_enumerator()?;
+    if device_id.is_empty() {
+        return get_endpoint_volume(&get_default_device_from(&enumerator)?);
+    }
+    let (flow, raw_id) = resolve_device_target(&enumerator, device_id)?;
+    for (device, id) in enumerate_active_devices(&enumerator, flow)? {
+        if id == raw_id {
+            return get_endpoint_volume(&device);
+        }
+    }
+    Err(anyhow!("Device not found"))
 }
 
 fn get_session_manager(
     device: &windows::Win32::Media::Audio::IMMDevice,
 ) -> Result<IAudioSessionManager2> {
-    let manager: IAudioSessionManager2 = unsafe { device.Activate(CLSCTX_ALL, None) }?;
-    Ok(manager)
+    let Some(device_id) = device_id_string(device) else {
+        return Ok(unsafe { device.Activate(CLSCTX_ALL, None) }?);
+    };
+    with_com_cache(|cache| {
+        if let Some((manager, _)) = cache.session_managers.get(&device_id) {
+            return Ok(manager.clone());
+        }
+        let manager: IAudioSessionManager2 = unsafe { device.Activate(CLSCTX_ALL, None) }?;
+        // Creation events only start flowing once the session list has been read.
+        let _ = unsafe { manager.GetSessionEnumerator() }?;
+        let notifier: IAudioSessionNotification = SessionCreatedNotifier.into();
+        unsafe { manager.RegisterSessionNotification(&notifier) }?;
+        cache
+            .session_managers
+            .insert(device_id, (manager.clone(), notifier));
+        Ok(manager)
+    })
 }
 
 fn device_id_string(device: &IMMDevice) -> Option<String> {
@@ -1167,23 +2101,25 @@ fn is_resource_display_name(name: &str) -> bool {
 }
 
 fn should_skip_session(
+    filter: &SessionFilter,
     process_id: u32,
     display_name: &Option<String>,
     process_name: &Option<String>,
     process_path: &Option<String>,
     friendly_name: &str,
 ) -> bool {
+    if filter.show_hidden {
+        return false;
+    }
     if process_id == 0 {
         return true;
     }
 
-    let blocked = [
-        "audiosrv",
-        "audiodg",
-        "msedgewebview2",
-        "system sounds",
-        "midimaster",
-    ];
+    let blocked: Vec<String> = filter
+        .blocked_names
+        .iter()
+        .map(|name| canonical_label(name))
+        .collect();
 
     let mut labels = Vec::new();
     labels.push(canonical_label(friendly_name));
@@ -1226,41 +2162,6 @@ fn canonical_label(label: &str) -> String {
     trimmed.to_string()
 }
 
-fn friendly_process_label(path: &str) -> Option<String> {
-    let stem = Path::new(path).file_stem()?.to_string_lossy();
-    let cleaned = stem.replace(['_', '-'], " ");
-    let label = cleaned
-        .split_whitespace()
-        .map(humanize_word)
-        .collect::<Vec<_>>()
-        .join(" ");
-    if label.is_empty() {
-        None
-    } else {
-        Some(label)
-    }
-}
-
-fn humanize_label(label: &str) -> String {
-    let cleaned = label.replace(['_', '-'], " ");
-    cleaned
-        .split_whitespace()
-        .map(humanize_word)
-        .collect::<Vec<_>>()
-        .join(" ")
-}
-
-fn humanize_word(word: &str) -> String {
-    if word.chars().any(|ch| ch.is_uppercase()) {
-        return word.to_string();
-    }
-    let mut chars = word.chars();
-    match chars.next() {
-        Some(first) => format!("{}{}", first.to_uppercase(), chars.as_str().to_lowercase()),
-        None => String::new(),
-    }
-}
-
 fn query_process_path(process_id: u32) -> Option<String> {
     if process_id == 0 {
         return None;
@@ -1,44 +1,130 @@
-use crate::model::{Binding, MidiEvent, MidiMode, Profile};
+use crate::model::{
+    Binding, BindingAction, MidiEvent, MidiMessageType, MidiMode, Profile, RelativeMode,
+    VolumeCurve,
+};
 use std::time::{Duration, Instant};
 
 const RELATIVE_STEP: f32 = 0.02;
+/// Bottom of the logarithmic curve; anything below is treated as silence.
+const LOG_CURVE_FLOOR_DB: f32 = -60.0;
+/// Encoder ticks closer together than this are accelerated.
+const FAST_TICK: Duration = Duration::from_millis(30);
+/// Strongest allowed smoothing; anything higher lags behind the hand.
+const MAX_SMOOTHING: f32 = 0.9;
+/// A smoothed position this close to the raw one jumps the rest of the way.
+const SMOOTHING_SNAP: f32 = 0.5 / 127.0;
+/// A smoothed control quiet for this long is glided on by the main loop.
+pub const SMOOTHING_SETTLE_DELAY: Duration = Duration::from_millis(30);
+/// `BindingKey::channel` of bindings that match their control on any channel,
+/// so each has one key however many channels it is heard on.
+pub const ANY_CHANNEL: u8 = 0xFF;
+/// Rest position of a spring-loaded pitch-bend control.
+pub const PITCH_BEND_CENTER: f32 = 8192.0;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct BindingKey {
     pub device_id: String,
     pub channel: u8,
     pub controller: u8,
+    /// Keeps e.g. Program Change 7 apart from CC 7 on the same channel.
+    pub msg_type: MidiMessageType,
+    pub layer: u8,
 }
 
 #[derive(Debug, Clone)]
 pub struct BindingState {
     pub last_value: f32,
-    pub last_update: Instant,
+    /// When the last event passed the binding's debounce.
+    pub last_debounce: Option<Instant>,
+    /// When the user last touched the control; feedback holds off while this is recent
+    /// so it doesn't fight a fader being moved.
+    pub last_user_activity: Instant,
+    pub pressed: bool,
+    /// When the last Note-on that got through the binding's debounce arrived.
+    pub last_press: Option<Instant>,
+    /// Arrival time of the previous relative encoder tick.
+    pub last_tick: Option<Instant>,
+    /// Latest raw message of a smoothed control whose output hasn't caught up with it yet.
+    pub smoothing_event: Option<MidiEvent>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonEdge {
+    Press,
+    Release,
+}
+
+impl BindingState {
+    pub fn new() -> Self {
+        Self {
+            last_value: 0.0,
+            last_debounce: None,
+            last_user_activity: Instant::now(),
+            pressed: false,
+            last_press: None,
+            last_tick: None,
+            smoothing_event: None,
+        }
+    }
 }
 
 impl BindingKey {
-    pub fn from_event(event: &MidiEvent) -> Self {
+    pub fn from_event(event: &MidiEvent, layer: u8) -> Self {
         Self {
             device_id: event.device_id.clone(),
             channel: event.channel,
             controller: event.controller,
+            msg_type: event.msg_type.clone(),
+            layer,
         }
     }
 
     pub fn from_binding(binding: &Binding) -> Self {
         Self {
             device_id: binding.device_id.clone(),
-            channel: binding.control.channel,
+            channel: if binding.control.any_channel {
+                ANY_CHANNEL
+            } else {
+                binding.control.channel
+            },
             controller: binding.control.controller,
+            msg_type: binding.control.msg_type.clone(),
+            layer: binding.layer,
         }
     }
 }
 
+/// Finds the binding for `key` on its layer, falling back to a Shift binding
+/// on the same control from any layer, then to an any-channel binding.
 pub fn find_binding<'a>(profile: &'a Profile, key: &BindingKey) -> Option<&'a Binding> {
+    find_binding_on_channel(profile, key).or_else(|| {
+        let any_channel = BindingKey {
+            channel: ANY_CHANNEL,
+            ..key.clone()
+        };
+        find_binding_on_channel(profile, &any_channel)
+    })
+}
+
+fn find_binding_on_channel<'a>(profile: &'a Profile, key: &BindingKey) -> Option<&'a Binding> {
     profile
         .bindings
         .iter()
         .find(|binding| BindingKey::from_binding(binding) == *key)
+        .or_else(|| {
+            profile.bindings.iter().find(|binding| {
+                matches!(binding.action, BindingAction::Shift { .. })
+                    && BindingKey {
+                        layer: key.layer,
+                        ..BindingKey::from_binding(binding)
+                    } == *key
+            })
+        })
+}
+
+/// Whether the binding currently drives its control (and so may send feedback).
+pub fn on_layer(binding: &Binding, layer: u8) -> bool {
+    binding.layer == layer || matches!(binding.action, BindingAction::Shift { .. })
 }
 
 pub fn apply_midi_event(
@@ -46,44 +132,484 @@ pub fn apply_midi_event(
     event: &MidiEvent,
     state: &mut BindingState,
 ) -> Option<f32> {
-    let now = Instant::now();
-    if binding.debounce_ms > 0 {
+    apply_midi_event_at(binding, event, state, Instant::now())
+}
+
+fn apply_midi_event_at(
+    binding: &Binding,
+    event: &MidiEvent,
+    state: &mut BindingState,
+    now: Instant,
+) -> Option<f32> {
+    state.last_user_activity = now;
+    // Only fader sweeps are thinned out. Buttons are debounced by `is_bounce`, and
+    // a release must always get through or a quick tap leaves the button held.
+    let continuous = matches!(
+        binding.action,
+        BindingAction::Volume | BindingAction::Balance
+    );
+    if binding.debounce_ms > 0 && continuous && event.value != 0 {
         let debounce = Duration::from_millis(binding.debounce_ms);
-        if now.duration_since(state.last_update) < debounce {
+        if state
+            .last_debounce
+            .is_some_and(|last| now.duration_since(last) < debounce)
+        {
             return None;
         }
     }
 
     let next_value = match binding.mode {
-        MidiMode::Absolute => absolute_value(binding, event)?,
+        MidiMode::Absolute => {
+            smoothed_value(binding, event, state, absolute_value(binding, event)?)
+        }
         MidiMode::Relative => {
-            let delta = relative_delta(event.value)?;
-            (state.last_value + (delta as f32 * RELATIVE_STEP)).clamp(0.0, 1.0)
+            let delta = relative_delta(event.value, binding.relative_mode)?;
+            let step = RELATIVE_STEP * acceleration_multiplier(binding, state, now);
+            (state.last_value + (delta as f32 * step)).clamp(0.0, 1.0)
         }
     };
 
-    if binding.deadzone > 0.0 && (next_value - state.last_value).abs() < binding.deadzone {
+    // The ends of travel always get through, or a fader nudged onto its stop from
+    // inside the deadzone would never quite reach silence or full volume.
+    let at_extreme = (next_value == 0.0 || next_value == 1.0) && next_value != state.last_value;
+    if binding.deadzone > 0.0
+        && !at_extreme
+        && (next_value - state.last_value).abs() < binding.deadzone
+    {
         return None;
     }
 
     state.last_value = next_value;
-    state.last_update = now;
-    Some(next_value)
+    state.last_debounce = Some(now);
+    Some(position_to_volume(binding, next_value))
+}
+
+fn volume_range(binding: &Binding) -> (f32, f32) {
+    let min = binding.min_volume.clamp(0.0, 1.0);
+    let max = binding.max_volume.clamp(0.0, 1.0);
+    if max < min {
+        (max, min)
+    } else {
+        (min, max)
+    }
+}
+
+fn apply_curve(curve: VolumeCurve, position: f32) -> f32 {
+    match curve {
+        VolumeCurve::Linear => position,
+        VolumeCurve::Logarithmic => {
+            if position <= 0.0 {
+                0.0
+            } else {
+                10f32.powf((1.0 - position) * LOG_CURVE_FLOOR_DB / 20.0)
+            }
+        }
+        VolumeCurve::Power(exponent) if exponent > 0.0 => position.powf(exponent),
+        VolumeCurve::Power(_) => position,
+    }
+}
+
+fn invert_curve(curve: VolumeCurve, volume: f32) -> f32 {
+    match curve {
+        VolumeCurve::Linear => volume,
+        VolumeCurve::Logarithmic => {
+            if volume <= 0.0 {
+                0.0
+            } else {
+                (1.0 - 20.0 * volume.log10() / LOG_CURVE_FLOOR_DB).clamp(0.0, 1.0)
+            }
+        }
+        VolumeCurve::Power(exponent) if exponent > 0.0 => volume.powf(1.0 / exponent),
+        VolumeCurve::Power(_) => volume,
+    }
+}
+
+/// Maps a normalized control position (0..1) onto the binding's volume range.
+pub fn position_to_volume(binding: &Binding, position: f32) -> f32 {
+    let (min, max) = volume_range(binding);
+    let shaped = apply_curve(binding.volume_curve, position.clamp(0.0, 1.0));
+    min + shaped * (max - min)
+}
+
+/// Inverse of `position_to_volume`, used for feedback so a motor fader parks
+/// where the current volume sits within the binding's range.
+pub fn volume_to_position(binding: &Binding, volume: f32) -> f32 {
+    let (min, max) = volume_range(binding);
+    if max - min <= f32::EPSILON {
+        return 0.0;
+    }
+    let shaped = ((volume - min) / (max - min)).clamp(0.0, 1.0);
+    invert_curve(binding.volume_curve, shaped)
+}
+
+/// Detects press/release transitions for button-style bindings.
+///
+/// Returns `None` when the value does not change the pressed state (e.g. a
+/// decaying pressure value that stays above the threshold).
+pub fn button_edge(binding: &Binding, value: u8, state: &mut BindingState) -> Option<ButtonEdge> {
+    let Some(threshold) = binding.press_threshold else {
+        state.pressed = value != 0;
+        return Some(if value == 0 {
+            ButtonEdge::Release
+        } else {
+            ButtonEdge::Press
+        });
+    };
+
+    let pressed = value > threshold;
+    let was_pressed = state.pressed;
+    state.pressed = pressed;
+    match (was_pressed, pressed) {
+        (false, true) => Some(ButtonEdge::Press),
+        (true, false) => Some(ButtonEdge::Release),
+        _ => None,
+    }
+}
+
+/// Whether `event` is a Note-on within the binding's `debounce_ms` of the last
+/// accepted one: contact bounce from a cheap button, which would otherwise
+/// toggle twice for a single press.
+pub fn is_bounce(binding: &Binding, event: &MidiEvent, state: &mut BindingState) -> bool {
+    is_bounce_at(binding, event, state, Instant::now())
+}
+
+fn is_bounce_at(
+    binding: &Binding,
+    event: &MidiEvent,
+    state: &mut BindingState,
+    now: Instant,
+) -> bool {
+    if event.msg_type != MidiMessageType::Note || event.value == 0 {
+        return false;
+    }
+    let debounce = Duration::from_millis(binding.debounce_ms);
+    if state
+        .last_press
+        .is_some_and(|last| now.duration_since(last) < debounce)
+    {
+        return true;
+    }
+    state.last_press = Some(now);
+    false
+}
+
+/// Constant-power pan: returns (left, right) volumes for a 0..1 fader position.
+pub fn balance_volumes(position: f32) -> (f32, f32) {
+    let angle = position.clamp(0.0, 1.0) * std::f32::consts::FRAC_PI_2;
+    (angle.cos(), angle.sin())
+}
+
+/// Inverse of `balance_volumes`, recovering the fader position from two volumes.
+pub fn balance_position(left: f32, right: f32) -> f32 {
+    if left <= 0.0 && right <= 0.0 {
+        return 0.5;
+    }
+    (right.atan2(left) / std::f32::consts::FRAC_PI_2).clamp(0.0, 1.0)
+}
+
+/// Exponential moving average of an absolute control's position. While the output
+/// trails the raw position the event is kept, so `AppState::settle_smoothing` can
+/// replay it and the output still converges once the fader stops sending.
+fn smoothed_value(
+    binding: &Binding,
+    event: &MidiEvent,
+    state: &mut BindingState,
+    target: f32,
+) -> f32 {
+    let factor = binding.smoothing.clamp(0.0, MAX_SMOOTHING);
+    // The first message has no previous position to glide from.
+    if factor <= 0.0 || state.last_debounce.is_none() {
+        state.smoothing_event = None;
+        return target;
+    }
+    let value = state.last_value + (target - state.last_value) * (1.0 - factor);
+    // Within the deadzone the remaining glide would be dropped anyway; finish it
+    // here so the deadzone decides on the real position instead.
+    if (target - value).abs() <= SMOOTHING_SNAP.max(binding.deadzone) {
+        state.smoothing_event = None;
+        return target;
+    }
+    state.smoothing_event = Some(event.clone());
+    value
 }
 
 fn absolute_value(binding: &Binding, event: &MidiEvent) -> Option<f32> {
+    let value = raw_absolute_value(binding, event)?;
+    Some(if binding.invert { 1.0 - value } else { value })
+}
+
+fn raw_absolute_value(binding: &Binding, event: &MidiEvent) -> Option<f32> {
     if binding.control.controller == 0xE0 {
-        let value_14 = event.value_14?;
-        return Some((value_14 as f32) / 16383.0);
+        let value_14 = event.value_14? as f32;
+        if binding.pitch_bend_centered {
+            return Some(((value_14 - PITCH_BEND_CENTER) / (16383.0 - PITCH_BEND_CENTER)).max(0.0));
+        }
+        return Some(value_14 / 16383.0);
+    }
+    if binding.control.lsb_controller.is_some() {
+        if let Some(value_14) = event.value_14 {
+            return Some((value_14 as f32) / 16383.0);
+        }
     }
     Some((event.value as f32) / 127.0)
 }
 
-fn relative_delta(value: u8) -> Option<i8> {
-    match value {
-        0 | 64 => Some(0),
-        1..=63 => Some(value as i8),
-        65..=127 => Some(-((value - 64) as i8)),
-        _ => None,
+/// Scales the encoder step by tick rate: up to `1 + acceleration` for back-to-back
+/// ticks, falling to 1 at `FAST_TICK`. Any slower tick (or the first one after a
+/// pause) is unaccelerated, so a nudge after spinning stays fine-grained.
+fn acceleration_multiplier(binding: &Binding, state: &mut BindingState, now: Instant) -> f32 {
+    let previous = state.last_tick.replace(now);
+    if binding.acceleration <= 0.0 {
+        return 1.0;
+    }
+    let Some(previous) = previous else {
+        return 1.0;
+    };
+    let interval = now.duration_since(previous);
+    if interval >= FAST_TICK {
+        return 1.0;
+    }
+    let speed = 1.0 - interval.as_secs_f32() / FAST_TICK.as_secs_f32();
+    1.0 + binding.acceleration * speed
+}
+
+fn relative_delta(value: u8, mode: RelativeMode) -> Option<i8> {
+    if value > 127 {
+        return None;
+    }
+    match mode {
+        RelativeMode::SignedBit => match value {
+            0 | 64 => Some(0),
+            1..=63 => Some(value as i8),
+            _ => Some(-((value - 64) as i8)),
+        },
+        RelativeMode::TwosComplement => Some(if value >= 64 {
+            value as i8 - 64 - 64
+        } else {
+            value as i8
+        }),
+        RelativeMode::BinaryOffset => Some(value as i8 - 64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fader() -> Binding {
+        serde_json::from_value(json!({
+            "id": "fader",
+            "device_id": "midi:name:test",
+            "control": { "channel": 0, "controller": 7, "msg_type": "ControlChange" },
+            "target": "Master",
+            "mode": "Absolute",
+            "deadzone": 0.0,
+            "debounce_ms": 0,
+        }))
+        .unwrap()
+    }
+
+    fn cc(value: u8) -> MidiEvent {
+        MidiEvent {
+            device_id: "midi:name:test".to_string(),
+            channel: 0,
+            controller: 7,
+            value,
+            value_14: None,
+            msg_type: MidiMessageType::ControlChange,
+            is_note_off: false,
+            release_velocity: None,
+        }
+    }
+
+    #[test]
+    fn rapid_sweep_is_debounced_per_message() {
+        let binding = Binding {
+            debounce_ms: 10,
+            ..fader()
+        };
+        let mut state = BindingState::new();
+        let start = Instant::now();
+        let mut accepted = Vec::new();
+        // A full sweep, one message every 4ms.
+        for step in 0..=32u8 {
+            let now = start + Duration::from_millis(step as u64 * 4);
+            let event = cc((step * 4).min(127));
+            if let Some(volume) = apply_midi_event_at(&binding, &event, &mut state, now) {
+                accepted.push((step, volume));
+            }
+            // Dropped messages still count as the user touching the control.
+            assert_eq!(state.last_user_activity, now);
+        }
+        let steps: Vec<u8> = accepted.iter().map(|(step, _)| *step).collect();
+        assert_eq!(steps, (0..=32).step_by(3).collect::<Vec<u8>>());
+        for (step, volume) in &accepted {
+            assert_eq!(*volume, (step * 4).min(127) as f32 / 127.0);
+        }
+
+        // Once the fader rests, its final position gets through.
+        let settled = start + Duration::from_millis(32 * 4 + 10);
+        assert_eq!(
+            apply_midi_event_at(&binding, &cc(127), &mut state, settled),
+            Some(1.0)
+        );
+    }
+
+    #[test]
+    fn deadzone_lets_the_ends_of_travel_through() {
+        let binding = Binding {
+            deadzone: 0.05,
+            ..fader()
+        };
+        let mut state = BindingState::new();
+        state.last_value = 0.02;
+        state.last_debounce = Some(Instant::now());
+        assert_eq!(apply_midi_event(&binding, &cc(0), &mut state), Some(0.0));
+
+        state.last_value = 0.98;
+        assert_eq!(apply_midi_event(&binding, &cc(127), &mut state), Some(1.0));
+
+        // Anywhere else a move smaller than the deadzone is still dropped.
+        state.last_value = 0.5;
+        assert_eq!(apply_midi_event(&binding, &cc(65), &mut state), None);
+    }
+
+    #[test]
+    fn bouncing_button_toggles_once() {
+        let binding = Binding {
+            debounce_ms: 50,
+            ..fader()
+        };
+        let note_on = MidiEvent {
+            msg_type: MidiMessageType::Note,
+            ..cc(127)
+        };
+        let note_off = MidiEvent {
+            value: 0,
+            ..note_on.clone()
+        };
+        let mut state = BindingState::new();
+        let start = Instant::now();
+        let mut toggles = 0;
+        for offset in [0, 5, 10] {
+            let now = start + Duration::from_millis(offset);
+            if !is_bounce_at(&binding, &note_on, &mut state, now) {
+                toggles += 1;
+            }
+            // Releases are never swallowed, or a held button could stick.
+            assert!(!is_bounce_at(&binding, &note_off, &mut state, now));
+        }
+        assert_eq!(toggles, 1);
+        assert!(!is_bounce_at(
+            &binding,
+            &note_on,
+            &mut state,
+            start + Duration::from_millis(60)
+        ));
+    }
+
+    #[test]
+    fn quick_tap_release_gets_past_the_debounce() {
+        let binding = Binding {
+            action: BindingAction::PushToTalk,
+            debounce_ms: 50,
+            ..fader()
+        };
+        let press = MidiEvent {
+            msg_type: MidiMessageType::Note,
+            ..cc(127)
+        };
+        let release = MidiEvent {
+            value: 0,
+            is_note_off: true,
+            ..press.clone()
+        };
+        let mut state = BindingState::new();
+        let start = Instant::now();
+        assert_eq!(
+            apply_midi_event_at(&binding, &press, &mut state, start),
+            Some(1.0)
+        );
+        assert_eq!(
+            apply_midi_event_at(
+                &binding,
+                &release,
+                &mut state,
+                start + Duration::from_millis(5)
+            ),
+            Some(0.0)
+        );
+
+        // A fader pulled to the bottom inside the window still lands on silence.
+        let fader = Binding {
+            debounce_ms: 50,
+            ..fader()
+        };
+        let mut state = BindingState::new();
+        assert!(apply_midi_event_at(&fader, &cc(90), &mut state, start).is_some());
+        assert_eq!(
+            apply_midi_event_at(&fader, &cc(0), &mut state, start + Duration::from_millis(5)),
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn volume_curves_round_trip_through_feedback() {
+        for curve in [
+            VolumeCurve::Linear,
+            VolumeCurve::Logarithmic,
+            VolumeCurve::Power(2.0),
+            VolumeCurve::Power(0.5),
+        ] {
+            let binding = Binding {
+                volume_curve: curve,
+                min_volume: 0.1,
+                max_volume: 0.9,
+                ..fader()
+            };
+            // Every fader step maps to a volume whose feedback parks the fader
+            // back on the same step, so a motor fader doesn't creep.
+            for raw in 0..=127u8 {
+                let volume = position_to_volume(&binding, raw as f32 / 127.0);
+                let feedback = (volume_to_position(&binding, volume) * 127.0).round() as u8;
+                assert_eq!(feedback, raw, "{:?} at {}", curve, raw);
+            }
+            for step in 0..=100 {
+                let volume = 0.1 + 0.8 * step as f32 / 100.0;
+                let position = volume_to_position(&binding, volume);
+                let back = position_to_volume(&binding, position);
+                assert!((back - volume).abs() < 1e-4, "{:?} at {}", curve, volume);
+            }
+        }
+    }
+
+    #[test]
+    fn inverted_fader_reads_full_at_raw_zero() {
+        let binding = Binding {
+            invert: true,
+            ..fader()
+        };
+        assert_eq!(absolute_value(&binding, &cc(0)), Some(1.0));
+        assert_eq!(absolute_value(&binding, &cc(127)), Some(0.0));
+
+        let mut pitch_bend = Binding {
+            invert: true,
+            ..fader()
+        };
+        pitch_bend.control.controller = 0xE0;
+        pitch_bend.control.msg_type = MidiMessageType::PitchBend;
+        let bend = |value_14: u16| MidiEvent {
+            controller: 0xE0,
+            value: (value_14 >> 7) as u8,
+            value_14: Some(value_14),
+            msg_type: MidiMessageType::PitchBend,
+            ..cc(0)
+        };
+        assert_eq!(absolute_value(&pitch_bend, &bend(0)), Some(1.0));
+        assert_eq!(absolute_value(&pitch_bend, &bend(16383)), Some(0.0));
+
+        let mut state = BindingState::new();
+        assert_eq!(apply_midi_event(&binding, &cc(0), &mut state), Some(1.0));
     }
 }
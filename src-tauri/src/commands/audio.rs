@@ -1,25 +1,46 @@
-use crate::{model::PlaybackDeviceInfo, model::SessionInfo, AppState};
+use crate::{
+    model::{BindingTarget, PlaybackDeviceInfo, SessionInfo, TargetState},
+    AppState,
+};
 use tauri::State;
 
 #[tauri::command]
 pub fn list_sessions(state: State<AppState>) -> Result<Vec<SessionInfo>, String> {
-    state.audio.list_sessions().map_err(|err| err.to_string())
+    let mut sessions = state.audio.list_sessions().map_err(|err| err.to_string())?;
+    state.apply_session_aliases(&mut sessions);
+    Ok(sessions)
 }
 
 #[tauri::command]
 pub fn list_playback_devices(state: State<AppState>) -> Result<Vec<PlaybackDeviceInfo>, String> {
-    state
+    let mut devices = state
         .audio
         .list_playback_devices()
-        .map_err(|err| err.to_string())
+        .map_err(|err| err.to_string())?;
+    state.apply_device_aliases(&mut devices, false);
+    Ok(devices)
 }
 
 #[tauri::command]
 pub fn list_recording_devices(state: State<AppState>) -> Result<Vec<PlaybackDeviceInfo>, String> {
-    state
+    let mut devices = state
         .audio
         .list_recording_devices()
-        .map_err(|err| err.to_string())
+        .map_err(|err| err.to_string())?;
+    state.apply_device_aliases(&mut devices, true);
+    Ok(devices)
+}
+
+/// Reads a target's current volume and mute straight from the audio backend.
+#[tauri::command]
+pub fn get_target_state(
+    state: State<AppState>,
+    target: BindingTarget,
+) -> Result<TargetState, String> {
+    state
+        .target_state(&target)
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "Target not found".to_string())
 }
 
 #[tauri::command]
@@ -30,6 +51,14 @@ pub fn set_master_volume(state: State<AppState>, volume: f32) -> Result<(), Stri
         .map_err(|err| err.to_string())
 }
 
+#[tauri::command]
+pub fn set_system_sounds_volume(state: State<AppState>, volume: f32) -> Result<(), String> {
+    state
+        .audio
+        .set_system_sounds_volume(volume)
+        .map_err(|err| err.to_string())
+}
+
 #[tauri::command]
 pub fn set_session_volume(
     state: State<AppState>,
@@ -66,6 +95,27 @@ pub fn set_device_volume(
         .map_err(|err| err.to_string())
 }
 
+#[tauri::command]
+pub fn set_channel_volume(
+    state: State<AppState>,
+    device_id: String,
+    channel: u32,
+    volume: f32,
+) -> Result<(), String> {
+    state
+        .audio
+        .set_channel_volume(&device_id, channel, volume)
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub fn set_balance(state: State<AppState>, device_id: String, balance: f32) -> Result<(), String> {
+    state
+        .audio
+        .set_balance(&device_id, balance)
+        .map_err(|err| err.to_string())
+}
+
 #[tauri::command]
 pub fn set_master_mute(state: State<AppState>, muted: bool) -> Result<(), String> {
     state
@@ -74,6 +124,14 @@ pub fn set_master_mute(state: State<AppState>, muted: bool) -> Result<(), String
         .map_err(|err| err.to_string())
 }
 
+#[tauri::command]
+pub fn set_system_sounds_mute(state: State<AppState>, muted: bool) -> Result<(), String> {
+    state
+        .audio
+        .set_system_sounds_mute(muted)
+        .map_err(|err| err.to_string())
+}
+
 #[tauri::command]
 pub fn set_session_mute(
     state: State<AppState>,
@@ -109,3 +167,23 @@ pub fn set_device_mute(
         .set_device_mute(&device_id, muted)
         .map_err(|err| err.to_string())
 }
+
+#[tauri::command]
+pub fn set_application_output_device(
+    state: State<AppState>,
+    name: String,
+    device_id: String,
+) -> Result<(), String> {
+    state
+        .audio
+        .set_application_output_device(&name, &device_id)
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub fn set_default_device(state: State<AppState>, device_id: String) -> Result<(), String> {
+    state
+        .audio
+        .set_default_device(&device_id)
+        .map_err(|err| err.to_string())
+}
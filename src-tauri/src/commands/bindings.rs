@@ -1,6 +1,42 @@
 use crate::{bindings::BindingKey, model, model::Binding, AppState};
+use serde::Serialize;
 use std::time::{Duration, Instant};
-use tauri::{AppHandle, Emitter, Manager, State};
+use tauri::{AppHandle, Emitter, State};
+
+/// An existing binding that adding another would replace.
+#[derive(Serialize)]
+pub struct BindingConflict {
+    pub id: String,
+    pub name: String,
+}
+
+/// Whether two bindings listen to the same control, so only one can stay.
+fn same_control(a: &Binding, b: &Binding) -> bool {
+    a.device_id == b.device_id && a.control == b.control && a.layer == b.layer
+}
+
+/// The binding in the active profile that `add_binding` would replace with
+/// `binding`, so the UI can ask before overwriting it.
+#[tauri::command]
+pub fn find_binding_conflict(
+    state: State<AppState>,
+    binding: Binding,
+) -> Result<Option<BindingConflict>, String> {
+    let profile_guard = state
+        .active_profile
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())?;
+    Ok(profile_guard.as_ref().and_then(|profile| {
+        profile
+            .bindings
+            .iter()
+            .find(|existing| existing.id != binding.id && same_control(existing, &binding))
+            .map(|existing| BindingConflict {
+                id: existing.id.clone(),
+                name: existing.name.clone(),
+            })
+    }))
+}
 
 #[tauri::command]
 pub fn add_binding(state: State<AppState>, binding: Binding) -> Result<(), String> {
@@ -13,12 +49,66 @@ pub fn add_binding(state: State<AppState>, binding: Binding) -> Result<(), Strin
         bindings: Vec::new(),
         osd_settings: model::OsdSettings::default(),
         plugin_settings: std::collections::HashMap::new(),
+        target_aliases: std::collections::HashMap::new(),
+        auto_switch_processes: Vec::new(),
+        midi_input_device_id: None,
+        midi_output_device_ids: Vec::new(),
     });
-    profile.bindings.retain(|existing| {
-        !(existing.device_id == binding.device_id && existing.control == binding.control)
-    });
+    profile
+        .bindings
+        .retain(|existing| !same_control(existing, &binding));
     profile.bindings.push(binding);
+    state.sync_midi_config(profile);
+    state.sync_feedback_values(profile);
+    Ok(())
+}
+
+/// Replaces the binding with the same `id` in the active profile, keeping its
+/// place in the list. When the control address changes, the old control's
+/// cached state is dropped and its feedback cleared.
+#[tauri::command]
+pub fn update_binding(state: State<AppState>, binding: Binding) -> Result<(), String> {
+    let mut profile_guard = state
+        .active_profile
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())?;
+    let profile = profile_guard
+        .as_mut()
+        .ok_or_else(|| "No active profile".to_string())?;
+    let index = profile
+        .bindings
+        .iter()
+        .position(|existing| existing.id == binding.id)
+        .ok_or_else(|| format!("Binding not found: {}", binding.id))?;
+
+    let previous = std::mem::replace(&mut profile.bindings[index], binding.clone());
+    // Like `add_binding`, a control can only drive one binding.
+    profile
+        .bindings
+        .retain(|existing| existing.id == binding.id || !same_control(existing, &binding));
+
+    state
+        .profile_store
+        .save_profile(profile.clone())
+        .map_err(|err| err.to_string())?;
+
+    let old_key = BindingKey::from_binding(&previous);
+    let moved = old_key != BindingKey::from_binding(&binding);
+    if moved {
+        if let Ok(mut feedback) = state.feedback_values.lock() {
+            feedback.remove(&old_key);
+        }
+        if let Ok(mut states) = state.binding_state.lock() {
+            states.remove(&old_key);
+        }
+    }
+    state.sync_midi_config(profile);
     state.sync_feedback_values(profile);
+    drop(profile_guard);
+
+    if moved {
+        state.send_feedback(&previous, 0.0);
+    }
     Ok(())
 }
 
@@ -41,6 +131,7 @@ pub async fn remove_binding(state: State<'_, AppState>, binding: Binding) -> Res
                 .profile_store
                 .save_profile(profile.clone())
                 .map_err(|err| err.to_string())?;
+            state.sync_midi_config(profile);
         }
     }
 
@@ -57,19 +148,43 @@ pub async fn remove_binding(state: State<'_, AppState>, binding: Binding) -> Res
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     // 4. Send 0.0 value to the binding's control
-    if let Ok(mut midi) = state.midi.lock() {
-        let _ = midi.send_feedback(
-            &binding.device_id,
-            binding.control.channel,
-            binding.control.controller,
-            0.0,
-            binding.control.msg_type.clone(),
-        );
-    }
+    state.send_feedback(&binding, 0.0);
 
     Ok(())
 }
 
+/// Puts the active profile's bindings in the order of `ordered_ids`. Unknown
+/// and repeated ids are ignored; bindings left out keep their relative order
+/// after the listed ones.
+#[tauri::command]
+pub fn reorder_bindings(
+    state: State<AppState>,
+    ordered_ids: Vec<String>,
+) -> Result<Vec<Binding>, String> {
+    let mut profile_guard = state
+        .active_profile
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())?;
+    let profile = profile_guard
+        .as_mut()
+        .ok_or_else(|| "No active profile".to_string())?;
+
+    let mut remaining = std::mem::take(&mut profile.bindings);
+    for id in &ordered_ids {
+        if let Some(index) = remaining.iter().position(|binding| &binding.id == id) {
+            profile.bindings.push(remaining.remove(index));
+        }
+    }
+    profile.bindings.append(&mut remaining);
+
+    state
+        .profile_store
+        .save_profile(profile.clone())
+        .map_err(|err| err.to_string())?;
+    state.sync_feedback_values(profile);
+    Ok(profile.bindings.clone())
+}
+
 #[tauri::command]
 pub fn update_midi_feedback(
     state: State<AppState>,
@@ -106,7 +221,7 @@ pub fn update_midi_feedback(
             if !is_note {
                 if let Ok(states) = state.binding_state.lock() {
                     if let Some(state) = states.get(&key) {
-                        let elapsed = state.last_update.elapsed().as_millis();
+                        let elapsed = state.last_user_activity.elapsed().as_millis();
                         if elapsed < 500 {
                             continue;
                         }
@@ -132,15 +247,7 @@ pub fn update_midi_feedback(
             }
 
             // Send the actual MIDI feedback
-            if let Ok(mut midi) = state.midi.lock() {
-                let _ = midi.send_feedback(
-                    &binding.device_id,
-                    binding.control.channel,
-                    binding.control.controller,
-                    value,
-                    binding.control.msg_type.clone(),
-                );
-            }
+            state.send_feedback(binding, value);
         }
     }
 
@@ -163,23 +270,57 @@ pub fn set_binding_feedback(
     };
 
     let binding = match profile.bindings.iter().find(|b| b.id == binding_id) {
-        Some(b) => b,
+        Some(b) => b.clone(),
         None => return Ok(()),
     };
+    let is_toggle = matches!(
+        binding.action,
+        model::BindingAction::ToggleMute | model::BindingAction::PushToTalk
+    );
+    // Toggle state reported for one binding is authoritative for every toggle
+    // on the same target, otherwise the next press on a sibling flips the wrong way.
+    let sibling_keys: Vec<BindingKey> = if is_toggle {
+        profile
+            .bindings
+            .iter()
+            .filter(|other| {
+                other.id != binding.id
+                    && other.target == binding.target
+                    && matches!(
+                        other.action,
+                        model::BindingAction::ToggleMute | model::BindingAction::PushToTalk
+                    )
+            })
+            .map(BindingKey::from_binding)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    drop(profile_guard);
 
-    let key = BindingKey::from_binding(binding);
+    let key = BindingKey::from_binding(&binding);
 
     let silent = silent.unwrap_or(false);
 
+    if !sibling_keys.is_empty() {
+        if let Ok(mut feedback) = state.feedback_values.lock() {
+            for sibling in sibling_keys {
+                feedback.insert(sibling, value);
+            }
+        }
+    }
+
     // Prevent fighting the user while they're moving a control.
     // For motor faders, sending feedback while the user is actively moving causes jitter.
     // We still want to update internal state + UI/OSD for user-driven changes.
+    // Toggles are excluded: the press itself marks the control active, and the plugin's
+    // confirmation of the new state must not be dropped.
     let is_note = matches!(binding.control.msg_type, model::MidiMessageType::Note);
     let mut user_active = false;
-    if !is_note {
+    if !is_note && !is_toggle {
         if let Ok(states) = state.binding_state.lock() {
             if let Some(st) = states.get(&key) {
-                user_active = st.last_update.elapsed().as_millis() < 500;
+                user_active = st.last_user_activity.elapsed().as_millis() < 500;
             }
         }
     }
@@ -203,7 +344,9 @@ pub fn set_binding_feedback(
             feedback.insert(key.clone(), value);
         }
     }
-    if skip {
+    // A toggle's value was already recorded optimistically on press, so a
+    // non-silent confirmation still has to reach the UI/OSD.
+    if skip && (silent || !is_toggle) {
         return Ok(());
     }
 
@@ -214,30 +357,18 @@ pub fn set_binding_feedback(
     // Send MIDI feedback to hardware.
     // Suppress during active user movement to avoid motor jitter.
     if !user_active {
-        if let Ok(mut midi) = state.midi.lock() {
-            let _ = midi.send_feedback(
-                &binding.device_id,
-                binding.control.channel,
-                binding.control.controller,
-                value,
-                binding.control.msg_type.clone(),
-            );
-        }
+        state.send_feedback(&binding, value);
     }
 
     // Emit UI/OSD updates.
     let effective_action = action.unwrap_or_else(|| binding.action.clone());
-    let settings_enabled = state
-        .osd_settings
-        .lock()
-        .map(|settings| settings.enabled)
-        .unwrap_or(true);
+    let (display_name, icon_data) = state.target_display(&binding.target);
 
     match effective_action {
-        model::BindingAction::ToggleMute => {
+        model::BindingAction::ToggleMute | model::BindingAction::PushToTalk => {
             let muted = value > 0.5;
             let focus_session = if matches!(&binding.target, model::BindingTarget::Focus) {
-                state.audio.focused_session().ok().flatten()
+                state.focused_session_with_alias()
             } else {
                 None
             };
@@ -246,27 +377,23 @@ pub fn set_binding_feedback(
               "muted": muted,
               "action": "toggle_mute",
               "focus_session": focus_session,
+              "alias": state.target_alias(&binding.target),
               "binding_id": binding.id,
-              "silent": silent
+              "silent": silent,
+              "display_name": display_name,
+              "icon_data": icon_data,
             });
             let _ = app.emit("mute_update", payload.clone());
-            if settings_enabled && !silent {
-                if let Some(osd_window) = app.get_webview_window("osd") {
-                    let _ = osd_window.show();
-                    let _ = osd_window.emit("mute_update", payload.clone());
-                    if let Ok(payload_json) = serde_json::to_string(&payload) {
-                        let script = format!(
-                            "window.__OSD_UPDATE__ && window.__OSD_UPDATE__({});",
-                            payload_json
-                        );
-                        let _ = osd_window.eval(&script);
-                    }
-                }
+            if !silent {
+                state.emit_osd(&app, &binding, &payload);
             }
         }
-        model::BindingAction::Volume => {
+        model::BindingAction::Volume
+        | model::BindingAction::VolumeStep { .. }
+        | model::BindingAction::SetVolume { .. }
+        | model::BindingAction::Duck { .. } => {
             let focus_session = if matches!(&binding.target, model::BindingTarget::Focus) {
-                state.audio.focused_session().ok().flatten()
+                state.focused_session_with_alias()
             } else {
                 None
             };
@@ -274,24 +401,23 @@ pub fn set_binding_feedback(
               "target": binding.target,
               "volume": value,
               "focus_session": focus_session,
+              "alias": state.target_alias(&binding.target),
+              "companion_muted": state.companion_muted(&binding),
               "binding_id": binding.id,
-              "silent": silent
+              "silent": silent,
+              "display_name": display_name,
+              "icon_data": icon_data,
             });
             let _ = app.emit("volume_update", payload.clone());
-            if settings_enabled && !silent {
-                if let Some(osd_window) = app.get_webview_window("osd") {
-                    let _ = osd_window.show();
-                    let _ = osd_window.emit("volume_update", payload.clone());
-                    if let Ok(payload_json) = serde_json::to_string(&payload) {
-                        let script = format!(
-                            "window.__OSD_UPDATE__ && window.__OSD_UPDATE__({});",
-                            payload_json
-                        );
-                        let _ = osd_window.eval(&script);
-                    }
-                }
+            if !silent {
+                state.emit_osd(&app, &binding, &payload);
             }
         }
+        model::BindingAction::RouteToDevice { .. }
+        | model::BindingAction::CycleDefaultDevice
+        | model::BindingAction::Balance
+        | model::BindingAction::Solo
+        | model::BindingAction::Shift { .. } => {}
     }
 
     Ok(())
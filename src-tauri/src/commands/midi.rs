@@ -1,6 +1,15 @@
-use crate::{model::DeviceInfo, AppState};
+use crate::midi::{validate_midi_message, MidiOutputStatus};
+use crate::{app_settings::MidiHeartbeat, model::DeviceInfo, AppState};
+use serde::Serialize;
+use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager, State};
 
+#[derive(Serialize)]
+pub struct MidiActivityInfo {
+    pub active: bool,
+    pub idle_ms: Option<u64>,
+}
+
 #[tauri::command]
 pub fn list_midi_devices(state: State<AppState>) -> Result<Vec<DeviceInfo>, String> {
     state
@@ -27,18 +36,48 @@ pub fn start_midi_device(
     state: State<AppState>,
     input_device_id: String,
     output_device_id: String,
-) -> Result<(), String> {
+    additional_output_device_ids: Option<Vec<String>>,
+) -> Result<String, String> {
+    let mut output_device_ids = vec![output_device_id];
+    output_device_ids.extend(additional_output_device_ids.unwrap_or_default());
+    connect_midi_device(&app, &state, &input_device_id, &output_device_ids)
+}
+
+/// Starts MIDI on the given ports. Shared by `start_midi_device` and profiles
+/// that remember their device.
+pub fn connect_midi_device(
+    app: &AppHandle,
+    state: &AppState,
+    input_device_id: &str,
+    output_device_ids: &[String],
+) -> Result<String, String> {
     let app_handle = app.clone();
-    state
+    let device_id = state
         .midi
         .lock()
         .map_err(|_| "Lock poisoned".to_string())?
-        .start_device(&input_device_id, &output_device_id, move |event| {
+        .start_device(input_device_id, output_device_ids, move |event| {
             let _ = app_handle.emit("midi_event", &event);
             let state = app_handle.state::<AppState>();
+            state.record_midi_activity(&app_handle);
             let _ = state.apply_midi_event(&app_handle, event);
         })
-        .map_err(|err| err.to_string())
+        .map_err(|err| err.to_string())?;
+
+    if device_id != input_device_id
+        && state.migrate_legacy_device_id(input_device_id, &device_id)?
+    {
+        let _ = app.emit("bindings_migrated", &device_id);
+    }
+    let profile = state
+        .active_profile
+        .lock()
+        .ok()
+        .and_then(|profile| profile.clone());
+    if let Some(profile) = profile {
+        state.send_feedback_snapshot(&profile);
+    }
+    Ok(device_id)
 }
 
 #[tauri::command]
@@ -51,8 +90,122 @@ pub fn stop_midi_device(state: State<AppState>) -> Result<(), String> {
     Ok(())
 }
 
+#[tauri::command]
+pub fn last_midi_activity(state: State<AppState>) -> Result<MidiActivityInfo, String> {
+    let activity = state
+        .midi_activity
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())?;
+    Ok(MidiActivityInfo {
+        active: activity.active,
+        idle_ms: activity
+            .last_event
+            .map(|time| time.elapsed().as_millis() as u64),
+    })
+}
+
+/// Health of every open output.
+#[tauri::command]
+pub fn midi_output_status(state: State<AppState>) -> Result<Vec<MidiOutputStatus>, String> {
+    let midi = state.midi.lock().map_err(|_| "Lock poisoned".to_string())?;
+    Ok(midi.output_status())
+}
+
+/// Changes how persistently failing outputs are reopened from now on. Omitted
+/// values keep their current setting.
+#[tauri::command]
+pub fn set_midi_reconnect_policy(
+    state: State<AppState>,
+    cooldown_ms: Option<u64>,
+    max_failures: Option<u32>,
+) -> Result<(), String> {
+    let mut midi = state.midi.lock().map_err(|_| "Lock poisoned".to_string())?;
+    let mut policy = midi.reconnect_policy();
+    if let Some(cooldown_ms) = cooldown_ms {
+        policy.cooldown = Duration::from_millis(cooldown_ms);
+    }
+    if let Some(max_failures) = max_failures {
+        policy.max_failures = max_failures;
+    }
+    midi.set_reconnect_policy(policy);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_midi_heartbeat(
+    state: State<AppState>,
+    output_device_id: String,
+    interval_ms: u64,
+    message: Option<Vec<u8>>,
+) -> Result<(), String> {
+    // It is resent every interval, so a bad one would fail on every tick.
+    let message = message.unwrap_or_default();
+    if !message.is_empty() {
+        validate_midi_message(&message).map_err(|err| err.to_string())?;
+    }
+    let mut settings = state
+        .app_settings
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())?;
+    settings
+        .midi_heartbeats
+        .retain(|heartbeat| heartbeat.output_device_id != output_device_id);
+    if interval_ms > 0 {
+        settings.midi_heartbeats.push(MidiHeartbeat {
+            output_device_id,
+            interval_ms,
+            message,
+        });
+    }
+    let updated = settings.clone();
+    drop(settings);
+
+    state
+        .app_settings_store
+        .save(&updated)
+        .map_err(|err| err.to_string())
+}
+
+/// While enabled, every incoming MIDI message is emitted as `midi_raw`, including
+/// SysEx and others that bindings ignore.
+#[tauri::command]
+pub fn set_midi_passthrough(
+    app: AppHandle,
+    state: State<AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    let midi = state.midi.lock().map_err(|_| "Lock poisoned".to_string())?;
+    if enabled {
+        midi.set_raw_listener(move |message| {
+            let _ = app.emit("midi_raw", &message);
+        });
+    } else {
+        midi.clear_raw_listener();
+    }
+    Ok(())
+}
+
+/// Sends a raw MIDI message (e.g. a SysEx lighting command) to an open output.
+#[tauri::command]
+pub fn send_raw_midi(
+    state: State<AppState>,
+    device_id: String,
+    bytes: Vec<u8>,
+) -> Result<(), String> {
+    state
+        .midi
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())?
+        .send_raw(&device_id, &bytes)
+        .map_err(|err| err.to_string())
+}
+
 #[tauri::command]
 pub fn start_midi_learn(state: State<AppState>) -> Result<(), String> {
+    *state
+        .unlearn_pending
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())? = false;
     *state
         .learn_pending
         .lock()
@@ -64,6 +217,29 @@ pub fn start_midi_learn(state: State<AppState>) -> Result<(), String> {
     Ok(())
 }
 
+/// The next input on a bound control removes that binding and emits `binding_unlearned`.
+#[tauri::command]
+pub fn start_unlearn(state: State<AppState>) -> Result<(), String> {
+    *state
+        .learn_pending
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())? = false;
+    *state
+        .unlearn_pending
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())? = true;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn cancel_unlearn(state: State<AppState>) -> Result<(), String> {
+    *state
+        .unlearn_pending
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())? = false;
+    Ok(())
+}
+
 #[tauri::command]
 pub fn consume_learned_control(
     state: State<AppState>,
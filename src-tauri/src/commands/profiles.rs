@@ -1,5 +1,15 @@
-use crate::{model::Profile, model::ProfileSummary, AppState};
-use tauri::{AppHandle, State};
+use super::midi::connect_midi_device;
+use crate::{
+    model::BindingTarget,
+    model::Profile,
+    model::ProfileSummary,
+    plugin_api::load_plugin_manifest,
+    plugin_settings::validate_setting,
+    profile_merge::{merge_profiles, MergeStrategy, ProfileMerge},
+    AppState,
+};
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter, State};
 
 #[tauri::command]
 pub fn list_profiles(state: State<AppState>) -> Result<Vec<ProfileSummary>, String> {
@@ -15,9 +25,15 @@ pub fn load_profile(
     state: State<AppState>,
     name: String,
 ) -> Result<Profile, String> {
+    activate_profile(&app, &state, &name)
+}
+
+/// Makes a stored profile the active one. Shared by `load_profile` and
+/// foreground-app auto-switching.
+pub fn activate_profile(app: &AppHandle, state: &AppState, name: &str) -> Result<Profile, String> {
     let profile = state
         .profile_store
-        .load_profile(&name)
+        .load_profile(name)
         .map_err(|err| err.to_string())?
         .ok_or_else(|| "Profile not found".to_string())?;
 
@@ -28,18 +44,117 @@ pub fn load_profile(
 
     if let Ok(mut settings) = state.osd_settings.lock() {
         *settings = profile.osd_settings.clone();
-        crate::AppState::apply_osd_settings(&app, &settings);
+        crate::AppState::apply_osd_settings(app, &settings);
     }
-    state.sync_feedback_values(&profile);
+    state.sync_midi_config(&profile);
+    state.set_active_layer(app, &profile, 0);
+    switch_midi_device(app, state, &profile);
+
+    // Connecting may have migrated legacy device ids in the active profile.
+    let profile = state
+        .active_profile
+        .lock()
+        .ok()
+        .and_then(|active| active.clone())
+        .unwrap_or(profile);
+    state.send_feedback_snapshot(&profile);
+    warn_missing_devices(app, state, &profile);
     Ok(profile)
 }
 
+/// Starts the profile's MIDI device unless it is already running with the same
+/// outputs. When it isn't connected the current device stays up and
+/// `profile_midi_device_missing` is emitted.
+fn switch_midi_device(app: &AppHandle, state: &AppState, profile: &Profile) {
+    let Some(input_device_id) = profile.midi_input_device_id.as_deref() else {
+        return;
+    };
+    let (active, active_outputs, devices) = match state.midi.lock() {
+        Ok(midi) => (
+            midi.active_device().map(str::to_string),
+            midi.output_device_ids(),
+            midi.list_devices(),
+        ),
+        Err(_) => return,
+    };
+    let mut outputs = profile.midi_output_device_ids.clone();
+    outputs.sort();
+    outputs.dedup();
+    if active.as_deref() == Some(input_device_id) && active_outputs == outputs {
+        return;
+    }
+    let present = devices
+        .map(|devices| devices.iter().any(|device| device.id == input_device_id))
+        .unwrap_or(false);
+    let started = if present {
+        connect_midi_device(app, state, input_device_id, &profile.midi_output_device_ids)
+    } else {
+        Err("Device not connected".to_string())
+    };
+    match started {
+        Ok(device_id) => {
+            let payload = serde_json::json!({
+              "device_id": device_id,
+              "output_device_ids": profile.midi_output_device_ids,
+            });
+            let _ = app.emit("midi_device_switched", payload);
+        }
+        Err(err) => {
+            log_warn!(
+                "Profiles: MIDI device {} for \"{}\" unavailable: {}",
+                input_device_id,
+                profile.name,
+                err
+            );
+            let payload = serde_json::json!({
+              "profile": profile.name,
+              "device_id": input_device_id,
+              "error": err,
+            });
+            let _ = app.emit("profile_midi_device_missing", payload);
+        }
+    }
+}
+
+/// Emits `profile_device_missing` when bindings reference MIDI devices that are not connected.
+fn warn_missing_devices(app: &AppHandle, state: &AppState, profile: &Profile) {
+    let connected = match state.midi.lock().map(|midi| midi.list_devices()) {
+        Ok(Ok(devices)) => devices,
+        _ => return,
+    };
+
+    let mut missing: Vec<String> = Vec::new();
+    for binding in &profile.bindings {
+        let device_id = &binding.device_id;
+        if device_id.is_empty() || missing.contains(device_id) {
+            continue;
+        }
+        // Legacy `midi:N` ids resolve by port index until the device is next
+        // started and they are migrated, so they count as connected too.
+        let present = connected
+            .iter()
+            .any(|device| &device.id == device_id || &device.legacy_id == device_id);
+        if !present {
+            missing.push(device_id.clone());
+        }
+    }
+
+    if !missing.is_empty() {
+        let payload = serde_json::json!({
+          "profile": profile.name,
+          "missing_devices": missing,
+        });
+        let _ = app.emit("profile_device_missing", payload);
+    }
+}
+
 #[tauri::command]
 pub fn save_profile(
     app: AppHandle,
     state: State<AppState>,
-    profile: Profile,
+    mut profile: Profile,
 ) -> Result<(), String> {
+    state.describe_session_targets(&mut profile);
     state
         .profile_store
         .save_profile(profile.clone())
@@ -52,10 +167,147 @@ pub fn save_profile(
         *settings = profile.osd_settings.clone();
         crate::AppState::apply_osd_settings(&app, &settings);
     }
+    state.sync_midi_config(&profile);
     state.sync_feedback_values(&profile);
     Ok(())
 }
 
+/// Merges `incoming` into the stored profile of the same name without saving,
+/// so the caller can confirm the result with `save_profile`.
+#[tauri::command]
+pub fn merge_profile(
+    state: State<AppState>,
+    incoming: Profile,
+    strategy: MergeStrategy,
+) -> Result<ProfileMerge, String> {
+    let existing = state
+        .profile_store
+        .load_profile(&incoming.name)
+        .map_err(|err| err.to_string())?;
+    let Some(existing) = existing else {
+        return Ok(ProfileMerge {
+            added: incoming
+                .bindings
+                .iter()
+                .map(|binding| binding.id.clone())
+                .collect(),
+            profile: incoming,
+            updated: Vec::new(),
+            skipped: Vec::new(),
+        });
+    };
+    Ok(merge_profiles(&existing, &incoming, strategy))
+}
+
+/// Sets the executables that load `name` when they gain focus.
+#[tauri::command]
+pub fn set_profile_auto_switch(
+    state: State<AppState>,
+    name: String,
+    processes: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let processes: Vec<String> = processes
+        .iter()
+        .map(|process| process.trim().to_string())
+        .filter(|process| !process.is_empty())
+        .collect();
+    let mut profile = state
+        .profile_store
+        .load_profile(&name)
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "Profile not found".to_string())?;
+    profile.auto_switch_processes = processes.clone();
+    state
+        .profile_store
+        .save_profile(profile)
+        .map_err(|err| err.to_string())?;
+
+    if let Some(active) = state
+        .active_profile
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())?
+        .as_mut()
+        .filter(|active| active.name == name)
+    {
+        active.auto_switch_processes = processes.clone();
+    }
+    Ok(processes)
+}
+
+/// Remembers the MIDI device a profile starts when it loads. `None` forgets it.
+#[tauri::command]
+pub fn set_profile_midi_devices(
+    state: State<AppState>,
+    name: String,
+    input_device_id: Option<String>,
+    output_device_ids: Vec<String>,
+) -> Result<(), String> {
+    let mut profile = state
+        .profile_store
+        .load_profile(&name)
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "Profile not found".to_string())?;
+    profile.midi_input_device_id = input_device_id.clone();
+    profile.midi_output_device_ids = output_device_ids.clone();
+    state
+        .profile_store
+        .save_profile(profile)
+        .map_err(|err| err.to_string())?;
+
+    if let Some(active) = state
+        .active_profile
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())?
+        .as_mut()
+        .filter(|active| active.name == name)
+    {
+        active.midi_input_device_id = input_device_id;
+        active.midi_output_device_ids = output_device_ids;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn duplicate_profile(
+    state: State<AppState>,
+    source_name: String,
+    new_name: String,
+) -> Result<ProfileSummary, String> {
+    let profile = state
+        .profile_store
+        .duplicate_profile(&source_name, &new_name)
+        .map_err(|err| err.to_string())?;
+    Ok(ProfileSummary {
+        name: profile.name,
+        auto_switch_processes: profile.auto_switch_processes,
+    })
+}
+
+/// Returns a single profile as a shareable JSON document.
+#[tauri::command]
+pub fn export_profile(state: State<AppState>, name: String) -> Result<String, String> {
+    state
+        .profile_store
+        .export_profile(&name)
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub fn import_profile(
+    state: State<AppState>,
+    json: String,
+    rename_if_conflict: bool,
+) -> Result<ProfileSummary, String> {
+    let profile = state
+        .profile_store
+        .import_profile(&json, rename_if_conflict)
+        .map_err(|err| err.to_string())?;
+    Ok(ProfileSummary {
+        name: profile.name,
+        auto_switch_processes: profile.auto_switch_processes,
+    })
+}
+
 #[tauri::command]
 pub fn delete_profile(state: State<AppState>, name: String) -> Result<(), String> {
     state
@@ -73,3 +325,85 @@ pub fn get_active_profile(state: State<AppState>) -> Result<Option<Profile>, Str
         .map_err(|_| "Lock poisoned".to_string())?
         .clone())
 }
+
+#[tauri::command]
+pub fn set_target_alias(
+    state: State<AppState>,
+    target: BindingTarget,
+    alias: Option<String>,
+) -> Result<HashMap<String, String>, String> {
+    let key = target
+        .alias_key()
+        .ok_or_else(|| "Target does not support aliases".to_string())?;
+
+    let mut profile_guard = state
+        .active_profile
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())?;
+    let profile = profile_guard
+        .as_mut()
+        .ok_or_else(|| "No active profile".to_string())?;
+
+    match alias.map(|alias| alias.trim().to_string()) {
+        Some(alias) if !alias.is_empty() => {
+            profile.target_aliases.insert(key, alias);
+        }
+        _ => {
+            profile.target_aliases.remove(&key);
+        }
+    }
+
+    let aliases = profile.target_aliases.clone();
+    state
+        .profile_store
+        .save_profile(profile.clone())
+        .map_err(|err| err.to_string())?;
+    Ok(aliases)
+}
+
+/// Validates one plugin setting against the plugin's manifest schema, then stores
+/// it in the active profile. A `null` value removes the setting. Returns the
+/// plugin's settings after the change.
+#[tauri::command]
+pub fn set_plugin_setting(
+    app: AppHandle,
+    state: State<AppState>,
+    plugin_id: String,
+    key: String,
+    value: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let manifest = load_plugin_manifest(&app, &plugin_id)?;
+    if let Some(schema) = &manifest.settings {
+        validate_setting(schema, &key, &value)?;
+    }
+
+    let mut profile_guard = state
+        .active_profile
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())?;
+    let profile = profile_guard
+        .as_mut()
+        .ok_or_else(|| "No active profile".to_string())?;
+
+    let settings = profile
+        .plugin_settings
+        .entry(plugin_id)
+        .or_insert_with(|| serde_json::json!({}));
+    if !settings.is_object() {
+        *settings = serde_json::json!({});
+    }
+    if let Some(settings) = settings.as_object_mut() {
+        if value.is_null() {
+            settings.remove(&key);
+        } else {
+            settings.insert(key, value);
+        }
+    }
+
+    let updated = settings.clone();
+    state
+        .profile_store
+        .save_profile(profile.clone())
+        .map_err(|err| err.to_string())?;
+    Ok(updated)
+}
@@ -1,14 +1,21 @@
-use crate::{app_settings::AppSettings, model::OsdSettings, AppState};
-use serde::Serialize;
-use tauri::{AppHandle, State};
+use crate::{
+    app_settings::{AppSettings, Hotkey, HotkeyAction},
+    logging::{self, LogLevel},
+    model::{OsdInstance, OsdSettings},
+    AppState,
+};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State, WebviewWindow};
 
-use crate::windows_display::{display_device_id, monitor_display_name};
+use crate::windows_display::{display_device_id, monitor_display_name, monitor_edid_id};
 
 #[derive(Clone, Serialize)]
 pub struct MonitorInfo {
     pub index: usize,
     pub name: String,
     pub stable_id: String,
+    pub edid_id: Option<String>,
+    pub geometry: String,
     pub is_primary: bool,
 }
 
@@ -28,6 +35,7 @@ pub fn list_monitors(app: AppHandle) -> Result<Vec<MonitorInfo>, String> {
                 .unwrap_or_else(|| format!("Monitor {}", index + 1));
             let stable_id = display_device_id(&raw_name).unwrap_or_else(|| raw_name.clone());
             let name = monitor_display_name(&raw_name).unwrap_or_else(|| raw_name.clone());
+            let edid_id = monitor_edid_id(&raw_name);
             let is_primary = primary
                 .as_ref()
                 .map(|primary| {
@@ -40,6 +48,8 @@ pub fn list_monitors(app: AppHandle) -> Result<Vec<MonitorInfo>, String> {
                 index,
                 name,
                 stable_id,
+                edid_id,
+                geometry: crate::monitor_geometry(monitor),
                 is_primary,
             }
         })
@@ -55,25 +65,124 @@ pub fn get_osd_settings(state: State<AppState>) -> Result<OsdSettings, String> {
         .map_err(|_| "Lock poisoned".to_string())
 }
 
+/// Where the main OSD shows, as sent by `update_osd_settings`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OsdPlacementUpdate {
+    pub enabled: bool,
+    pub monitor_index: usize,
+    #[serde(default)]
+    pub monitor_name: Option<String>,
+    #[serde(default)]
+    pub monitor_id: Option<String>,
+    #[serde(default)]
+    pub monitor_edid: Option<String>,
+    #[serde(default)]
+    pub monitor_geometry: Option<String>,
+    pub anchor: String,
+}
+
 #[tauri::command]
 pub fn update_osd_settings(
     app: AppHandle,
     state: State<AppState>,
-    enabled: bool,
-    monitor_index: usize,
-    monitor_name: Option<String>,
-    monitor_id: Option<String>,
-    anchor: String,
+    placement: OsdPlacementUpdate,
+) -> Result<(), String> {
+    let mut settings = state
+        .osd_settings
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())?;
+    settings.enabled = placement.enabled;
+    settings.monitor_index = placement.monitor_index;
+    settings.monitor_name = placement.monitor_name;
+    settings.monitor_id = placement.monitor_id;
+    settings.monitor_edid = placement.monitor_edid;
+    settings.monitor_geometry = placement.monitor_geometry;
+    settings.anchor = placement.anchor;
+    let updated = settings.clone();
+    drop(settings);
+
+    if let Ok(mut profile_guard) = state.active_profile.lock() {
+        if let Some(profile) = profile_guard.as_mut() {
+            profile.osd_settings = updated.clone();
+            state
+                .profile_store
+                .save_profile(profile.clone())
+                .map_err(|err| err.to_string())?;
+        }
+    }
+
+    crate::AppState::apply_osd_settings(&app, &updated);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_osd_instances(
+    app: AppHandle,
+    state: State<AppState>,
+    instances: Vec<OsdInstance>,
+) -> Result<(), String> {
+    let mut seen = std::collections::HashSet::new();
+    if let Some(duplicate) = instances
+        .iter()
+        .find(|instance| !seen.insert(instance.id.as_str()))
+    {
+        return Err(format!("Duplicate OSD instance id: {}", duplicate.id));
+    }
+
+    let mut settings = state
+        .osd_settings
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())?;
+    settings.instances = instances;
+    let updated = settings.clone();
+    drop(settings);
+
+    if let Ok(mut profile_guard) = state.active_profile.lock() {
+        if let Some(profile) = profile_guard.as_mut() {
+            profile.osd_settings = updated.clone();
+            state
+                .profile_store
+                .save_profile(profile.clone())
+                .map_err(|err| err.to_string())?;
+        }
+    }
+
+    crate::AppState::apply_osd_settings(&app, &updated);
+    Ok(())
+}
+
+/// OSD window size is clamped to this range (logical pixels).
+const MIN_OSD_SIZE: f64 = 120.0;
+const MAX_OSD_SIZE: f64 = 2000.0;
+
+/// Updates the OSD look; omitted values are kept and an empty `accent_color`
+/// restores the default bar colour.
+#[tauri::command]
+pub fn update_osd_appearance(
+    app: AppHandle,
+    state: State<AppState>,
+    width: Option<f64>,
+    height: Option<f64>,
+    opacity: Option<f32>,
+    accent_color: Option<String>,
 ) -> Result<(), String> {
     let mut settings = state
         .osd_settings
         .lock()
         .map_err(|_| "Lock poisoned".to_string())?;
-    settings.enabled = enabled;
-    settings.monitor_index = monitor_index;
-    settings.monitor_name = monitor_name;
-    settings.monitor_id = monitor_id;
-    settings.anchor = anchor;
+    if let Some(width) = width {
+        settings.width = width.clamp(MIN_OSD_SIZE, MAX_OSD_SIZE);
+    }
+    if let Some(height) = height {
+        settings.height = height.clamp(MIN_OSD_SIZE, MAX_OSD_SIZE);
+    }
+    if let Some(opacity) = opacity {
+        settings.opacity = opacity.clamp(0.0, 1.0);
+    }
+    if let Some(accent_color) = accent_color {
+        let accent_color = accent_color.trim().to_string();
+        settings.accent_color = (!accent_color.is_empty()).then_some(accent_color);
+    }
     let updated = settings.clone();
     drop(settings);
 
@@ -91,6 +200,59 @@ pub fn update_osd_settings(
     Ok(())
 }
 
+/// Called by an OSD webview whenever its stacked cards change height, so the
+/// window can be resized and re-anchored around what is actually visible.
+#[tauri::command]
+pub fn report_osd_height(
+    app: AppHandle,
+    window: WebviewWindow,
+    state: State<AppState>,
+    height: f64,
+) -> Result<(), String> {
+    if !height.is_finite() || height <= 0.0 {
+        return Ok(());
+    }
+    let label = window.label().to_string();
+    {
+        let mut heights = state
+            .osd_content_heights
+            .lock()
+            .map_err(|_| "Lock poisoned".to_string())?;
+        if heights
+            .get(&label)
+            .is_some_and(|previous| (previous - height).abs() < 1.0)
+        {
+            return Ok(());
+        }
+        heights.insert(label.clone(), height);
+    }
+    crate::AppState::reposition_osd_window(&app, &label);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_log_level() -> LogLevel {
+    logging::level()
+}
+
+/// `trace` also logs every incoming MIDI message.
+#[tauri::command]
+pub fn set_log_level(state: State<AppState>, level: LogLevel) -> Result<(), String> {
+    let mut settings = state
+        .app_settings
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())?;
+    settings.log_level = level;
+    let updated = settings.clone();
+    drop(settings);
+
+    logging::set_level(level);
+    state
+        .app_settings_store
+        .save(&updated)
+        .map_err(|err| err.to_string())
+}
+
 #[tauri::command]
 pub fn get_app_settings(state: State<AppState>) -> Result<AppSettings, String> {
     state
@@ -100,23 +262,53 @@ pub fn get_app_settings(state: State<AppState>) -> Result<AppSettings, String> {
         .map_err(|_| "Lock poisoned".to_string())
 }
 
+/// General app settings sent by `update_app_settings`. Fields left out keep
+/// their current value.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AppSettingsUpdate {
+    pub start_with_windows: Option<bool>,
+    pub start_in_tray: Option<bool>,
+    pub minimize_to_tray: Option<bool>,
+    pub exit_to_tray: Option<bool>,
+    pub focus_primary_session_only: Option<bool>,
+    pub startup_grace_ms: Option<u64>,
+    pub double_tap_ms: Option<u64>,
+    pub learn_window_ms: Option<u64>,
+}
+
+impl AppSettingsUpdate {
+    fn apply(self, settings: &mut AppSettings) {
+        fn set<T>(field: &mut T, value: Option<T>) {
+            if let Some(value) = value {
+                *field = value;
+            }
+        }
+        set(&mut settings.start_with_windows, self.start_with_windows);
+        set(&mut settings.start_in_tray, self.start_in_tray);
+        set(&mut settings.minimize_to_tray, self.minimize_to_tray);
+        set(&mut settings.exit_to_tray, self.exit_to_tray);
+        set(
+            &mut settings.focus_primary_session_only,
+            self.focus_primary_session_only,
+        );
+        set(&mut settings.startup_grace_ms, self.startup_grace_ms);
+        set(&mut settings.double_tap_ms, self.double_tap_ms);
+        set(&mut settings.learn_window_ms, self.learn_window_ms);
+    }
+}
+
 #[tauri::command]
 pub fn update_app_settings(
     app: AppHandle,
     state: State<AppState>,
-    start_with_windows: bool,
-    start_in_tray: bool,
-    minimize_to_tray: bool,
-    exit_to_tray: bool,
+    update: AppSettingsUpdate,
 ) -> Result<(), String> {
     let mut settings = state
         .app_settings
         .lock()
         .map_err(|_| "Lock poisoned".to_string())?;
-    settings.start_with_windows = start_with_windows;
-    settings.start_in_tray = start_in_tray;
-    settings.minimize_to_tray = minimize_to_tray;
-    settings.exit_to_tray = exit_to_tray;
+    update.apply(&mut settings);
     let updated = settings.clone();
     drop(settings);
 
@@ -128,6 +320,84 @@ pub fn update_app_settings(
     Ok(())
 }
 
+/// Updates which audio sessions are hidden from the session list. Returns the
+/// cleaned-up names that were saved.
+#[tauri::command]
+pub fn set_session_filter(
+    state: State<AppState>,
+    hidden_sessions: Vec<String>,
+    show_hidden: bool,
+) -> Result<Vec<String>, String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for name in hidden_sessions {
+        let name = name.trim().to_string();
+        if name.is_empty()
+            || cleaned
+                .iter()
+                .any(|existing| existing.eq_ignore_ascii_case(&name))
+        {
+            continue;
+        }
+        cleaned.push(name);
+    }
+
+    let mut settings = state
+        .app_settings
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())?;
+    settings.hidden_sessions = cleaned.clone();
+    settings.show_hidden_sessions = show_hidden;
+    let updated = settings.clone();
+    drop(settings);
+
+    state
+        .app_settings_store
+        .save(&updated)
+        .map_err(|err| err.to_string())?;
+    state.audio.set_session_filter(updated.session_filter());
+    if let Some(changes) = state.audio.changes() {
+        changes.mark();
+    }
+    Ok(cleaned)
+}
+
+/// Replaces the global hotkeys and re-registers them. Returns the shortcuts that
+/// could not be registered so the UI can flag them.
+#[tauri::command]
+pub fn set_hotkeys(
+    app: AppHandle,
+    state: State<AppState>,
+    hotkeys: Vec<Hotkey>,
+) -> Result<Vec<String>, String> {
+    for hotkey in &hotkeys {
+        if hotkey.shortcut.trim().is_empty() {
+            return Err("Hotkey shortcut is empty".to_string());
+        }
+        if let HotkeyAction::Binding { action, .. } = &hotkey.action {
+            if !crate::hotkeys::is_button_action(action) {
+                return Err(format!(
+                    "{} can't be used as a hotkey action",
+                    serde_json::to_string(action).unwrap_or_default()
+                ));
+            }
+        }
+    }
+
+    let mut settings = state
+        .app_settings
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())?;
+    settings.hotkeys = hotkeys;
+    let updated = settings.clone();
+    drop(settings);
+
+    state
+        .app_settings_store
+        .save(&updated)
+        .map_err(|err| err.to_string())?;
+    Ok(crate::hotkeys::register_hotkeys(&app, &updated.hotkeys))
+}
+
 #[tauri::command]
 pub fn reset_app_data(app: AppHandle, state: State<AppState>) -> Result<(), String> {
     state
@@ -139,6 +409,10 @@ pub fn reset_app_data(app: AppHandle, state: State<AppState>) -> Result<(), Stri
         .clear()
         .map_err(|err| err.to_string())?;
 
+    if let Ok(mut hints) = state.control_hints.lock() {
+        hints.clear().map_err(|err| err.to_string())?;
+    }
+
     if let Ok(mut midi) = state.midi.lock() {
         midi.stop();
     }
@@ -0,0 +1,131 @@
+use crate::app_settings::Hotkey;
+use crate::model::{
+    Binding, BindingAction, BindingTarget, ButtonMode, MidiControl, MidiEvent, MidiMessageType,
+    MidiMode, RelativeMode, VolumeCurve,
+};
+use crate::AppState;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+/// Device id of the synthetic bindings hotkeys dispatch through, so their
+/// button state never collides with a real MIDI control.
+const HOTKEY_DEVICE_ID: &str = "hotkey";
+
+pub fn plugin() -> TauriPlugin<Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, shortcut, event| {
+            if let Some(state) = app.try_state::<AppState>() {
+                state.run_hotkey(app, shortcut.id(), event.state == ShortcutState::Pressed);
+            }
+        })
+        .build()
+}
+
+/// Replaces every registered shortcut with `hotkeys`. Returns the shortcuts that
+/// could not be registered: unparseable, duplicated, or taken by another app.
+pub fn register_hotkeys(app: &AppHandle, hotkeys: &[Hotkey]) -> Vec<String> {
+    let global_shortcut = app.global_shortcut();
+    let _ = global_shortcut.unregister_all();
+    let mut failed = Vec::new();
+    for hotkey in hotkeys {
+        let registered = hotkey
+            .shortcut
+            .parse::<Shortcut>()
+            .map_err(|err| err.to_string())
+            .and_then(|shortcut| {
+                global_shortcut
+                    .register(shortcut)
+                    .map_err(|err| err.to_string())
+            });
+        if let Err(err) = registered {
+            log_warn!("Hotkeys: {} unavailable: {}", hotkey.shortcut, err);
+            failed.push(hotkey.shortcut.clone());
+        }
+    }
+    failed
+}
+
+/// Index of the hotkey whose shortcut has the id the plugin reported.
+pub fn hotkey_index(hotkeys: &[Hotkey], shortcut_id: u32) -> Option<usize> {
+    hotkeys.iter().position(|hotkey| {
+        hotkey
+            .shortcut
+            .parse::<Shortcut>()
+            .is_ok_and(|shortcut| shortcut.id() == shortcut_id)
+    })
+}
+
+/// Hotkeys can't drive a fader, so only button actions make sense for them.
+pub fn is_button_action(action: &BindingAction) -> bool {
+    !matches!(action, BindingAction::Volume | BindingAction::Balance)
+}
+
+/// The button binding hotkey `index` runs through `dispatch_binding`. Feedback
+/// is off because there is no MIDI control behind it; bindings on the same
+/// target pick the change up on the next feedback sync.
+pub fn hotkey_binding(
+    index: usize,
+    target: &BindingTarget,
+    action: &BindingAction,
+    button_mode: ButtonMode,
+) -> Binding {
+    Binding {
+        id: format!("hotkey-{}", index),
+        name: String::new(),
+        device_id: HOTKEY_DEVICE_ID.to_string(),
+        output_device_id: None,
+        button_mode,
+        long_action: None,
+        long_press_ms: 0,
+        double_action: None,
+        layer: 0,
+        mute_group: None,
+        control: MidiControl {
+            channel: (index / 128) as u8,
+            controller: (index % 128) as u8,
+            msg_type: MidiMessageType::Note,
+            lsb_controller: None,
+            any_channel: false,
+        },
+        target: target.clone(),
+        action: action.clone(),
+        mode: MidiMode::Absolute,
+        relative_mode: RelativeMode::default(),
+        acceleration: 0.0,
+        min_volume: 0.0,
+        max_volume: 1.0,
+        volume_curve: VolumeCurve::default(),
+        invert: false,
+        pitch_bend_centered: false,
+        deadzone: 0.0,
+        smoothing: 0.0,
+        debounce_ms: 0,
+        press_threshold: None,
+        release_delay_ms: 0,
+        ptt_max_hold_ms: 0,
+        feedback_table: Vec::new(),
+        feedback_sysex: None,
+        meter_feedback: false,
+        meter_interval_ms: 0,
+        feedback_enabled: false,
+        feedback_channel: None,
+        feedback_controller: None,
+        led_off_value: None,
+        show_osd: true,
+    }
+}
+
+/// A full-velocity note on for a key press, note off for the release.
+pub fn hotkey_event(binding: &Binding, pressed: bool) -> MidiEvent {
+    MidiEvent {
+        device_id: binding.device_id.clone(),
+        channel: binding.control.channel,
+        controller: binding.control.controller,
+        value: if pressed { 127 } else { 0 },
+        value_14: None,
+        msg_type: MidiMessageType::Note,
+        is_note_off: !pressed,
+        release_velocity: None,
+    }
+}
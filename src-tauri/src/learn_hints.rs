@@ -0,0 +1,300 @@
+use crate::model::{ControlKind, LearnedControl, MidiEvent, MidiMessageType};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use std::{fs, path::PathBuf};
+
+type Result<T> = anyhow::Result<T>;
+
+/// How long an unclassified note is held back waiting for fader movement. Touch
+/// sensors fire as soon as the cap is touched, which can be well before the
+/// first CC/pitch bend, so this is longer than the CC window.
+pub const LEARN_NOTE_PREFERENCE_WINDOW: Duration = Duration::from_millis(400);
+
+/// What learn does with an event while it holds an unresolved candidate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CandidateChoice {
+    /// Keep the buffered candidate and its window.
+    Keep,
+    /// Buffer the incoming control and restart the window.
+    Replace,
+}
+
+pub fn is_continuous(msg_type: &MidiMessageType) -> bool {
+    matches!(
+        msg_type,
+        MidiMessageType::ControlChange | MidiMessageType::PitchBend
+    )
+}
+
+/// How long `candidate` is watched before it is committed. `learn_window` is the
+/// configured window for an unclassified CC: one that sends anything between 0
+/// and 127 in it is a fader, otherwise a button. Notes get at least
+/// [`LEARN_NOTE_PREFERENCE_WINDOW`].
+pub fn candidate_window(candidate: &LearnedControl, learn_window: Duration) -> Duration {
+    if candidate.msg_type == MidiMessageType::Note {
+        LEARN_NOTE_PREFERENCE_WINDOW.max(learn_window)
+    } else {
+        learn_window
+    }
+}
+
+fn same_control(a: &LearnedControl, b: &LearnedControl) -> bool {
+    a.device_id == b.device_id
+        && a.msg_type == b.msg_type
+        && a.channel == b.channel
+        && a.controller == b.controller
+}
+
+/// Continuous controllers win over notes from the same device, whichever
+/// arrives first; repeats of the buffered control keep its window running.
+pub fn choose_candidate(
+    current: Option<&LearnedControl>,
+    incoming: &LearnedControl,
+) -> CandidateChoice {
+    let Some(current) = current else {
+        return CandidateChoice::Replace;
+    };
+    if same_control(current, incoming) {
+        return CandidateChoice::Keep;
+    }
+    if current.device_id == incoming.device_id
+        && is_continuous(&current.msg_type)
+        && incoming.msg_type == MidiMessageType::Note
+    {
+        return CandidateChoice::Keep;
+    }
+    CandidateChoice::Replace
+}
+
+/// The note that turned out to be a touch sensor when `incoming` continuous
+/// input from the same device competes with it.
+pub fn touch_note<'a>(
+    current: &'a LearnedControl,
+    incoming: &'a LearnedControl,
+) -> Option<&'a LearnedControl> {
+    if current.device_id != incoming.device_id {
+        return None;
+    }
+    match (&current.msg_type, &incoming.msg_type) {
+        (MidiMessageType::Note, msg_type) if is_continuous(msg_type) => Some(current),
+        (msg_type, MidiMessageType::Note) if is_continuous(msg_type) => Some(incoming),
+        _ => None,
+    }
+}
+
+/// What has been observed about a single control on a device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlHint {
+    #[serde(default)]
+    pub kind: Option<ControlKind>,
+    pub min_value: u8,
+    pub max_value: u8,
+    /// A value strictly between 0 and 127 was seen, which buttons never send.
+    #[serde(default)]
+    pub intermediate_seen: bool,
+}
+
+/// Per-device control classifications, persisted so repeated learns can
+/// skip the touch-sense disambiguation window.
+pub struct ControlHintStore {
+    path: PathBuf,
+    hints: HashMap<String, ControlHint>,
+    dirty: bool,
+}
+
+fn hint_key(device_id: &str, msg_type: &MidiMessageType, channel: u8, controller: u8) -> String {
+    format!("{}|{:?}|{}|{}", device_id, msg_type, channel, controller)
+}
+
+fn control_key(control: &LearnedControl) -> String {
+    hint_key(
+        &control.device_id,
+        &control.msg_type,
+        control.channel,
+        control.controller,
+    )
+}
+
+impl ControlHintStore {
+    pub fn new(config_dir: PathBuf) -> Self {
+        let path = config_dir.join("control_hints.json");
+        let hints = fs::read_to_string(&path)
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            hints,
+            dirty: false,
+        }
+    }
+
+    pub fn kind(&self, control: &LearnedControl) -> Option<ControlKind> {
+        self.hints
+            .get(&control_key(control))
+            .and_then(|hint| hint.kind)
+    }
+
+    /// Records the value range of an incoming event and classifies CC/pitch bend
+    /// controls from it. Notes are classified by the learn flow via `mark`.
+    pub fn observe(&mut self, event: &MidiEvent) {
+        let key = hint_key(
+            &event.device_id,
+            &event.msg_type,
+            event.channel,
+            event.controller,
+        );
+        let hint = self.hints.entry(key).or_insert(ControlHint {
+            kind: None,
+            min_value: event.value,
+            max_value: event.value,
+            intermediate_seen: false,
+        });
+        hint.min_value = hint.min_value.min(event.value);
+        hint.max_value = hint.max_value.max(event.value);
+        hint.intermediate_seen |= event.value > 0 && event.value < 127;
+
+        let kind = match event.msg_type {
+            MidiMessageType::Note => return,
+            MidiMessageType::PitchBend
+            | MidiMessageType::ChannelPressure
+            | MidiMessageType::PolyAftertouch => ControlKind::Fader,
+            MidiMessageType::ProgramChange => ControlKind::Button,
+            MidiMessageType::ControlChange if hint.intermediate_seen => ControlKind::Fader,
+            // Only call it a button once both extremes have been seen.
+            MidiMessageType::ControlChange if hint.min_value == 0 && hint.max_value == 127 => {
+                ControlKind::Button
+            }
+            MidiMessageType::ControlChange => return,
+        };
+        if hint.kind != Some(kind) {
+            hint.kind = Some(kind);
+            self.dirty = true;
+        }
+    }
+
+    pub fn mark(&mut self, control: &LearnedControl, kind: ControlKind) {
+        let hint = self
+            .hints
+            .entry(control_key(control))
+            .or_insert(ControlHint {
+                kind: None,
+                min_value: 0,
+                max_value: 0,
+                intermediate_seen: false,
+            });
+        if hint.kind != Some(kind) {
+            hint.kind = Some(kind);
+            self.dirty = true;
+        }
+    }
+
+    pub fn save_if_dirty(&mut self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed creating {}", parent.display()))?;
+        }
+        let data = serde_json::to_string_pretty(&self.hints)?;
+        fs::write(&self.path, data)
+            .with_context(|| format!("Failed writing {}", self.path.display()))?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    pub fn clear(&mut self) -> Result<()> {
+        self.hints.clear();
+        self.dirty = false;
+        if self.path.exists() {
+            fs::remove_file(&self.path)
+                .with_context(|| format!("Failed deleting {}", self.path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn control(device_id: &str, msg_type: MidiMessageType, controller: u8) -> LearnedControl {
+        LearnedControl {
+            device_id: device_id.to_string(),
+            channel: 0,
+            controller,
+            msg_type,
+            control_kind: None,
+        }
+    }
+
+    #[test]
+    fn fader_replaces_a_buffered_touch_note() {
+        let touch = control("dev", MidiMessageType::Note, 104);
+        let fader = control("dev", MidiMessageType::PitchBend, 0xE0);
+        assert_eq!(
+            choose_candidate(Some(&touch), &fader),
+            CandidateChoice::Replace
+        );
+        assert_eq!(
+            touch_note(&touch, &fader).map(|note| note.controller),
+            Some(104)
+        );
+    }
+
+    #[test]
+    fn touch_note_after_fader_keeps_the_fader() {
+        let fader = control("dev", MidiMessageType::ControlChange, 7);
+        let touch = control("dev", MidiMessageType::Note, 104);
+        assert_eq!(
+            choose_candidate(Some(&fader), &touch),
+            CandidateChoice::Keep
+        );
+        assert_eq!(
+            touch_note(&fader, &touch).map(|note| note.controller),
+            Some(104)
+        );
+    }
+
+    #[test]
+    fn repeats_keep_the_window_and_other_devices_compete_normally() {
+        let fader = control("dev", MidiMessageType::ControlChange, 7);
+        assert_eq!(choose_candidate(None, &fader), CandidateChoice::Replace);
+        assert_eq!(
+            choose_candidate(Some(&fader), &fader),
+            CandidateChoice::Keep
+        );
+
+        let other_note = control("other", MidiMessageType::Note, 60);
+        assert_eq!(
+            choose_candidate(Some(&fader), &other_note),
+            CandidateChoice::Replace
+        );
+        assert!(touch_note(&fader, &other_note).is_none());
+
+        let button = control("dev", MidiMessageType::Note, 60);
+        let second_button = control("dev", MidiMessageType::Note, 61);
+        assert_eq!(
+            choose_candidate(Some(&button), &second_button),
+            CandidateChoice::Replace
+        );
+        assert!(touch_note(&button, &second_button).is_none());
+    }
+
+    #[test]
+    fn notes_wait_at_least_the_preference_window() {
+        let note = control("dev", MidiMessageType::Note, 60);
+        let cc = control("dev", MidiMessageType::ControlChange, 7);
+        let learn_window = Duration::from_millis(150);
+        assert_eq!(
+            candidate_window(&note, learn_window),
+            LEARN_NOTE_PREFERENCE_WINDOW
+        );
+        assert_eq!(candidate_window(&cc, learn_window), learn_window);
+        let long_window = Duration::from_millis(600);
+        assert_eq!(candidate_window(&note, long_window), long_window);
+    }
+}
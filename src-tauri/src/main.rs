@@ -1,28 +1,51 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+// Declared first so its macros are visible to the modules below.
+#[macro_use]
+mod logging;
+
+mod app_match;
 mod app_paths;
 mod app_settings;
 mod audio;
 mod bindings;
 mod commands;
+mod hotkeys;
+mod learn_hints;
 mod midi;
 mod model;
+mod osd_layout;
 mod plugin_api;
+mod plugin_settings;
+mod profile_merge;
 mod profile_store;
+mod profile_switch;
 mod store_api;
 mod windows_autostart;
 mod windows_display;
 mod ws_bridge;
 
+use app_match::{
+    find_application_session, find_application_target, find_session_target, for_each_application,
+    resolve_target_session, with_application,
+};
 use app_paths::app_data_root_dir;
-use app_settings::{AppSettings, AppSettingsStore};
+use app_settings::{AppSettings, AppSettingsStore, HotkeyAction};
 use audio::AudioBackend;
-use bindings::{apply_midi_event, find_binding, BindingKey, BindingState};
+use bindings::{
+    apply_midi_event, balance_position, balance_volumes, button_edge, find_binding, is_bounce,
+    on_layer, BindingKey, BindingState, ButtonEdge, SMOOTHING_SETTLE_DELAY,
+};
 use commands::*;
-use midi::MidiManager;
-use model::{LearnedControl, MidiEvent, OsdSettings, Profile};
+use learn_hints::ControlHintStore;
+use midi::{HighResPair, MidiManager};
+use model::{
+    LearnedControl, MidiEvent, OsdSettings, PlaybackDeviceInfo, Profile, SessionInfo, TargetState,
+};
+use osd_layout::{osd_window_position, osd_window_size, MonitorGeometry};
+use profile_switch::ForegroundTracker;
 use windows_autostart::set_windows_autostart;
-use windows_display::{display_device_id, monitor_display_name};
+use windows_display::{display_device_id, monitor_display_name, monitor_edid_id};
 
 #[derive(Clone, Copy)]
 enum DeviceTargetKind {
@@ -30,6 +53,19 @@ enum DeviceTargetKind {
     Recording,
 }
 
+/// Looks up a device by raw id, resolving `COMMUNICATIONS_DEVICE_ID` and
+/// `DEFAULT_DEVICE_ID` to the current Communications and default endpoints.
+fn find_device<'a>(
+    devices: &'a [PlaybackDeviceInfo],
+    raw_id: &str,
+) -> Option<&'a PlaybackDeviceInfo> {
+    devices.iter().find(|device| {
+        device.id == raw_id
+            || (raw_id == audio::COMMUNICATIONS_DEVICE_ID && device.is_comms_default)
+            || (raw_id == audio::DEFAULT_DEVICE_ID && device.is_default)
+    })
+}
+
 fn parse_device_target(device_id: &str) -> (DeviceTargetKind, &str) {
     if let Some(raw) = device_id.strip_prefix("recording:") {
         return (DeviceTargetKind::Recording, raw);
@@ -41,15 +77,15 @@ fn parse_device_target(device_id: &str) -> (DeviceTargetKind, &str) {
 }
 
 use profile_store::ProfileStore;
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use tauri::menu::{Menu, MenuEvent, MenuItem};
 use tauri::tray::TrayIconBuilder;
 use tauri::{
-    AppHandle, Emitter, LogicalPosition, LogicalSize, Manager, WebviewUrl, WebviewWindowBuilder,
+    AppHandle, Emitter, Manager, Monitor, PhysicalPosition, PhysicalSize, WebviewUrl,
+    WebviewWindow, WebviewWindowBuilder,
 };
 use tokio::time::sleep;
 
@@ -57,13 +93,18 @@ use plugin_api::{
     ensure_builtin_plugin, get_plugins_dir, install_plugin_package, list_plugins,
     read_plugin_base64, read_plugin_text, set_plugin_enabled, uninstall_plugin,
 };
-use store_api::{fetch_store_catalog, install_store_plugin};
-use ws_bridge::{ws_close, ws_open, ws_send, WsHub};
+use store_api::{
+    check_plugin_updates, fetch_store_catalog, install_store_plugin, set_store_catalog_urls,
+};
+use ws_bridge::{ws_close, ws_open, ws_send, ws_send_binary, WsHub};
 
 #[cfg(target_os = "windows")]
 use audio::windows::WindowsAudioBackend;
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(target_os = "linux")]
+use audio::linux::LinuxAudioBackend;
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
 use audio::unsupported::UnsupportedAudioBackend;
 
 struct AppState {
@@ -77,23 +118,441 @@ struct AppState {
     learn_pending: Mutex<bool>,
     learn_candidate: Mutex<Option<(LearnedControl, Instant)>>,
     learned_control: Mutex<Option<LearnedControl>>,
+    control_hints: Mutex<ControlHintStore>,
+    unlearn_pending: Mutex<bool>,
     osd_last_update: Mutex<Option<Instant>>,
     osd_settings: Mutex<OsdSettings>,
+    /// Rendered card height (logical px) reported by each OSD webview, keyed by window label.
+    osd_content_heights: Mutex<HashMap<String, f64>>,
     app_settings: Mutex<AppSettings>,
+    midi_activity: Mutex<MidiActivity>,
+    audio_snapshot: Mutex<AudioSnapshot>,
+    ptt_generations: Mutex<HashMap<BindingKey, u64>>,
+    solo: Mutex<Option<SoloState>>,
+    /// Pre-duck volume of each engaged Duck binding.
+    ducked_volumes: Mutex<HashMap<BindingKey, f32>>,
+    active_layer: Mutex<u8>,
+    /// Press start of bindings with a long-press action, until their release.
+    press_starts: Mutex<HashMap<BindingKey, Instant>>,
+    /// First tap of bindings with a double-tap action, waiting out the window.
+    pending_taps: Mutex<HashMap<BindingKey, u64>>,
+    tap_generation: Mutex<u64>,
+    /// Coalesced `volume_update`/`mute_update` events, keyed by event and target.
+    ui_updates: Mutex<HashMap<String, UiUpdateSlot>>,
+}
+
+/// The last UI event sent for one target and the one waiting to replace it.
+#[derive(Default)]
+struct UiUpdateSlot {
+    last_emit: Option<Instant>,
+    pending: Option<(model::Binding, serde_json::Value)>,
+}
+
+/// An engaged Solo binding and the mute states it overrode.
+struct SoloState {
+    binding_id: String,
+    /// The soloed application, which the solo never mutes.
+    target: String,
+    /// Pre-solo mute state of each application the solo touched. Applications
+    /// that appear mid-solo are added (and muted) as they show up.
+    snapshot: Vec<(String, bool)>,
+}
+
+/// A control moved this recently is left out of feedback snapshots, so a fader
+/// being touched while a profile loads isn't yanked away.
+const USER_ACTIVITY_GRACE: Duration = Duration::from_millis(500);
+
+/// Controller is considered idle after this long without input.
+const MIDI_IDLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Even with change notifications, audio state is re-read this often so focus
+/// changes and newly added bindings still get feedback.
+const AUDIO_RESYNC_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Minimum gap between audio resyncs, so a burst of change notifications (a
+/// device being reconfigured fires dozens) collapses into one re-read.
+const AUDIO_RESYNC_MIN_GAP: Duration = Duration::from_millis(50);
+
+#[derive(Default)]
+struct MidiActivity {
+    last_event: Option<Instant>,
+    active: bool,
+}
+
+/// Session and device lists from the last feedback sync, reused to label OSD
+/// updates without another audio API round-trip per fader move.
+#[derive(Default)]
+struct AudioSnapshot {
+    sessions: Vec<SessionInfo>,
+    system_sounds: Option<SessionInfo>,
+    playback_devices: Vec<PlaybackDeviceInfo>,
+    recording_devices: Vec<PlaybackDeviceInfo>,
+}
+
+impl AudioSnapshot {
+    /// Resolves a target against these lists. `focused` is only called for Focus
+    /// targets, which aren't part of the snapshot.
+    fn target_state(
+        &self,
+        target: &model::BindingTarget,
+        focused: impl FnOnce() -> Option<SessionInfo>,
+    ) -> Option<TargetState> {
+        let of_session = |session: &SessionInfo| TargetState {
+            volume: session.volume,
+            muted: session.is_muted,
+        };
+        match target {
+            model::BindingTarget::Master
+            | model::BindingTarget::Session { .. }
+            | model::BindingTarget::Application { .. } => {
+                resolve_target_session(&self.sessions, target).map(of_session)
+            }
+            model::BindingTarget::Focus => focused().as_ref().map(of_session),
+            model::BindingTarget::SystemSounds => self.system_sounds.as_ref().map(of_session),
+            model::BindingTarget::DefaultMicrophone | model::BindingTarget::DefaultSpeakers => {
+                self.target_state(&target.default_device()?, focused)
+            }
+            model::BindingTarget::ApplicationBalance { left, right } => {
+                let left = find_application_session(&self.sessions, left)?;
+                let right = find_application_session(&self.sessions, right)?;
+                Some(TargetState {
+                    volume: balance_position(left.volume, right.volume),
+                    muted: left.is_muted && right.is_muted,
+                })
+            }
+            // A group reports its first running member's volume: the members all get
+            // the same level, and an average would jump whenever one starts or quits.
+            // It counts as muted only when every running member is.
+            model::BindingTarget::ApplicationGroup { names } => {
+                let members: Vec<&SessionInfo> = names
+                    .iter()
+                    .filter_map(|name| find_application_session(&self.sessions, name))
+                    .collect();
+                let first = members.first()?;
+                Some(TargetState {
+                    volume: first.volume,
+                    muted: members.iter().all(|session| session.is_muted),
+                })
+            }
+            model::BindingTarget::Device { device_id } => {
+                let (kind, raw_id) = parse_device_target(device_id);
+                let devices = match kind {
+                    DeviceTargetKind::Playback => &self.playback_devices,
+                    DeviceTargetKind::Recording => &self.recording_devices,
+                };
+                find_device(devices, raw_id).map(|device| TargetState {
+                    volume: device.volume,
+                    muted: device.is_muted,
+                })
+            }
+            model::BindingTarget::Unset => None,
+            model::BindingTarget::Integration { .. } => None,
+        }
+    }
+}
+
+/// Label prefix for OSD windows created from `OsdSettings::instances`.
+const OSD_INSTANCE_PREFIX: &str = "osd-";
+
+/// Meter OSD windows are refreshed at most this often.
+const OSD_METER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An unchanged meter payload is still re-sent this often, so a window that was
+/// just created doesn't stay empty.
+const OSD_METER_REFRESH: Duration = Duration::from_secs(2);
+
+/// Feedback for a mute button: lit while muted, except push-to-talk, which is
+/// lit while talking.
+fn mute_feedback(binding: &model::Binding, muted: bool) -> f32 {
+    let lit = muted != (binding.action == model::BindingAction::PushToTalk);
+    if lit {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// `volume_update`/`mute_update` events and their OSD updates are sent at most
+/// this often per target; a fader sweep only shows its latest value.
+const UI_UPDATE_INTERVAL: Duration = Duration::from_millis(30);
+
+/// Feedback this close to the value last sent to a control isn't sent again,
+/// the same tolerance `update_midi_feedback` uses.
+const FEEDBACK_DELTA: f32 = 0.005;
+
+/// Unchanged feedback is still re-sent this often, so a controller that was
+/// power-cycled or moved by something else catches up.
+const FEEDBACK_KEEPALIVE: Duration = Duration::from_secs(5);
+
+/// Whether the background loop should send `value` to the control behind `key`,
+/// recording it as sent if so.
+fn feedback_due(
+    last_sent: &mut HashMap<BindingKey, (f32, Instant)>,
+    key: &BindingKey,
+    value: f32,
+) -> bool {
+    let due = last_sent.get(key).is_none_or(|(sent, at)| {
+        (sent - value).abs() >= FEEDBACK_DELTA || at.elapsed() >= FEEDBACK_KEEPALIVE
+    });
+    if due {
+        last_sent.insert(key.clone(), (value, Instant::now()));
+    }
+    due
+}
+
+/// How often the monitor layout is checked so OSD windows can be moved back to
+/// their saved monitor after a display is reconnected.
+const MONITOR_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What the background loop last pushed to each meter OSD window.
+#[derive(Default)]
+struct OsdMeterState {
+    last_tick: Option<Instant>,
+    pushed: HashMap<String, (String, Instant)>,
+}
+
+fn osd_instance_label(id: &str) -> String {
+    let sanitized: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}{}", OSD_INSTANCE_PREFIX, sanitized)
+}
+
+fn build_osd_window(app: &AppHandle, label: &str) -> tauri::Result<WebviewWindow> {
+    let osd_window =
+        WebviewWindowBuilder::new(app, label, WebviewUrl::App("index.html?osd=1".into()))
+            .title("MIDIMaster OSD")
+            .decorations(false)
+            .transparent(true)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .resizable(false)
+            .focused(false)
+            .shadow(false)
+            .inner_size(320.0, 120.0)
+            .build()?;
+    let _ = osd_window.set_ignore_cursor_events(true);
+    let _ = osd_window.hide();
+    Ok(osd_window)
+}
+
+fn meter_osd_labels(settings: &OsdSettings) -> Vec<String> {
+    settings
+        .instances
+        .iter()
+        .filter(|instance| instance.meter)
+        .map(|instance| osd_instance_label(&instance.id))
+        .collect()
+}
+
+/// `x,y,WxH` in physical pixels, stored with OSD placements.
+fn monitor_geometry(monitor: &Monitor) -> String {
+    format!(
+        "{},{},{}x{}",
+        monitor.position().x,
+        monitor.position().y,
+        monitor.size().width,
+        monitor.size().height
+    )
+}
+
+/// The monitor an OSD placement was saved on, matched by EDID id, device id,
+/// geometry and finally name. `None` when it isn't connected (or nothing was
+/// saved, in which case the index is used).
+fn saved_osd_monitor<'a>(monitors: &'a [Monitor], settings: &OsdSettings) -> Option<&'a Monitor> {
+    let raw_name = |monitor: &Monitor| monitor.name().cloned().unwrap_or_default();
+    let find = |saved: &Option<String>, key: &dyn Fn(&Monitor) -> Option<String>| {
+        let saved = saved.as_ref()?;
+        monitors
+            .iter()
+            .find(|monitor| key(monitor).as_ref() == Some(saved))
+    };
+    find(&settings.monitor_id, &|monitor| {
+        let raw_name = raw_name(monitor);
+        Some(display_device_id(&raw_name).unwrap_or(raw_name))
+    })
+    .or_else(|| {
+        // Two identical panels share an EDID id, so it only identifies a
+        // monitor when no other connected one has it.
+        let saved = settings.monitor_edid.as_ref()?;
+        let mut matches = monitors
+            .iter()
+            .filter(|monitor| monitor_edid_id(&raw_name(monitor)).as_ref() == Some(saved));
+        let first = matches.next()?;
+        matches.next().is_none().then_some(first)
+    })
+    .or_else(|| {
+        find(&settings.monitor_geometry, &|monitor| {
+            Some(monitor_geometry(monitor))
+        })
+    })
+    .or_else(|| {
+        find(&settings.monitor_name, &|monitor| {
+            let raw_name = raw_name(monitor);
+            Some(monitor_display_name(&raw_name).unwrap_or(raw_name))
+        })
+    })
+}
+
+/// Reports each OSD whose saved monitor isn't connected, so it's clear why the
+/// OSD moved to another screen. Emits `osd_monitor_missing` (possibly with an
+/// empty list, once every monitor is back) so the UI can show or clear a notice.
+fn warn_missing_osd_monitors(app: &AppHandle, settings: &OsdSettings) {
+    let Ok(monitors) = app.available_monitors() else {
+        return;
+    };
+    let placements = std::iter::once(("osd".to_string(), settings.clone())).chain(
+        settings.instances.iter().map(|instance| {
+            (
+                osd_instance_label(&instance.id),
+                instance.placement(settings),
+            )
+        }),
+    );
+    let mut missing = Vec::new();
+    for (label, placement) in placements {
+        let saved = placement
+            .monitor_name
+            .as_ref()
+            .or(placement.monitor_id.as_ref())
+            .or(placement.monitor_edid.as_ref())
+            .or(placement.monitor_geometry.as_ref());
+        if let Some(saved) = saved {
+            if saved_osd_monitor(&monitors, &placement).is_none() {
+                log_warn!(
+                    "OSD: Monitor {} for {} not found, using monitor {}",
+                    saved,
+                    label,
+                    placement.monitor_index + 1
+                );
+                missing.push(serde_json::json!({
+                    "label": label,
+                    "monitor": saved,
+                    "fallback_index": placement.monitor_index,
+                }));
+            }
+        }
+    }
+    let _ = app.emit(
+        "osd_monitor_missing",
+        serde_json::json!({ "missing": missing }),
+    );
+}
+
+/// Position and size of every connected monitor; changes when one is plugged
+/// in, unplugged or rearranged.
+fn monitor_layout(app: &AppHandle) -> Option<String> {
+    let monitors = app.available_monitors().ok()?;
+    Some(
+        monitors
+            .iter()
+            .map(|monitor| {
+                format!(
+                    "{}@{}",
+                    monitor.name().cloned().unwrap_or_default(),
+                    monitor_geometry(monitor)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(";"),
+    )
+}
+
+/// The primary OSD window plus every instance window.
+fn osd_windows(app: &AppHandle) -> Vec<WebviewWindow> {
+    app.webview_windows()
+        .into_iter()
+        .filter(|(label, _)| label == "osd" || label.starts_with(OSD_INSTANCE_PREFIX))
+        .map(|(_, window)| window)
+        .collect()
 }
 
 impl AppState {
     fn apply_osd_settings(app: &AppHandle, settings: &OsdSettings) {
-        let Some(osd_window) = app.get_webview_window("osd") else {
+        if settings.enabled {
+            warn_missing_osd_monitors(app, settings);
+        }
+        if let Some(osd_window) = app.get_webview_window("osd") {
+            Self::place_osd_window(app, &osd_window, settings);
+        }
+
+        // Windows must be created on the main thread; building them from a
+        // synchronous command deadlocks on Windows.
+        let app_handle = app.clone();
+        let settings = settings.clone();
+        let _ = app.run_on_main_thread(move || {
+            let wanted: Vec<String> = settings
+                .instances
+                .iter()
+                .map(|instance| osd_instance_label(&instance.id))
+                .collect();
+            for (label, window) in app_handle.webview_windows() {
+                if label.starts_with(OSD_INSTANCE_PREFIX) && !wanted.contains(&label) {
+                    let _ = window.close();
+                }
+            }
+            for (instance, label) in settings.instances.iter().zip(wanted.iter()) {
+                let window = match app_handle.get_webview_window(label) {
+                    Some(window) => window,
+                    None => match build_osd_window(&app_handle, label) {
+                        Ok(window) => window,
+                        Err(_) => continue,
+                    },
+                };
+                Self::place_osd_window(&app_handle, &window, &instance.placement(&settings));
+                // Meter windows never auto-hide; the others only show on updates.
+                if instance.meter && settings.enabled {
+                    let _ = window.show();
+                } else {
+                    let _ = window.hide();
+                }
+            }
+        });
+    }
+
+    /// Re-anchors a single OSD window, e.g. after its content height changed.
+    fn reposition_osd_window(app: &AppHandle, label: &str) {
+        let Some(osd_window) = app.get_webview_window(label) else {
+            return;
+        };
+        let Ok(settings) = app
+            .state::<AppState>()
+            .osd_settings
+            .lock()
+            .map(|settings| settings.clone())
+        else {
             return;
         };
+        let placement = if label == "osd" {
+            settings
+        } else {
+            match settings
+                .instances
+                .iter()
+                .find(|instance| osd_instance_label(&instance.id) == label)
+            {
+                Some(instance) => instance.placement(&settings),
+                None => return,
+            }
+        };
+        Self::place_osd_window(app, &osd_window, &placement);
+    }
 
+    fn place_osd_window(app: &AppHandle, osd_window: &WebviewWindow, settings: &OsdSettings) {
         if !settings.enabled {
             let _ = osd_window.hide();
             return;
         }
 
         let _ = osd_window.set_always_on_top(true);
+        Self::push_osd_appearance(osd_window, settings);
 
         // Force topmost on Windows using native API for fullscreen game compatibility
         #[cfg(target_os = "windows")]
@@ -118,127 +577,1069 @@ impl AppState {
             }
         }
 
-        let monitor = app
-            .available_monitors()
-            .ok()
-            .and_then(|monitors| {
-                // First try to find by monitor_id if provided
-                if let Some(ref id) = settings.monitor_id {
-                    if let Some(m) = monitors.iter().find(|m| {
-                        let raw_name = m.name().cloned().unwrap_or_default();
-                        let m_id = display_device_id(&raw_name).unwrap_or_else(|| raw_name);
-                        m_id == *id
-                    }) {
-                        return Some(m.clone());
-                    }
-                }
-                // Then try to find by name if provided (legacy)
-                if let Some(ref name) = settings.monitor_name {
-                    if let Some(m) = monitors.iter().find(|m| {
-                        let raw_name = m.name().cloned().unwrap_or_default();
-                        let m_name = monitor_display_name(&raw_name).unwrap_or_else(|| raw_name);
-                        m_name == *name
-                    }) {
-                        return Some(m.clone());
-                    }
-                }
-                // Fall back to index
-                monitors
-                    .get(settings.monitor_index)
-                    .cloned()
-                    .or_else(|| monitors.first().cloned())
-            })
+        let monitors = app.available_monitors().unwrap_or_default();
+        let monitor = saved_osd_monitor(&monitors, settings)
+            .or_else(|| monitors.get(settings.monitor_index))
+            .or_else(|| monitors.first())
+            .cloned()
             .or_else(|| app.primary_monitor().ok().flatten());
 
         if let Some(monitor) = monitor {
-            let scale_factor = monitor.scale_factor();
-            let size = monitor.size();
-            let position = monitor.position();
-            let width = 320.0;
-            let height = 800.0;
-            let padding = 24.0;
-            let logical_width = size.width as f64 / scale_factor;
-            let logical_height = size.height as f64 / scale_factor;
-            let origin_x = position.x as f64 / scale_factor;
-            let origin_y = position.y as f64 / scale_factor;
-            let anchor = settings.anchor.as_str();
-            let (mut x, mut y) = match anchor {
-                "top-left" => (origin_x + padding, origin_y + padding),
-                "top-center" => (origin_x + (logical_width - width) / 2.0, origin_y + padding),
-                "top-right" => (
-                    origin_x + logical_width - width - padding,
-                    origin_y + padding,
-                ),
-                "center-left" => (
-                    origin_x + padding,
-                    origin_y + (logical_height - height) / 2.0,
-                ),
-                "center" => (
-                    origin_x + (logical_width - width) / 2.0,
-                    origin_y + (logical_height - height) / 2.0,
-                ),
-                "center-right" => (
-                    origin_x + logical_width - width - padding,
-                    origin_y + (logical_height - height) / 2.0,
-                ),
-                "bottom-left" => (
-                    origin_x + padding,
-                    origin_y + logical_height - height - padding,
-                ),
-                "bottom-center" => (
-                    origin_x + (logical_width - width) / 2.0,
-                    origin_y + logical_height - height - padding,
-                ),
-                "bottom-right" => (
-                    origin_x + logical_width - width - padding,
-                    origin_y + logical_height - height - padding,
-                ),
-                _ => (
-                    origin_x + logical_width - width - padding,
-                    origin_y + padding,
-                ),
+            let geometry = MonitorGeometry {
+                x: monitor.position().x,
+                y: monitor.position().y,
+                width: monitor.size().width,
+                height: monitor.size().height,
+                scale_factor: monitor.scale_factor(),
             };
-            x = x.max(origin_x + padding);
-            y = y.max(origin_y + padding);
-            let _ = osd_window.set_size(LogicalSize::new(width, height));
-            let _ = osd_window.set_position(LogicalPosition::new(x, y));
+            // Shrink to the cards actually shown so center/bottom anchors line up
+            // with the visible content rather than the empty part of the window.
+            let height = app
+                .state::<AppState>()
+                .osd_content_heights
+                .lock()
+                .ok()
+                .and_then(|heights| heights.get(osd_window.label()).copied())
+                .map(|content| content.min(settings.height))
+                .unwrap_or(settings.height);
+            let (x, y) = osd_window_position(&geometry, &settings.anchor, settings.width, height);
+            let (width, height) = osd_window_size(&geometry, settings.width, height);
+            let _ = osd_window.set_size(PhysicalSize::new(width, height));
+            let _ = osd_window.set_position(PhysicalPosition::new(x, y));
         }
     }
 
-    fn apply_app_settings(_app: &AppHandle, settings: &AppSettings) {
+    /// Hands the styling part of the settings to the OSD webview. A window that is
+    /// still loading misses this and reads `get_osd_settings` itself on startup.
+    fn push_osd_appearance(osd_window: &WebviewWindow, settings: &OsdSettings) {
+        let appearance = serde_json::json!({
+            "opacity": settings.opacity,
+            "accent_color": settings.accent_color,
+        });
+        let script = format!(
+            "window.__OSD_APPEARANCE__ && window.__OSD_APPEARANCE__({});",
+            appearance
+        );
+        let _ = osd_window.eval(&script);
+    }
+
+    fn apply_app_settings(app: &AppHandle, settings: &AppSettings) {
         #[cfg(target_os = "windows")]
         {
             let _ = set_windows_autostart(settings.start_with_windows);
         }
+        hotkeys::register_hotkeys(app, &settings.hotkeys);
+    }
+
+    fn target_alias(&self, target: &model::BindingTarget) -> Option<String> {
+        let profile = self.active_profile.lock().ok()?;
+        profile.as_ref()?.alias_for_target(target)
+    }
+
+    fn apply_session_aliases(&self, sessions: &mut [SessionInfo]) {
+        let Ok(profile) = self.active_profile.lock() else {
+            return;
+        };
+        let Some(profile) = profile.as_ref() else {
+            return;
+        };
+        for session in sessions.iter_mut() {
+            session.alias = profile.alias_for_session(session);
+        }
+    }
+
+    fn apply_device_aliases(&self, devices: &mut [PlaybackDeviceInfo], recording: bool) {
+        let Ok(profile) = self.active_profile.lock() else {
+            return;
+        };
+        let Some(profile) = profile.as_ref() else {
+            return;
+        };
+        for device in devices.iter_mut() {
+            device.alias = profile.alias_for_device(&device.id, recording);
+        }
+    }
+
+    /// Display name and icon for an OSD payload, from the last synced audio lists.
+    /// Focus targets carry their session in `focus_session` instead.
+    fn target_display(&self, target: &model::BindingTarget) -> (Option<String>, Option<String>) {
+        let default_device = target.default_device();
+        let target = default_device.as_ref().unwrap_or(target);
+        let Ok(snapshot) = self.audio_snapshot.lock() else {
+            return (None, None);
+        };
+        let from_session = |session: &SessionInfo| {
+            (
+                Some(session.display_name.clone()),
+                session.icon_data.clone(),
+            )
+        };
+        let from_device = |device: &PlaybackDeviceInfo| {
+            (Some(device.display_name.clone()), device.icon_data.clone())
+        };
+        match target {
+            model::BindingTarget::Master => (Some("Master".to_string()), None),
+            model::BindingTarget::SystemSounds => (Some("System Sounds".to_string()), None),
+            model::BindingTarget::Session { .. } | model::BindingTarget::Application { .. } => {
+                resolve_target_session(&snapshot.sessions, target)
+                    .map(from_session)
+                    .unwrap_or_default()
+            }
+            model::BindingTarget::Device { device_id } => {
+                let (kind, raw_id) = parse_device_target(device_id);
+                let devices = match kind {
+                    DeviceTargetKind::Playback => &snapshot.playback_devices,
+                    DeviceTargetKind::Recording => &snapshot.recording_devices,
+                };
+                find_device(devices, raw_id)
+                    .map(from_device)
+                    .unwrap_or_default()
+            }
+            _ => (None, None),
+        }
+    }
+
+    /// Pushes the level of every matching volume binding to the meter OSD
+    /// windows. Payloads that haven't changed are skipped so the webviews aren't
+    /// flooded on every tick.
+    fn push_osd_meters(&self, app: &AppHandle, profile: &Profile, meters: &mut OsdMeterState) {
+        if meters
+            .last_tick
+            .is_some_and(|last| last.elapsed() < OSD_METER_INTERVAL)
+        {
+            return;
+        }
+        meters.last_tick = Some(Instant::now());
+
+        let Ok(settings) = self.osd_settings.lock().map(|settings| settings.clone()) else {
+            return;
+        };
+        let labels = if settings.enabled {
+            meter_osd_labels(&settings)
+        } else {
+            Vec::new()
+        };
+        meters.pushed.retain(|label, _| labels.contains(label));
+        if labels.is_empty() {
+            return;
+        }
+
+        // Integrations aren't in the audio snapshot; their last reported value is.
+        let feedback = self
+            .feedback_values
+            .lock()
+            .map(|values| values.clone())
+            .unwrap_or_default();
+        let levels: Vec<(&model::Binding, TargetState)> = {
+            let Ok(snapshot) = self.audio_snapshot.lock() else {
+                return;
+            };
+            profile
+                .bindings
+                .iter()
+                .filter(|binding| binding.action == model::BindingAction::Volume)
+                .filter_map(|binding| {
+                    let level = match &binding.target {
+                        model::BindingTarget::Integration { .. } => feedback
+                            .get(&BindingKey::from_binding(binding))
+                            .map(|volume| TargetState {
+                                volume: *volume,
+                                muted: false,
+                            }),
+                        target => snapshot.target_state(target, || None),
+                    }?;
+                    Some((binding, level))
+                })
+                .collect()
+        };
+
+        for instance in settings.instances.iter().filter(|instance| instance.meter) {
+            let mut seen = HashSet::new();
+            let entries: Vec<serde_json::Value> = levels
+                .iter()
+                .filter(|(binding, _)| instance.matches(binding))
+                .filter(|(binding, _)| {
+                    binding
+                        .target
+                        .alias_key()
+                        .is_none_or(|key| seen.insert(key))
+                })
+                .map(|(binding, level)| {
+                    let (display_name, icon_data) = self.target_display(&binding.target);
+                    serde_json::json!({
+                      "binding_id": binding.id,
+                      "target": binding.target,
+                      "volume": level.volume,
+                      "muted": level.muted,
+                      "alias": self.target_alias(&binding.target),
+                      "display_name": display_name,
+                      "icon_data": icon_data,
+                    })
+                })
+                .collect();
+            let Ok(payload) = serde_json::to_string(&entries) else {
+                continue;
+            };
+            let label = osd_instance_label(&instance.id);
+            if meters.pushed.get(&label).is_some_and(|(last, pushed_at)| {
+                *last == payload && pushed_at.elapsed() < OSD_METER_REFRESH
+            }) {
+                continue;
+            }
+            if let Some(window) = app.get_webview_window(&label) {
+                let script = format!("window.__OSD_METER__ && window.__OSD_METER__({});", payload);
+                let _ = window.eval(&script);
+                meters.pushed.insert(label, (payload, Instant::now()));
+            }
+        }
+    }
+
+    fn focused_session_with_alias(&self) -> Option<SessionInfo> {
+        let mut session = self.audio.focused_session().ok().flatten()?;
+        self.apply_session_aliases(std::slice::from_mut(&mut session));
+        Some(session)
+    }
+
+    /// Mute state of a ToggleMute binding that shares this binding's target, if any.
+    fn companion_muted(&self, binding: &model::Binding) -> Option<bool> {
+        let profile = self.active_profile.lock().ok()?;
+        let companion = profile.as_ref()?.bindings.iter().find(|other| {
+            other.id != binding.id
+                && matches!(
+                    other.action,
+                    model::BindingAction::ToggleMute | model::BindingAction::PushToTalk
+                )
+                && other.target == binding.target
+        })?;
+        let key = BindingKey::from_binding(companion);
+        let feedback = self.feedback_values.lock().ok()?;
+        Some(
+            feedback
+                .get(&key)
+                .map(|value| *value > 0.5)
+                .unwrap_or(false),
+        )
+    }
+
+    fn record_midi_activity(&self, app: &AppHandle) {
+        let became_active = match self.midi_activity.lock() {
+            Ok(mut activity) => {
+                activity.last_event = Some(Instant::now());
+                !std::mem::replace(&mut activity.active, true)
+            }
+            Err(_) => false,
+        };
+        if became_active {
+            let _ = app.emit("midi_activity", serde_json::json!({ "active": true }));
+        }
+    }
+
+    fn check_midi_idle(&self, app: &AppHandle) {
+        let became_idle = match self.midi_activity.lock() {
+            Ok(mut activity) => {
+                let idle = activity
+                    .last_event
+                    .map(|time| time.elapsed() > MIDI_IDLE_TIMEOUT)
+                    .unwrap_or(true);
+                if activity.active && idle {
+                    activity.active = false;
+                    true
+                } else {
+                    false
+                }
+            }
+            Err(_) => false,
+        };
+        if became_idle {
+            let _ = app.emit("midi_activity", serde_json::json!({ "active": false }));
+        }
+    }
+
+    /// Records a binding's new mute state and pushes it to the controller, UI and OSD.
+    fn publish_mute_state(
+        &self,
+        app: &AppHandle,
+        binding: &model::Binding,
+        key: &BindingKey,
+        muted: bool,
+    ) {
+        if let Ok(mut last_update) = self.osd_last_update.lock() {
+            *last_update = Some(Instant::now());
+        }
+
+        let value = mute_feedback(binding, muted);
+        if let Ok(mut feedback) = self.feedback_values.lock() {
+            feedback.insert(key.clone(), value);
+        }
+
+        // println!("MIDI Event Matched Binding: {:?} -> {:?}", binding.name, binding.target);
+        self.send_feedback(binding, value);
+
+        let focus_session = if matches!(&binding.target, model::BindingTarget::Focus) {
+            self.focused_session_with_alias()
+        } else {
+            None
+        };
+
+        let (display_name, icon_data) = self.target_display(&binding.target);
+        let payload = serde_json::json!({
+          "target": binding.target,
+          "muted": muted,
+          "action": "toggle_mute",
+          "focus_session": focus_session,
+          "alias": self.target_alias(&binding.target),
+          "display_name": display_name,
+          "icon_data": icon_data,
+        });
+        self.emit_ui_update(app, "mute_update", binding, payload);
+    }
+
+    /// Sends `event` to the UI and the payload to the OSD. Within
+    /// `UI_UPDATE_INTERVAL` of the previous one for the same target, only the
+    /// latest payload is kept and sent once the interval is over.
+    fn emit_ui_update(
+        &self,
+        app: &AppHandle,
+        event: &'static str,
+        binding: &model::Binding,
+        payload: serde_json::Value,
+    ) {
+        let key = format!(
+            "{}:{}",
+            event,
+            serde_json::to_string(&binding.target).unwrap_or_default()
+        );
+        let Ok(mut slots) = self.ui_updates.lock() else {
+            return;
+        };
+        let slot = slots.entry(key.clone()).or_default();
+        let wait = slot
+            .last_emit
+            .map(|last| UI_UPDATE_INTERVAL.saturating_sub(last.elapsed()))
+            .unwrap_or_default();
+        if wait.is_zero() {
+            slot.last_emit = Some(Instant::now());
+            drop(slots);
+            let _ = app.emit(event, payload.clone());
+            self.emit_osd(app, binding, &payload);
+            return;
+        }
+        let scheduled = slot.pending.replace((binding.clone(), payload)).is_some();
+        drop(slots);
+        if scheduled {
+            return;
+        }
+
+        let app_handle = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(wait).await;
+            let state = app_handle.state::<AppState>();
+            let pending = state.ui_updates.lock().ok().and_then(|mut slots| {
+                let slot = slots.get_mut(&key)?;
+                let pending = slot.pending.take()?;
+                slot.last_emit = Some(Instant::now());
+                Some(pending)
+            });
+            if let Some((binding, payload)) = pending {
+                let _ = app_handle.emit(event, payload.clone());
+                state.emit_osd(&app_handle, &binding, &payload);
+            }
+        });
+    }
+
+    /// Shows an OSD update on every OSD window whose filter matches the binding.
+    /// The primary window shows whatever no filtered instance has claimed.
+    fn emit_osd(&self, app: &AppHandle, binding: &model::Binding, payload: &serde_json::Value) {
+        if !binding.show_osd {
+            return;
+        }
+        let Ok(settings) = self.osd_settings.lock().map(|settings| settings.clone()) else {
+            return;
+        };
+        if !settings.enabled {
+            return;
+        }
+
+        let matching: Vec<&model::OsdInstance> = settings
+            .instances
+            .iter()
+            .filter(|instance| !instance.meter && instance.matches(binding))
+            .collect();
+        let mut labels: Vec<String> = matching
+            .iter()
+            .map(|instance| osd_instance_label(&instance.id))
+            .collect();
+        if !matching.iter().any(|instance| instance.has_filter()) {
+            labels.push("osd".to_string());
+        }
+
+        let Ok(payload_json) = serde_json::to_string(payload) else {
+            return;
+        };
+        let script = format!(
+            "window.__OSD_UPDATE__ && window.__OSD_UPDATE__({});",
+            payload_json
+        );
+        for label in labels {
+            if let Some(osd_window) = app.get_webview_window(&label) {
+                let _ = osd_window.show();
+                let _ = osd_window.eval(&script);
+            }
+        }
+    }
+
+    /// Sets an absolute mute state; returns false for targets that cannot be muted directly.
+    fn set_target_mute(&self, target: &model::BindingTarget, muted: bool) -> Result<bool, String> {
+        let result = match target {
+            model::BindingTarget::Master => self.audio.set_master_mute(muted),
+            model::BindingTarget::SystemSounds => self.audio.set_system_sounds_mute(muted),
+            model::BindingTarget::DefaultMicrophone | model::BindingTarget::DefaultSpeakers => {
+                return match target.default_device() {
+                    Some(device) => self.set_target_mute(&device, muted),
+                    None => Ok(false),
+                };
+            }
+            model::BindingTarget::Focus => self
+                .audio
+                .set_focused_session_mute(muted, self.focus_all_sessions()),
+            model::BindingTarget::Session { session_id, path } => self
+                .audio
+                .set_session_mute(&self.live_session_id(session_id, path.as_deref()), muted),
+            model::BindingTarget::Application { name, path } => {
+                with_application(name, path.as_deref(), |app| {
+                    self.audio.set_application_mute(app, muted)
+                })
+            }
+            model::BindingTarget::Device { device_id } => {
+                self.audio.set_device_mute(device_id, muted)
+            }
+            model::BindingTarget::ApplicationBalance { left, right } => self
+                .audio
+                .set_application_mute(left, muted)
+                .and_then(|_| self.audio.set_application_mute(right, muted)),
+            model::BindingTarget::ApplicationGroup { names } => {
+                for_each_application(names, |app| self.audio.set_application_mute(app, muted))
+            }
+            model::BindingTarget::Integration { .. } | model::BindingTarget::Unset => {
+                return Ok(false)
+            }
+        };
+        result.map_err(|err| err.to_string())?;
+        Ok(true)
+    }
+
+    /// Invalidates any pending push-to-talk release timer for the binding.
+    fn next_ptt_generation(&self, key: &BindingKey) -> u64 {
+        let Ok(mut generations) = self.ptt_generations.lock() else {
+            return 0;
+        };
+        let generation = generations.entry(key.clone()).or_insert(0);
+        *generation += 1;
+        *generation
+    }
+
+    fn handle_push_to_talk(
+        &self,
+        app: &AppHandle,
+        binding: &model::Binding,
+        key: &BindingKey,
+        edge: ButtonEdge,
+    ) -> Result<(), String> {
+        let generation = self.next_ptt_generation(key);
+
+        if edge == ButtonEdge::Press || binding.release_delay_ms == 0 {
+            let muted = edge == ButtonEdge::Release;
+            if self.set_target_mute(&binding.target, muted)? {
+                self.publish_mute_state(app, binding, key, muted);
+                self.resend_feedback_after_edge(binding, key);
+            }
+            if !muted && binding.ptt_max_hold_ms > 0 {
+                self.schedule_ptt_safety_mute(app, binding, key, generation);
+            }
+            return Ok(());
+        }
+
+        // Keep the mic open briefly after release unless the button is pressed again
+        let app_handle = app.clone();
+        let binding = binding.clone();
+        let key = key.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(binding.release_delay_ms)).await;
+
+            let state = app_handle.state::<AppState>();
+            let still_pending = state
+                .ptt_generations
+                .lock()
+                .map(|generations| generations.get(&key) == Some(&generation))
+                .unwrap_or(false);
+            if !still_pending {
+                return;
+            }
+            if let Ok(true) = state.set_target_mute(&binding.target, true) {
+                state.publish_mute_state(&app_handle, &binding, &key, true);
+            }
+        });
+        Ok(())
+    }
+
+    /// Re-mutes a push-to-talk target that is still held `ptt_max_hold_ms` after
+    /// the press, so a lost Note-off doesn't leave the mic open. The button is
+    /// treated as released, so the next Note-on talks again.
+    fn schedule_ptt_safety_mute(
+        &self,
+        app: &AppHandle,
+        binding: &model::Binding,
+        key: &BindingKey,
+        generation: u64,
+    ) {
+        let app_handle = app.clone();
+        let binding = binding.clone();
+        let key = key.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(binding.ptt_max_hold_ms)).await;
+
+            let state = app_handle.state::<AppState>();
+            let still_held = state
+                .ptt_generations
+                .lock()
+                .map(|generations| generations.get(&key) == Some(&generation))
+                .unwrap_or(false);
+            if !still_held {
+                return;
+            }
+            log_warn!(
+                "PTT: {} held for {} ms without a release, muting",
+                binding.name,
+                binding.ptt_max_hold_ms
+            );
+            if let Ok(mut states) = state.binding_state.lock() {
+                if let Some(binding_state) = states.get_mut(&key) {
+                    binding_state.pressed = false;
+                }
+            }
+            if let Ok(true) = state.set_target_mute(&binding.target, true) {
+                state.publish_mute_state(&app_handle, &binding, &key, true);
+            }
+        });
+    }
+
+    /// Re-sends a button's feedback shortly after a press or release, for
+    /// controllers that switch the LED themselves when the button changes.
+    fn resend_feedback_after_edge(&self, binding: &model::Binding, key: &BindingKey) {
+        let key = key.clone();
+        let feedback_arc = self.feedback_values.clone();
+        let midi_arc = self.midi.clone();
+        let binding = binding.clone();
+
+        tauri::async_runtime::spawn(async move {
+            // Sleep for 20ms to allow the hardware to process the "Note Off" completely
+            tokio::time::sleep(Duration::from_millis(20)).await;
+
+            if let Ok(feedback) = feedback_arc.lock() {
+                let current_val = feedback.get(&key).cloned().unwrap_or(0.0);
+                if let Ok(mut midi) = midi_arc.lock() {
+                    let _ = midi.send_binding_feedback(&binding, current_val);
+                }
+            }
+        });
+    }
+
+    fn handle_solo(
+        &self,
+        app: &AppHandle,
+        profile: &Profile,
+        binding: &model::Binding,
+    ) -> Result<(), String> {
+        let target = match &binding.target {
+            model::BindingTarget::Application { name, .. } => name.clone(),
+            model::BindingTarget::Focus => {
+                let focused = self.audio.focused_session().ok().flatten();
+                match focused.and_then(|session| session.process_name) {
+                    Some(name) => name.strip_suffix(".exe").unwrap_or(&name).to_string(),
+                    None => return Ok(()),
+                }
+            }
+            _ => return Ok(()),
+        };
+
+        let previous = self.solo.lock().map_err(|_| "Lock poisoned")?.take();
+        if let Some(previous) = previous {
+            self.restore_solo(&previous);
+            if let Some(previous_binding) = profile
+                .bindings
+                .iter()
+                .find(|candidate| candidate.id == previous.binding_id)
+            {
+                self.publish_solo_state(app, previous_binding, false);
+            }
+            if previous.binding_id == binding.id {
+                return Ok(());
+            }
+        }
+
+        let sessions = self.audio.list_sessions().map_err(|err| err.to_string())?;
+        let mut solo = SoloState {
+            binding_id: binding.id.clone(),
+            target: target.clone(),
+            snapshot: Vec::new(),
+        };
+        if let Some(session) = find_application_session(&sessions, &target) {
+            solo.snapshot.push((target.clone(), session.is_muted));
+            if session.is_muted {
+                self.audio
+                    .set_application_mute(&target, false)
+                    .map_err(|err| err.to_string())?;
+            }
+        }
+        self.mute_solo_members(profile, binding, &sessions, &mut solo);
+        *self.solo.lock().map_err(|_| "Lock poisoned")? = Some(solo);
+        self.publish_solo_state(app, binding, true);
+        Ok(())
+    }
+
+    /// Applications a Solo binding mutes: those targeted by its group, or every
+    /// application session when it has no group.
+    fn solo_members(
+        profile: &Profile,
+        binding: &model::Binding,
+        sessions: &[SessionInfo],
+    ) -> Vec<String> {
+        let Some(group) = binding.mute_group.as_deref() else {
+            return sessions
+                .iter()
+                .filter(|session| !session.is_master)
+                .filter_map(|session| {
+                    session
+                        .process_name
+                        .as_deref()
+                        .map(|name| name.strip_suffix(".exe").unwrap_or(name).to_string())
+                })
+                .collect();
+        };
+        let mut members: Vec<String> = Vec::new();
+        for member in profile
+            .bindings
+            .iter()
+            .filter(|member| member.mute_group.as_deref() == Some(group))
+        {
+            let names = match &member.target {
+                model::BindingTarget::Application { name, .. } => vec![name.clone()],
+                model::BindingTarget::ApplicationBalance { left, right } => {
+                    vec![left.clone(), right.clone()]
+                }
+                model::BindingTarget::ApplicationGroup { names } => names.clone(),
+                _ => continue,
+            };
+            for name in names {
+                if !members
+                    .iter()
+                    .any(|existing| existing.eq_ignore_ascii_case(&name))
+                {
+                    members.push(name);
+                }
+            }
+        }
+        members
+    }
+
+    /// Mutes running group members the solo hasn't touched yet, recording their prior state.
+    fn mute_solo_members(
+        &self,
+        profile: &Profile,
+        binding: &model::Binding,
+        sessions: &[SessionInfo],
+        solo: &mut SoloState,
+    ) {
+        for name in Self::solo_members(profile, binding, sessions) {
+            if name.eq_ignore_ascii_case(&solo.target)
+                || solo
+                    .snapshot
+                    .iter()
+                    .any(|(existing, _)| existing.eq_ignore_ascii_case(&name))
+            {
+                continue;
+            }
+            let Some(session) = find_application_session(sessions, &name) else {
+                continue;
+            };
+            solo.snapshot.push((name.clone(), session.is_muted));
+            if !session.is_muted {
+                let _ = self.audio.set_application_mute(&name, true);
+            }
+        }
+    }
+
+    /// Puts back the pre-solo mute states. Applications that have since closed are skipped.
+    fn restore_solo(&self, solo: &SoloState) {
+        for (name, was_muted) in &solo.snapshot {
+            let _ = self.audio.set_application_mute(name, *was_muted);
+        }
+    }
+
+    /// Keeps an engaged solo exclusive as applications start, and releases it if
+    /// its binding was removed.
+    fn enforce_solo(&self, profile: &Profile) {
+        let Ok(mut guard) = self.solo.lock() else {
+            return;
+        };
+        let Some(solo) = guard.as_mut() else {
+            return;
+        };
+        let Some(binding) = profile
+            .bindings
+            .iter()
+            .find(|binding| binding.id == solo.binding_id)
+        else {
+            if let Some(solo) = guard.take() {
+                self.restore_solo(&solo);
+            }
+            return;
+        };
+        if let Ok(sessions) = self.audio.list_sessions() {
+            self.mute_solo_members(profile, binding, &sessions, solo);
+        }
+    }
+
+    fn active_layer(&self) -> u8 {
+        self.active_layer.lock().map(|layer| *layer).unwrap_or(0)
+    }
+
+    /// Sends controller feedback unless the binding has feedback disabled or sits
+    /// on an inactive layer, whose values would overwrite what the control shows.
+    fn send_feedback(&self, binding: &model::Binding, value: f32) {
+        if !binding.feedback_enabled || !on_layer(binding, self.active_layer()) {
+            return;
+        }
+        if let Ok(mut midi) = self.midi.lock() {
+            let _ = midi.send_binding_feedback(binding, value);
+        }
+    }
+
+    /// Live peak level of a meter-feedback binding's target.
+    fn target_peak(&self, target: &model::BindingTarget) -> Option<f32> {
+        let peak = match target {
+            model::BindingTarget::Master => self.audio.master_peak(),
+            model::BindingTarget::SystemSounds => {
+                let session = self.audio.system_sounds_session().ok().flatten()?;
+                self.audio.session_peak(&session.id)
+            }
+            model::BindingTarget::Session { session_id, path } => self
+                .audio
+                .session_peak(&self.live_session_id(session_id, path.as_deref())),
+            model::BindingTarget::Focus => {
+                let session = self.audio.focused_session().ok().flatten()?;
+                self.audio.session_peak(&session.id)
+            }
+            model::BindingTarget::Application { name, path } => {
+                let sessions = self.audio.list_sessions().ok()?;
+                let session = find_application_target(&sessions, name, path.as_deref())?;
+                self.audio.session_peak(&session.id)
+            }
+            _ => return None,
+        };
+        peak.ok()
+    }
+
+    /// Loads the profile whose rules match a newly focused app and tells the UI.
+    fn auto_switch_profile(&self, app: &AppHandle, process_path: &str) {
+        let Ok(profiles) = self.profile_store.load_all() else {
+            return;
+        };
+        let active = self
+            .active_profile
+            .lock()
+            .ok()
+            .and_then(|profile| profile.clone());
+        let Some(target) =
+            profile_switch::auto_switch_target(&profiles, active.as_ref(), process_path)
+        else {
+            return;
+        };
+        if let Err(err) = self.switch_profile(app, &target) {
+            log_warn!("Profiles: Auto-switch to {} failed: {}", target, err);
+        }
+    }
+
+    /// Loads a profile from the backend side and tells the UI to follow.
+    fn switch_profile(&self, app: &AppHandle, name: &str) -> Result<(), String> {
+        let profile = commands::activate_profile(app, self, name)?;
+        let _ = app.emit(
+            "profile_switched",
+            serde_json::json!({ "name": profile.name }),
+        );
+        Ok(())
+    }
+
+    /// Runs the hotkey registered under `shortcut_id` through the same dispatch
+    /// as a MIDI button, so OSD, feedback and button modes behave identically.
+    fn run_hotkey(&self, app: &AppHandle, shortcut_id: u32, pressed: bool) {
+        let hotkeys = self
+            .app_settings
+            .lock()
+            .map(|settings| settings.hotkeys.clone())
+            .unwrap_or_default();
+        let Some(index) = hotkeys::hotkey_index(&hotkeys, shortcut_id) else {
+            return;
+        };
+        let hotkey = &hotkeys[index];
+        let result = match &hotkey.action {
+            HotkeyAction::LoadProfile { name } if pressed => self.switch_profile(app, name),
+            HotkeyAction::LoadProfile { .. } => Ok(()),
+            HotkeyAction::Binding {
+                target,
+                action,
+                button_mode,
+            } => {
+                let profile = self
+                    .active_profile
+                    .lock()
+                    .ok()
+                    .and_then(|profile| profile.clone());
+                let Some(profile) = profile else {
+                    return;
+                };
+                let binding = hotkeys::hotkey_binding(index, target, action, *button_mode);
+                let key = BindingKey::from_binding(&binding);
+                let event = hotkeys::hotkey_event(&binding, pressed);
+                self.dispatch_binding(app, &profile, binding, key, event)
+            }
+        };
+        if let Err(err) = result {
+            log_warn!("Hotkeys: {} failed: {}", hotkey.shortcut, err);
+        }
+    }
+
+    /// Replays the last message of smoothed controls that have gone quiet, so their
+    /// output keeps gliding until it reaches where the fader actually is.
+    fn settle_smoothing(&self, app: &AppHandle, profile: &Profile) {
+        let pending: Vec<(BindingKey, MidiEvent)> = match self.binding_state.lock() {
+            Ok(states) => states
+                .iter()
+                .filter_map(|(key, state)| {
+                    let event = state.smoothing_event.as_ref()?;
+                    (state.last_user_activity.elapsed() >= SMOOTHING_SETTLE_DELAY)
+                        .then(|| (key.clone(), event.clone()))
+                })
+                .collect(),
+            Err(_) => return,
+        };
+        for (key, event) in pending {
+            match find_binding(profile, &key) {
+                Some(binding) => {
+                    let _ = self.dispatch_binding(app, profile, binding.clone(), key, event);
+                }
+                // The binding was removed or belongs to another profile.
+                None => {
+                    if let Ok(mut states) = self.binding_state.lock() {
+                        if let Some(state) = states.get_mut(&key) {
+                            state.smoothing_event = None;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Switches layers and re-sends feedback so motor faders and LEDs show the new layer.
+    fn set_active_layer(&self, app: &AppHandle, profile: &Profile, layer: u8) {
+        match self.active_layer.lock() {
+            Ok(mut active) if *active != layer => *active = layer,
+            _ => return,
+        }
+        self.sync_feedback_values(profile);
+        let feedback = self
+            .feedback_values
+            .lock()
+            .map(|values| values.clone())
+            .unwrap_or_default();
+        if let Ok(mut midi) = self.midi.lock() {
+            for binding in profile
+                .bindings
+                .iter()
+                .filter(|binding| on_layer(binding, layer))
+            {
+                let value = feedback
+                    .get(&BindingKey::from_binding(binding))
+                    .cloned()
+                    .unwrap_or(0.0);
+                let _ = midi.send_binding_feedback(binding, value);
+            }
+        }
+        let _ = app.emit("layer_changed", serde_json::json!({ "layer": layer }));
+    }
+
+    /// Sends the current value of every binding on the active layer right away,
+    /// so motor faders and LEDs match the audio state as soon as a profile loads
+    /// or a device starts instead of on the next change.
+    fn send_feedback_snapshot(&self, profile: &Profile) {
+        self.sync_feedback_values(profile);
+        let feedback = self
+            .feedback_values
+            .lock()
+            .map(|values| values.clone())
+            .unwrap_or_default();
+        let touched: HashSet<BindingKey> = self
+            .binding_state
+            .lock()
+            .map(|states| {
+                states
+                    .iter()
+                    .filter(|(_, state)| state.last_user_activity.elapsed() < USER_ACTIVITY_GRACE)
+                    .map(|(key, _)| key.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let layer = self.active_layer();
+        let Ok(mut midi) = self.midi.lock() else {
+            return;
+        };
+        // Meter bindings are refreshed by the main loop on their own interval.
+        for binding in profile
+            .bindings
+            .iter()
+            .filter(|binding| on_layer(binding, layer) && !binding.meter_feedback)
+        {
+            let key = BindingKey::from_binding(binding);
+            if touched.contains(&key) {
+                continue;
+            }
+            if let Some(value) = feedback.get(&key) {
+                let _ = midi.send_binding_feedback(binding, *value);
+            }
+        }
+    }
+
+    fn publish_solo_state(&self, app: &AppHandle, binding: &model::Binding, active: bool) {
+        let value = if active { 1.0 } else { 0.0 };
+        if let Ok(mut feedback) = self.feedback_values.lock() {
+            feedback.insert(BindingKey::from_binding(binding), value);
+        }
+        self.send_feedback(binding, value);
+        let payload = serde_json::json!({
+          "binding_id": binding.id,
+          "target": binding.target,
+          "active": active,
+        });
+        let _ = app.emit("solo_update", payload);
+    }
+
+    /// Marks user activity (to prevent a stale feedback loop) and detects press/release.
+    fn button_edge(
+        &self,
+        key: &BindingKey,
+        binding: &model::Binding,
+        value: u8,
+    ) -> Result<Option<ButtonEdge>, String> {
+        let mut states = self.binding_state.lock().map_err(|_| "Lock poisoned")?;
+        let state = states.entry(key.clone()).or_insert_with(BindingState::new);
+        state.last_user_activity = Instant::now();
+        Ok(button_edge(binding, value, state))
+    }
+
+    fn focus_all_sessions(&self) -> bool {
+        self.app_settings
+            .lock()
+            .map(|settings| !settings.focus_primary_session_only)
+            .unwrap_or(true)
+    }
+
+    fn in_startup_grace(&self) -> bool {
+        let grace_ms = self
+            .app_settings
+            .lock()
+            .map(|settings| settings.startup_grace_ms)
+            .unwrap_or(0);
+        if grace_ms == 0 {
+            return false;
+        }
+        self.midi
+            .lock()
+            .ok()
+            .and_then(|midi| midi.connected_at())
+            .map(|connected_at| connected_at.elapsed() < Duration::from_millis(grace_ms))
+            .unwrap_or(false)
+    }
+
+    /// Consumes a pending "unbind on next input" request.
+    fn take_unlearn_pending(&self) -> bool {
+        self.unlearn_pending
+            .lock()
+            .map(|mut pending| std::mem::take(&mut *pending))
+            .unwrap_or(false)
     }
 
     fn apply_midi_event(&self, app: &AppHandle, event: MidiEvent) -> Result<(), String> {
+        // Ignore the burst of state some controllers dump right after connecting
+        if self.in_startup_grace() {
+            return Ok(());
+        }
+
+        if let Ok(mut hints) = self.control_hints.lock() {
+            hints.observe(&event);
+        }
+
         let mut learn_pending = self.learn_pending.lock().map_err(|_| "Lock poisoned")?;
         if *learn_pending {
+            let mut hints = self.control_hints.lock().map_err(|_| "Lock poisoned")?;
             let msg_type = event.msg_type.clone();
-            let learned = LearnedControl {
+            let mut learned = LearnedControl {
                 device_id: event.device_id.clone(),
                 channel: event.channel,
                 controller: event.controller,
                 msg_type: msg_type.clone(),
+                control_kind: None,
             };
+            learned.control_kind = hints.kind(&learned);
+
+            // Known touch-sense notes never win; wait for the fader itself.
+            if learned.control_kind == Some(model::ControlKind::Touch) {
+                return Ok(());
+            }
 
-            if matches!(msg_type, model::MidiMessageType::Note) {
-                // Buffer note events as candidates to filter out touch-sense faders
+            // Unclassified notes may be touch sensors and unclassified CCs may be
+            // buttons sending 127/0, so both are buffered for their window.
+            // Known buttons skip the disambiguation window.
+            let buffer = learned.control_kind.is_none()
+                && matches!(
+                    msg_type,
+                    model::MidiMessageType::Note | model::MidiMessageType::ControlChange
+                );
+            if buffer {
                 if let Ok(mut candidate) = self.learn_candidate.lock() {
-                    *candidate = Some((learned, Instant::now()));
+                    let current = candidate.as_ref().map(|(current, _)| current);
+                    if let Some(touch) =
+                        current.and_then(|current| learn_hints::touch_note(current, &learned))
+                    {
+                        hints.mark(touch, model::ControlKind::Touch);
+                    }
+                    if learn_hints::choose_candidate(current, &learned)
+                        == learn_hints::CandidateChoice::Replace
+                    {
+                        *candidate = Some((learned, Instant::now()));
+                    }
                 }
                 return Ok(());
             }
 
-            // Immediate accept for non-Note events (CC, PitchBend)
+            // Immediate accept for classified CCs, PitchBend, Program Change... and known buttons
             *learn_pending = false;
             drop(learn_pending);
 
-            // Clear any pending candidate
+            // A note buffered just before fader movement was its touch sensor.
+            // Pad pressure follows its own note-on, so that note stays a button.
             if let Ok(mut candidate) = self.learn_candidate.lock() {
-                *candidate = None;
+                if let Some((note, _)) = candidate.take() {
+                    if note.msg_type == model::MidiMessageType::Note
+                        && note.device_id == learned.device_id
+                        && msg_type != model::MidiMessageType::Note
+                        && msg_type != model::MidiMessageType::PolyAftertouch
+                    {
+                        hints.mark(&note, model::ControlKind::Touch);
+                    }
+                }
             }
+            let _ = hints.save_if_dirty();
+            drop(hints);
 
             *self.learned_control.lock().map_err(|_| "Lock poisoned")? = Some(learned.clone());
             // println!(
@@ -257,7 +1658,7 @@ impl AppState {
             Some(profile) => profile,
             None => return Ok(()),
         };
-        let key = BindingKey::from_event(&event);
+        let key = BindingKey::from_event(&event, self.active_layer());
         // println!("Looking for binding: Key={:?} DeviceID={}", key, event.device_id);
         let binding = match find_binding(&profile, &key) {
             Some(binding) => {
@@ -272,13 +1673,143 @@ impl AppState {
                 return Ok(());
             }
         };
+        // A Shift binding found from another layer keeps its state under its own key.
+        let key = BindingKey::from_binding(&binding);
+        // Checked once per incoming message, so the press and release that
+        // long-press and double-tap replay aren't mistaken for bounce.
+        {
+            let mut states = self.binding_state.lock().map_err(|_| "Lock poisoned")?;
+            let state = states.entry(key.clone()).or_insert_with(BindingState::new);
+            if is_bounce(&binding, &event, state) {
+                return Ok(());
+            }
+        }
+
+        if self.take_unlearn_pending() {
+            let app_handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app_handle.state::<AppState>();
+                if remove_binding(state, binding.clone()).await.is_ok() {
+                    let _ = app_handle.emit("binding_unlearned", binding);
+                }
+            });
+            return Ok(());
+        }
+
+        if let Some(long_action) = binding.long_action.clone() {
+            let edge = match self.button_edge(&key, &binding, event.value)? {
+                Some(edge) => edge,
+                None => return Ok(()),
+            };
+            let mut press_starts = self.press_starts.lock().map_err(|_| "Lock poisoned")?;
+            let action = match edge {
+                ButtonEdge::Press => {
+                    // Key-repeat Note-ons keep the original press time.
+                    press_starts.entry(key).or_insert_with(Instant::now);
+                    return Ok(());
+                }
+                ButtonEdge::Release => match press_starts.remove(&key) {
+                    Some(start)
+                        if start.elapsed() >= Duration::from_millis(binding.long_press_ms) =>
+                    {
+                        long_action
+                    }
+                    Some(_) => binding.action.clone(),
+                    None => return Ok(()),
+                },
+            };
+            drop(press_starts);
+            return self.tap_binding(app, &profile, &binding, action, key, event);
+        }
+
+        if let Some(double_action) = binding.double_action.clone() {
+            if self.button_edge(&key, &binding, event.value)? != Some(ButtonEdge::Press) {
+                return Ok(());
+            }
+            let mut taps = self.pending_taps.lock().map_err(|_| "Lock poisoned")?;
+            if taps.remove(&key).is_some() {
+                drop(taps);
+                return self.tap_binding(app, &profile, &binding, double_action, key, event);
+            }
+            let generation = self.next_tap_generation();
+            taps.insert(key.clone(), generation);
+            drop(taps);
+
+            // No second press within the window: run the single-tap action.
+            let window = self
+                .app_settings
+                .lock()
+                .map(|settings| settings.double_tap_ms)
+                .unwrap_or(300);
+            let app_handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(window)).await;
+                let state = app_handle.state::<AppState>();
+                let expired = match state.pending_taps.lock() {
+                    Ok(mut taps) if taps.get(&key) == Some(&generation) => {
+                        taps.remove(&key);
+                        true
+                    }
+                    _ => false,
+                };
+                if expired {
+                    let action = binding.action.clone();
+                    let _ = state.tap_binding(&app_handle, &profile, &binding, action, key, event);
+                }
+            });
+            return Ok(());
+        }
+
+        self.dispatch_binding(app, &profile, binding, key, event)
+    }
+
+    fn next_tap_generation(&self) -> u64 {
+        let Ok(mut generation) = self.tap_generation.lock() else {
+            return 0;
+        };
+        *generation += 1;
+        *generation
+    }
+
+    /// Replays `action` on the binding as a full press and release.
+    fn tap_binding(
+        &self,
+        app: &AppHandle,
+        profile: &Profile,
+        binding: &model::Binding,
+        action: model::BindingAction,
+        key: BindingKey,
+        event: MidiEvent,
+    ) -> Result<(), String> {
+        let resolved = model::Binding {
+            action,
+            long_action: None,
+            double_action: None,
+            ..binding.clone()
+        };
+        let press = MidiEvent {
+            value: 127,
+            is_note_off: false,
+            release_velocity: None,
+            ..event.clone()
+        };
+        self.dispatch_binding(app, profile, resolved.clone(), key.clone(), press)?;
+        let release = MidiEvent { value: 0, ..event };
+        self.dispatch_binding(app, profile, resolved, key, release)
+    }
 
+    /// Runs a binding's action for an incoming event.
+    fn dispatch_binding(
+        &self,
+        app: &AppHandle,
+        profile: &Profile,
+        binding: model::Binding,
+        key: BindingKey,
+        event: MidiEvent,
+    ) -> Result<(), String> {
         let volume = {
             let mut states = self.binding_state.lock().map_err(|_| "Lock poisoned")?;
-            let state = states.entry(key.clone()).or_insert_with(|| BindingState {
-                last_value: 0.0,
-                last_update: Instant::now(),
-            });
+            let state = states.entry(key.clone()).or_insert_with(BindingState::new);
             apply_midi_event(&binding, &event, state)
         };
 
@@ -287,45 +1818,139 @@ impl AppState {
             None => return Ok(()),
         };
 
+        if let model::BindingAction::RouteToDevice { device_id } = &binding.action {
+            if self.button_edge(&key, &binding, event.value)? != Some(ButtonEdge::Press) {
+                return Ok(());
+            }
+            let (app_name, app_path) = match &binding.target {
+                model::BindingTarget::Application { name, path } => (name.clone(), path.clone()),
+                model::BindingTarget::Focus => {
+                    let focused = self.audio.focused_session().ok().flatten();
+                    match focused.and_then(|session| session.process_name) {
+                        Some(name) => {
+                            (name.strip_suffix(".exe").unwrap_or(&name).to_string(), None)
+                        }
+                        None => return Ok(()),
+                    }
+                }
+                _ => return Ok(()),
+            };
+            with_application(&app_name, app_path.as_deref(), |app| {
+                self.audio.set_application_output_device(app, device_id)
+            })
+            .map_err(|err| err.to_string())?;
+            let payload = serde_json::json!({
+              "binding_id": binding.id,
+              "target": binding.target,
+              "device_id": device_id,
+            });
+            let _ = app.emit("route_update", payload);
+            return Ok(());
+        }
+
+        if binding.action == model::BindingAction::CycleDefaultDevice {
+            if self.button_edge(&key, &binding, event.value)? != Some(ButtonEdge::Press) {
+                return Ok(());
+            }
+            return self.cycle_default_device(app, &binding);
+        }
+
+        if binding.action == model::BindingAction::Balance {
+            let device_id = match &binding.target {
+                model::BindingTarget::Master => "",
+                model::BindingTarget::Device { device_id } => device_id.as_str(),
+                _ => return Ok(()),
+            };
+            let balance = volume * 2.0 - 1.0;
+            self.audio
+                .set_balance(device_id, balance)
+                .map_err(|err| err.to_string())?;
+            if let Ok(mut feedback) = self.feedback_values.lock() {
+                feedback.insert(key.clone(), volume);
+            }
+            self.send_feedback(&binding, volume);
+            let payload = serde_json::json!({
+              "binding_id": binding.id,
+              "target": binding.target,
+              "balance": balance,
+            });
+            let _ = app.emit("balance_update", payload);
+            return Ok(());
+        }
+
+        if let model::BindingAction::Shift { layer, latch } = binding.action {
+            let edge = match self.button_edge(&key, &binding, event.value)? {
+                Some(edge) => edge,
+                None => return Ok(()),
+            };
+            let active = self.active_layer();
+            let next = match (edge, latch) {
+                (ButtonEdge::Press, true) if active == layer => 0,
+                (ButtonEdge::Press, _) => layer,
+                (ButtonEdge::Release, false) if active == layer => 0,
+                (ButtonEdge::Release, _) => return Ok(()),
+            };
+            self.set_active_layer(app, profile, next);
+            return Ok(());
+        }
+
+        if binding.action == model::BindingAction::Solo {
+            if self.button_edge(&key, &binding, event.value)? != Some(ButtonEdge::Press) {
+                return Ok(());
+            }
+            return self.handle_solo(app, profile, &binding);
+        }
+
+        if binding.action == model::BindingAction::PushToTalk {
+            return match self.button_edge(&key, &binding, event.value)? {
+                Some(edge) => self.handle_push_to_talk(app, &binding, &key, edge),
+                None => Ok(()),
+            };
+        }
+
         // Handle toggle mute action for button bindings
         if binding.action == model::BindingAction::ToggleMute {
-            // Mark user activity to prevent stale feedback loop
-            if let Ok(mut states) = self.binding_state.lock() {
-                if let Some(state) = states.get_mut(&key) {
-                    state.last_update = Instant::now();
+            let edge = match self.button_edge(&key, &binding, event.value)? {
+                Some(edge) => edge,
+                None => return Ok(()),
+            };
+
+            // Momentary buttons mute while held. The release sets the real state, so the
+            // latching re-send below must not run for them.
+            if binding.button_mode == model::ButtonMode::Momentary {
+                let muted = edge == ButtonEdge::Press;
+                if let model::BindingTarget::Integration {
+                    integration_id,
+                    kind,
+                    data,
+                } = &binding.target
+                {
+                    let value = if muted { 1.0 } else { 0.0 };
+                    if let Ok(mut feedback) = self.feedback_values.lock() {
+                        feedback.insert(key.clone(), value);
+                    }
+                    self.send_feedback(&binding, value);
+                    let payload = serde_json::json!({
+                      "binding_id": binding.id,
+                      "action": "ToggleMute",
+                      "value": value,
+                      "target": {
+                        "integration_id": integration_id,
+                        "kind": kind,
+                        "data": data,
+                      }
+                    });
+                    let _ = app.emit("integration_binding_triggered", payload);
+                } else if self.set_target_mute(&binding.target, muted)? {
+                    self.publish_mute_state(app, &binding, &key, muted);
                 }
+                return Ok(());
             }
 
-            // On button release (value == 0), re-send current state to enforce latching check
+            // On button release, re-send current state to enforce latching check
             // This fixes controllers that turn off LED on release (momentary behavior)
-            if event.value == 0 {
-                let key_clone = key.clone();
-                // Clone Arcs for async task
-                let feedback_arc = self.feedback_values.clone();
-                let midi_arc = self.midi.clone();
-
-                let device_id = binding.device_id.clone();
-                let channel = binding.control.channel;
-                let controller = binding.control.controller;
-                let msg_type = binding.control.msg_type.clone();
-
-                tauri::async_runtime::spawn(async move {
-                    // Sleep for 20ms to allow the hardware to process the "Note Off" completely
-                    tokio::time::sleep(Duration::from_millis(20)).await;
-
-                    if let Ok(feedback) = feedback_arc.lock() {
-                        let current_val = feedback.get(&key_clone).cloned().unwrap_or(0.0);
-                        if let Ok(mut midi) = midi_arc.lock() {
-                            let _ = midi.send_feedback(
-                                &device_id,
-                                channel,
-                                controller,
-                                current_val,
-                                msg_type,
-                            );
-                        }
-                    }
-                });
+            if edge == ButtonEdge::Release {
+                self.resend_feedback_after_edge(&binding, &key);
                 return Ok(());
             }
 
@@ -340,60 +1965,74 @@ impl AppState {
                         .map_err(|err| err.to_string())?;
                     new_muted
                 }
+                model::BindingTarget::SystemSounds => {
+                    let Some(session) = self.audio.system_sounds_session().ok().flatten() else {
+                        return Ok(());
+                    };
+                    let new_muted = !session.is_muted;
+                    self.audio
+                        .set_system_sounds_mute(new_muted)
+                        .map_err(|err| err.to_string())?;
+                    new_muted
+                }
                 model::BindingTarget::Focus => {
                     if let Some(focused) = self.audio.focused_session().ok().flatten() {
                         let new_muted = !focused.is_muted;
                         self.audio
-                            .set_focused_session_mute(new_muted)
+                            .set_focused_session_mute(new_muted, self.focus_all_sessions())
                             .map_err(|err| err.to_string())?;
                         new_muted
                     } else {
                         return Ok(());
                     }
                 }
-                model::BindingTarget::Application { name } => {
+                model::BindingTarget::ApplicationGroup { names } => {
+                    let Some(state) = self
+                        .read_audio_snapshot()
+                        .map_err(|err| err.to_string())?
+                        .target_state(&binding.target, || None)
+                    else {
+                        return Ok(());
+                    };
+                    let new_muted = !state.muted;
+                    for_each_application(names, |app| {
+                        self.audio.set_application_mute(app, new_muted)
+                    })
+                    .map_err(|err| err.to_string())?;
+                    new_muted
+                }
+                model::BindingTarget::Application { name, path } => {
                     let sessions = self.audio.list_sessions().map_err(|err| err.to_string())?;
-                    let target = name.to_lowercase();
-                    let session = sessions.iter().find(|session| {
-                        if let Some(path) = &session.process_path {
-                            if let Some(stem) = std::path::Path::new(path)
-                                .file_stem()
-                                .and_then(|s| s.to_str())
-                            {
-                                if stem.to_lowercase() == target {
-                                    return true;
-                                }
-                            }
-                        }
-                        if let Some(name) = &session.process_name {
-                            let stem = name.strip_suffix(".exe").unwrap_or(name);
-                            if stem.to_lowercase() == target {
-                                return true;
-                            }
-                        }
-                        session.display_name.to_lowercase() == target
-                    });
-                    if let Some(session) = session {
+                    if let Some(session) = find_application_target(&sessions, name, path.as_deref())
+                    {
                         let new_muted = !session.is_muted;
-                        self.audio
-                            .set_application_mute(name, new_muted)
-                            .map_err(|err| err.to_string())?;
+                        with_application(name, path.as_deref(), |app| {
+                            self.audio.set_application_mute(app, new_muted)
+                        })
+                        .map_err(|err| err.to_string())?;
                         new_muted
                     } else {
                         return Ok(());
                     }
                 }
+                model::BindingTarget::DefaultMicrophone | model::BindingTarget::DefaultSpeakers => {
+                    let Some(state) = self
+                        .target_state(&binding.target)
+                        .map_err(|err| err.to_string())?
+                    else {
+                        return Ok(());
+                    };
+                    let new_muted = !state.muted;
+                    self.set_target_mute(&binding.target, new_muted)?;
+                    new_muted
+                }
                 model::BindingTarget::Device { device_id } => {
                     let playback = self.audio.list_playback_devices().unwrap_or_default();
                     let recording = self.audio.list_recording_devices().unwrap_or_default();
                     let (kind, raw_id) = parse_device_target(device_id);
                     let device = match kind {
-                        DeviceTargetKind::Playback => {
-                            playback.iter().find(|device| device.id == raw_id)
-                        }
-                        DeviceTargetKind::Recording => {
-                            recording.iter().find(|device| device.id == raw_id)
-                        }
+                        DeviceTargetKind::Playback => find_device(&playback, raw_id),
+                        DeviceTargetKind::Recording => find_device(&recording, raw_id),
                     };
                     if let Some(device) = device {
                         let new_muted = !device.is_muted;
@@ -418,11 +2057,20 @@ impl AppState {
                         .unwrap_or(0.0);
                     let is_currently_muted = current_val > 0.5;
                     let new_muted = !is_currently_muted;
+                    let new_value = if new_muted { 1.0 } else { 0.0 };
+
+                    // Record the toggled state optimistically so a quick second press flips
+                    // back; the plugin confirms (or corrects) it via set_binding_feedback.
+                    if let Ok(mut feedback) = self.feedback_values.lock() {
+                        feedback.insert(key.clone(), new_value);
+                    }
+                    self.send_feedback(&binding, new_value);
 
                     let payload = serde_json::json!({
                       "binding_id": binding.id,
                       "action": "ToggleMute",
-                      "value": if new_muted { 1.0 } else { 0.0 },
+                      "value": new_value,
+                      "previous": current_val,
                       "target": {
                         "integration_id": integration_id,
                         "kind": kind,
@@ -437,83 +2085,101 @@ impl AppState {
                 }
             };
 
-            if let Ok(mut last_update) = self.osd_last_update.lock() {
-                *last_update = Some(Instant::now());
-            }
+            self.publish_mute_state(app, &binding, &key, muted);
+            return Ok(());
+        }
 
-            if let Ok(mut feedback) = self.feedback_values.lock() {
-                feedback.insert(key.clone(), if muted { 1.0 } else { 0.0 });
+        let volume = match &binding.action {
+            model::BindingAction::VolumeStep { delta } => {
+                // Only the press steps, so a held button or its Note-off doesn't repeat.
+                if self.button_edge(&key, &binding, event.value)? != Some(ButtonEdge::Press) {
+                    return Ok(());
+                }
+                let current = self
+                    .feedback_values
+                    .lock()
+                    .ok()
+                    .and_then(|feedback| feedback.get(&key).cloned())
+                    .unwrap_or(0.0);
+                (current + delta).clamp(0.0, 1.0)
             }
-
-            if let Ok(mut midi) = self.midi.lock() {
-                // println!("MIDI Event Matched Binding: {:?} -> {:?}", binding.name, binding.target);
-                let _ = midi.send_feedback(
-                    &binding.device_id,
-                    binding.control.channel,
-                    binding.control.controller,
-                    if muted { 1.0 } else { 0.0 },
-                    binding.control.msg_type.clone(),
-                );
+            model::BindingAction::SetVolume { value } => {
+                if self.button_edge(&key, &binding, event.value)? != Some(ButtonEdge::Press) {
+                    return Ok(());
+                }
+                *value
             }
-
-            let focus_session = if matches!(&binding.target, model::BindingTarget::Focus) {
-                self.audio.focused_session().ok().flatten()
-            } else {
-                None
-            };
-
-            let payload = serde_json::json!({
-              "target": binding.target,
-              "muted": muted,
-              "action": "toggle_mute",
-              "focus_session": focus_session,
-            });
-            let _ = app.emit("mute_update", payload.clone());
-
-            let settings_enabled = self
-                .osd_settings
-                .lock()
-                .map(|settings| settings.enabled)
-                .unwrap_or(true);
-
-            if settings_enabled {
-                if let Some(osd_window) = app.get_webview_window("osd") {
-                    let _ = osd_window.show();
-                    let _ = osd_window.emit("mute_update", payload.clone());
-                    if let Ok(payload_json) = serde_json::to_string(&payload) {
-                        let script = format!(
-                            "window.__OSD_UPDATE__ && window.__OSD_UPDATE__({});",
-                            payload_json
-                        );
-                        let _ = osd_window.eval(&script);
+            model::BindingAction::Duck { level } => {
+                if self.button_edge(&key, &binding, event.value)? != Some(ButtonEdge::Press) {
+                    return Ok(());
+                }
+                let mut ducked = self.ducked_volumes.lock().map_err(|_| "Lock poisoned")?;
+                match ducked.remove(&key) {
+                    Some(previous) => previous,
+                    None => {
+                        let Some(current) = self
+                            .target_state(&binding.target)
+                            .map_err(|err| err.to_string())?
+                        else {
+                            return Ok(());
+                        };
+                        ducked.insert(key.clone(), current.volume);
+                        *level
                     }
                 }
             }
-
-            return Ok(());
-        }
+            _ => volume,
+        };
 
         match &binding.target {
             model::BindingTarget::Master => self
                 .audio
                 .set_master_volume(volume)
                 .map_err(|err| err.to_string())?,
-            model::BindingTarget::Focus => self
+            model::BindingTarget::SystemSounds => self
                 .audio
-                .set_focused_session_volume(volume)
+                .set_system_sounds_volume(volume)
                 .map_err(|err| err.to_string())?,
-            model::BindingTarget::Session { session_id } => self
+            model::BindingTarget::Focus => self
                 .audio
-                .set_session_volume(session_id, volume)
+                .set_focused_session_volume(volume, self.focus_all_sessions())
                 .map_err(|err| err.to_string())?,
-            model::BindingTarget::Application { name } => self
+            model::BindingTarget::Session { session_id, path } => self
                 .audio
-                .set_application_volume(name, volume)
+                .set_session_volume(&self.live_session_id(session_id, path.as_deref()), volume)
                 .map_err(|err| err.to_string())?,
+            model::BindingTarget::Application { name, path } => {
+                with_application(name, path.as_deref(), |app| {
+                    self.audio.set_application_volume(app, volume)
+                })
+                .map_err(|err| err.to_string())?
+            }
             model::BindingTarget::Device { device_id } => self
                 .audio
                 .set_device_volume(device_id, volume)
                 .map_err(|err| err.to_string())?,
+            model::BindingTarget::DefaultMicrophone | model::BindingTarget::DefaultSpeakers => {
+                if let Some(model::BindingTarget::Device { device_id }) =
+                    binding.target.default_device()
+                {
+                    self.audio
+                        .set_device_volume(&device_id, volume)
+                        .map_err(|err| err.to_string())?;
+                }
+            }
+            model::BindingTarget::ApplicationGroup { names } => {
+                for_each_application(names, |app| self.audio.set_application_volume(app, volume))
+                    .map_err(|err| err.to_string())?
+            }
+            model::BindingTarget::ApplicationBalance { left, right } => {
+                let (left_volume, right_volume) = balance_volumes(volume);
+                self.audio
+                    .set_application_volume(left, left_volume)
+                    .map_err(|err| err.to_string())?;
+                self.audio
+                    .set_application_volume(right, right_volume)
+                    .map_err(|err| err.to_string())?;
+            }
             model::BindingTarget::Unset => {
                 return Ok(());
             }
@@ -545,178 +2211,250 @@ impl AppState {
             *last_update = Some(Instant::now());
         }
 
-        if let Ok(mut midi) = self.midi.lock() {
-            let _ = midi.send_feedback(
-                &binding.device_id,
-                binding.control.channel,
-                binding.control.controller,
-                volume,
-                binding.control.msg_type.clone(),
-            );
-        }
+        self.send_feedback(&binding, volume);
 
         let focus_session = if matches!(&binding.target, model::BindingTarget::Focus) {
-            self.audio.focused_session().ok().flatten()
+            self.focused_session_with_alias()
         } else {
             None
         };
+        let (display_name, icon_data) = self.target_display(&binding.target);
         let payload = serde_json::json!({
           "target": binding.target,
           "volume": volume,
           "focus_session": focus_session,
-          "binding_id": binding.id
+          "alias": self.target_alias(&binding.target),
+          "companion_muted": self.companion_muted(&binding),
+          "binding_id": binding.id,
+          "display_name": display_name,
+          "icon_data": icon_data,
         });
-        let _ = app.emit("volume_update", payload.clone());
-        let settings_enabled = self
-            .osd_settings
-            .lock()
-            .map(|settings| settings.enabled)
-            .unwrap_or(true);
-        if settings_enabled {
-            if let Some(osd_window) = app.get_webview_window("osd") {
-                let _ = osd_window.show();
-                let _ = osd_window.emit("volume_update", payload.clone());
-                if let Ok(payload_json) = serde_json::to_string(&payload) {
-                    let script = format!(
-                        "window.__OSD_UPDATE__ && window.__OSD_UPDATE__({});",
-                        payload_json
-                    );
-                    let _ = osd_window.eval(&script);
+        self.emit_ui_update(app, "volume_update", &binding, payload);
+
+        Ok(())
+    }
+
+    fn cycle_default_device(
+        &self,
+        app: &AppHandle,
+        binding: &model::Binding,
+    ) -> Result<(), String> {
+        let devices = self
+            .audio
+            .list_playback_devices()
+            .map_err(|err| err.to_string())?;
+        if devices.is_empty() {
+            return Ok(());
+        }
+        let next = devices
+            .iter()
+            .position(|device| device.is_default)
+            .map(|index| (index + 1) % devices.len())
+            .unwrap_or(0);
+        let device = &devices[next];
+        self.audio
+            .set_default_device(&format!("playback:{}", device.id))
+            .map_err(|err| err.to_string())?;
+
+        let alias = self.target_alias(&model::BindingTarget::Device {
+            device_id: format!("playback:{}", device.id),
+        });
+        let payload = serde_json::json!({
+          "binding_id": binding.id,
+          "action": "default_device",
+          "device_id": device.id,
+          "display_name": alias.unwrap_or_else(|| device.display_name.clone()),
+          "icon_data": device.icon_data,
+        });
+        let _ = app.emit("default_device_changed", payload.clone());
+        self.emit_osd(app, binding, &payload);
+        Ok(())
+    }
+
+    /// Rewrites bindings saved against an index-based input id to the port's
+    /// name-based id. Returns whether the active profile changed.
+    fn migrate_legacy_device_id(&self, legacy_id: &str, stable_id: &str) -> Result<bool, String> {
+        let profile = {
+            let mut guard = self
+                .active_profile
+                .lock()
+                .map_err(|_| "Lock poisoned".to_string())?;
+            let Some(profile) = guard.as_mut() else {
+                return Ok(false);
+            };
+            let mut changed = false;
+            for binding in profile
+                .bindings
+                .iter_mut()
+                .filter(|binding| binding.device_id == legacy_id)
+            {
+                binding.device_id = stable_id.to_string();
+                changed = true;
+            }
+            if !changed {
+                return Ok(false);
+            }
+            profile.clone()
+        };
+        self.profile_store
+            .save_profile(profile.clone())
+            .map_err(|err| err.to_string())?;
+        self.sync_midi_config(&profile);
+        log_info!("Migrated bindings from {} to {}", legacy_id, stable_id);
+        Ok(true)
+    }
+
+    /// Pushes profile-derived parser settings (14-bit CC pairs) to the MIDI manager.
+    fn sync_midi_config(&self, profile: &Profile) {
+        let mut pairs: Vec<HighResPair> = Vec::new();
+        for binding in &profile.bindings {
+            let control = &binding.control;
+            let Some(lsb) = control.lsb_controller else {
+                continue;
+            };
+            if control.msg_type != model::MidiMessageType::ControlChange {
+                continue;
+            }
+            let channels = if control.any_channel {
+                0..16
+            } else {
+                control.channel..control.channel + 1
+            };
+            for channel in channels {
+                let pair = HighResPair {
+                    device_id: binding.device_id.clone(),
+                    channel,
+                    msb: control.controller,
+                    lsb,
+                };
+                if !pairs.contains(&pair) {
+                    pairs.push(pair);
                 }
             }
         }
+        if let Ok(mut midi) = self.midi.lock() {
+            midi.set_high_res_pairs(pairs);
+        }
+    }
 
-        Ok(())
+    /// The id a Session target's session has right now. Saved ids carry the
+    /// process id, so after the app restarts the session is looked up in the
+    /// last audio snapshot by its stable key instead.
+    fn live_session_id(&self, session_id: &str, path: Option<&str>) -> String {
+        self.audio_snapshot
+            .lock()
+            .ok()
+            .and_then(|snapshot| {
+                find_session_target(&snapshot.sessions, session_id, path)
+                    .map(|session| session.id.clone())
+            })
+            .unwrap_or_else(|| session_id.to_string())
+    }
+
+    /// Records the executable path of Session targets that don't have one yet,
+    /// so they can still be matched when their id has no stable part.
+    fn describe_session_targets(&self, profile: &mut Profile) {
+        let Ok(snapshot) = self.audio_snapshot.lock() else {
+            return;
+        };
+        for binding in &mut profile.bindings {
+            if let model::BindingTarget::Session {
+                session_id,
+                path: path @ None,
+            } = &mut binding.target
+            {
+                *path = snapshot
+                    .sessions
+                    .iter()
+                    .find(|session| session.id == *session_id)
+                    .and_then(|session| session.process_path.clone());
+            }
+        }
+    }
+
+    /// Lists sessions and devices fresh from the audio backend.
+    fn read_audio_snapshot(&self) -> anyhow::Result<AudioSnapshot> {
+        Ok(AudioSnapshot {
+            sessions: self.audio.list_sessions()?,
+            system_sounds: self.audio.system_sounds_session().ok().flatten(),
+            playback_devices: self.audio.list_playback_devices().unwrap_or_default(),
+            recording_devices: self.audio.list_recording_devices().unwrap_or_default(),
+        })
+    }
+
+    /// Current volume and mute of `target`, or `None` if it isn't present right now.
+    fn target_state(&self, target: &model::BindingTarget) -> anyhow::Result<Option<TargetState>> {
+        let snapshot = self.read_audio_snapshot()?;
+        Ok(snapshot.target_state(target, || self.audio.focused_session().ok().flatten()))
     }
 
     fn sync_feedback_values(&self, profile: &Profile) {
-        let sessions = match self.audio.list_sessions() {
-            Ok(sessions) => sessions,
+        let snapshot = match self.read_audio_snapshot() {
+            Ok(snapshot) => snapshot,
             Err(_) => return,
         };
-        let playback_devices = self.audio.list_playback_devices().unwrap_or_default();
-        let recording_devices = self.audio.list_recording_devices().unwrap_or_default();
+        let solo_binding = self
+            .solo
+            .lock()
+            .ok()
+            .and_then(|solo| solo.as_ref().map(|solo| solo.binding_id.clone()));
         let mut feedback = match self.feedback_values.lock() {
             Ok(feedback) => feedback,
             Err(_) => return,
         };
 
         for binding in &profile.bindings {
-            let value = if binding.action == model::BindingAction::ToggleMute {
-                match &binding.target {
-                    model::BindingTarget::Master => sessions
-                        .iter()
-                        .find(|session| session.is_master)
-                        .map(|session| if session.is_muted { 1.0 } else { 0.0 }),
-                    model::BindingTarget::Focus => self
-                        .audio
-                        .focused_session()
-                        .ok()
-                        .flatten()
-                        .map(|s| if s.is_muted { 1.0 } else { 0.0 }),
-                    model::BindingTarget::Session { session_id } => sessions
-                        .iter()
-                        .find(|session| session.id == *session_id)
-                        .map(|session| if session.is_muted { 1.0 } else { 0.0 }),
-                    model::BindingTarget::Application { name } => {
-                        let target = name.to_lowercase();
-                        sessions
-                            .iter()
-                            .find(|session| {
-                                if let Some(path) = &session.process_path {
-                                    if let Some(stem) = Path::new(path)
-                                        .file_stem()
-                                        .and_then(|s: &std::ffi::OsStr| s.to_str())
-                                    {
-                                        if stem.to_lowercase() == target {
-                                            return true;
-                                        }
-                                    }
-                                }
-                                if let Some(name) = &session.process_name {
-                                    let stem = name.strip_suffix(".exe").unwrap_or(name);
-                                    if stem.to_lowercase() == target {
-                                        return true;
-                                    }
-                                }
-                                session.display_name.to_lowercase() == target
-                            })
-                            .map(|session| if session.is_muted { 1.0 } else { 0.0 })
-                    }
-                    model::BindingTarget::Device { device_id } => {
-                        let (kind, raw_id) = parse_device_target(device_id);
-                        match kind {
-                            DeviceTargetKind::Playback => playback_devices
-                                .iter()
-                                .find(|device| device.id == raw_id)
-                                .map(|device| if device.is_muted { 1.0 } else { 0.0 }),
-                            DeviceTargetKind::Recording => recording_devices
-                                .iter()
-                                .find(|device| device.id == raw_id)
-                                .map(|device| if device.is_muted { 1.0 } else { 0.0 }),
-                        }
-                    }
-                    model::BindingTarget::Unset => None,
-                    model::BindingTarget::Integration { .. } => None,
-                }
-            } else {
-                match &binding.target {
-                    model::BindingTarget::Master => sessions
-                        .iter()
-                        .find(|session| session.is_master)
-                        .map(|session| session.volume),
-                    model::BindingTarget::Focus => None,
-                    model::BindingTarget::Session { session_id } => sessions
-                        .iter()
-                        .find(|session| session.id == *session_id)
-                        .map(|session| session.volume),
-                    model::BindingTarget::Application { name } => {
-                        let target = name.to_lowercase();
-                        sessions
-                            .iter()
-                            .find(|session| {
-                                if let Some(path) = &session.process_path {
-                                    if let Some(stem) = Path::new(path)
-                                        .file_stem()
-                                        .and_then(|s: &std::ffi::OsStr| s.to_str())
-                                    {
-                                        if stem.to_lowercase() == target {
-                                            return true;
-                                        }
-                                    }
-                                }
-                                if let Some(name) = &session.process_name {
-                                    let stem = name.strip_suffix(".exe").unwrap_or(name);
-                                    if stem.to_lowercase() == target {
-                                        return true;
-                                    }
-                                }
-                                session.display_name.to_lowercase() == target
-                            })
-                            .map(|session| session.volume)
-                    }
-                    model::BindingTarget::Device { device_id } => {
-                        let (kind, raw_id) = parse_device_target(device_id);
-                        match kind {
-                            DeviceTargetKind::Playback => playback_devices
-                                .iter()
-                                .find(|device| device.id == raw_id)
-                                .map(|device| device.volume),
-                            DeviceTargetKind::Recording => recording_devices
-                                .iter()
-                                .find(|device| device.id == raw_id)
-                                .map(|device| device.volume),
-                        }
-                    }
-                    model::BindingTarget::Unset => None,
-                    model::BindingTarget::Integration { .. } => None,
-                }
+            if binding.action == model::BindingAction::Solo {
+                let active = solo_binding.as_deref() == Some(binding.id.as_str());
+                feedback.insert(
+                    BindingKey::from_binding(binding),
+                    if active { 1.0 } else { 0.0 },
+                );
+                continue;
+            }
+            if matches!(
+                binding.action,
+                model::BindingAction::RouteToDevice { .. }
+                    | model::BindingAction::CycleDefaultDevice
+                    | model::BindingAction::Balance
+            ) {
+                continue;
+            }
+            let mute = matches!(
+                binding.action,
+                model::BindingAction::ToggleMute | model::BindingAction::PushToTalk
+            );
+            // A focus fader isn't synced (the app under it keeps changing), and a
+            // balance pair has no single mute to light up.
+            let skip = match &binding.target {
+                model::BindingTarget::Focus => !mute,
+                model::BindingTarget::ApplicationBalance { .. } => mute,
+                _ => false,
             };
+            if skip {
+                continue;
+            }
+            let value = snapshot
+                .target_state(&binding.target, || {
+                    self.audio.focused_session().ok().flatten()
+                })
+                .map(|state| {
+                    if mute {
+                        mute_feedback(binding, state.muted)
+                    } else {
+                        state.volume
+                    }
+                });
 
             if let Some(val) = value {
                 feedback.insert(BindingKey::from_binding(binding), val);
             }
         }
+        drop(feedback);
+
+        if let Ok(mut audio_snapshot) = self.audio_snapshot.lock() {
+            *audio_snapshot = snapshot;
+        }
     }
 }
 
@@ -725,13 +2463,7 @@ fn shutdown_lights(state: &AppState) {
         if let Some(profile) = profile_guard.as_ref() {
             if let Ok(mut midi) = state.midi.lock() {
                 for binding in &profile.bindings {
-                    let _ = midi.send_feedback(
-                        &binding.device_id,
-                        binding.control.channel,
-                        binding.control.controller,
-                        0.0,
-                        binding.control.msg_type.clone(),
-                    );
+                    let _ = midi.send_binding_off(binding);
                 }
             }
         }
@@ -740,6 +2472,7 @@ fn shutdown_lights(state: &AppState) {
 
 fn main() {
     tauri::Builder::default()
+        .plugin(hotkeys::plugin())
         .plugin(
             tauri_plugin_window_state::Builder::default()
                 .with_state_flags(
@@ -774,18 +2507,26 @@ fn main() {
                 )],
             );
             let profile_store = ProfileStore::new(config_dir.clone());
-            let app_settings_store = AppSettingsStore::new(config_dir);
+            let app_settings_store = AppSettingsStore::new(config_dir.clone());
             let app_settings = app_settings_store.load().unwrap_or_default();
+            logging::init(&config_dir, app_settings.log_level);
             let audio: Box<dyn AudioBackend> = {
                 #[cfg(target_os = "windows")]
                 {
-                    Box::new(WindowsAudioBackend::new())
+                    Box::new(WindowsAudioBackend::new(Some(
+                        config_dir.join("icon_cache"),
+                    )))
                 }
-                #[cfg(not(target_os = "windows"))]
+                #[cfg(target_os = "linux")]
+                {
+                    Box::new(LinuxAudioBackend::new())
+                }
+                #[cfg(not(any(target_os = "windows", target_os = "linux")))]
                 {
                     Box::new(UnsupportedAudioBackend::new())
                 }
             };
+            audio.set_session_filter(app_settings.session_filter());
 
             // Shared WebSocket bridge for integration plugins.
             app.manage(WsHub::new());
@@ -801,25 +2542,25 @@ fn main() {
                 learn_pending: Mutex::new(false),
                 learn_candidate: Mutex::new(None),
                 learned_control: Mutex::new(None),
+                control_hints: Mutex::new(ControlHintStore::new(config_dir.clone())),
+                unlearn_pending: Mutex::new(false),
                 osd_last_update: Mutex::new(None),
+                osd_content_heights: Mutex::new(HashMap::new()),
                 osd_settings: Mutex::new(OsdSettings::default()),
                 app_settings: Mutex::new(app_settings.clone()),
+                midi_activity: Mutex::new(MidiActivity::default()),
+                audio_snapshot: Mutex::new(AudioSnapshot::default()),
+                ptt_generations: Mutex::new(HashMap::new()),
+                ducked_volumes: Mutex::new(HashMap::new()),
+                solo: Mutex::new(None),
+                active_layer: Mutex::new(0),
+                press_starts: Mutex::new(HashMap::new()),
+                pending_taps: Mutex::new(HashMap::new()),
+                tap_generation: Mutex::new(0),
+                ui_updates: Mutex::new(HashMap::new()),
             });
 
-            let osd_window =
-                WebviewWindowBuilder::new(app, "osd", WebviewUrl::App("index.html?osd=1".into()))
-                    .title("MIDIMaster OSD")
-                    .decorations(false)
-                    .transparent(true)
-                    .always_on_top(true)
-                    .skip_taskbar(true)
-                    .resizable(false)
-                    .focused(false)
-                    .shadow(false)
-                    .inner_size(320.0, 120.0)
-                    .build()?;
-            let _ = osd_window.set_ignore_cursor_events(true);
-            let _ = osd_window.hide();
+            build_osd_window(app.handle(), "osd")?;
             if let Ok(settings) = app.state::<AppState>().osd_settings.lock() {
                 AppState::apply_osd_settings(&app.handle(), &settings);
             }
@@ -879,7 +2620,7 @@ fn main() {
                             let _ = main_window_handle.hide();
                             return;
                         }
-                        if let Some(osd_window) = app_handle.get_webview_window("osd") {
+                        for osd_window in osd_windows(&app_handle) {
                             let _ = osd_window.close();
                         }
                         let state = app_handle.state::<AppState>();
@@ -912,23 +2653,51 @@ fn main() {
 
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                let mut last_known_volumes: HashMap<BindingKey, f32> = HashMap::new();
+                let mut last_sent: HashMap<BindingKey, (f32, Instant)> = HashMap::new();
+                let mut last_meter_polls: HashMap<BindingKey, Instant> = HashMap::new();
+                let mut last_audio_sync: Option<Instant> = None;
+                let mut foreground = ForegroundTracker::default();
+                let mut osd_meters = OsdMeterState::default();
+                let mut monitor_layout_seen = monitor_layout(&app_handle);
+                let mut last_monitor_poll = Instant::now();
+                let audio_changes = app_handle.state::<AppState>().audio.changes();
                 loop {
                     let state = app_handle.state::<AppState>();
 
                     // Check for expired learn candidates
+                    let learn_window = state
+                        .app_settings
+                        .lock()
+                        .map(|settings| Duration::from_millis(settings.learn_window_ms))
+                        .unwrap_or_default();
                     let mut commit_candidate = None;
                     if let Ok(mut candidate_guard) = state.learn_candidate.lock() {
-                        if let Some((_, time)) = &*candidate_guard {
-                            if time.elapsed() > Duration::from_millis(150) {
+                        if let Some((candidate, time)) = &*candidate_guard {
+                            if time.elapsed()
+                                > learn_hints::candidate_window(candidate, learn_window)
+                            {
                                 commit_candidate = candidate_guard.take().map(|(l, _)| l);
                             }
                         }
                     }
-                    if let Some(candidate) = commit_candidate {
+                    if let Some(mut candidate) = commit_candidate {
                         if let Ok(mut pending) = state.learn_pending.lock() {
                             if *pending {
                                 *pending = false;
+                                if let Ok(mut hints) = state.control_hints.lock() {
+                                    if candidate.msg_type == model::MidiMessageType::Note {
+                                        // No fader followed the note, so it is a plain button
+                                        hints.mark(&candidate, model::ControlKind::Button);
+                                    }
+                                    // A CC that only ever sent 0/127 during the window is a
+                                    // button; `observe` persists it once both extremes are seen.
+                                    candidate.control_kind = Some(
+                                        hints
+                                            .kind(&candidate)
+                                            .unwrap_or(model::ControlKind::Button),
+                                    );
+                                    let _ = hints.save_if_dirty();
+                                }
                                 if let Ok(mut learned) = state.learned_control.lock() {
                                     *learned = Some(candidate.clone());
                                 }
@@ -936,44 +2705,139 @@ fn main() {
                         }
                     }
 
+                    if foreground.poll_due() {
+                        foreground.observe(state.audio.foreground_process_path().ok().flatten());
+                    }
+                    if let Some(process_path) = foreground.take_settled() {
+                        state.auto_switch_profile(&app_handle, &process_path);
+                    }
+
+                    if last_monitor_poll.elapsed() >= MONITOR_POLL_INTERVAL {
+                        last_monitor_poll = Instant::now();
+                        let layout = monitor_layout(&app_handle);
+                        if layout.is_some() && layout != monitor_layout_seen {
+                            monitor_layout_seen = layout;
+                            log_info!("OSD: Monitor layout changed, re-placing OSD windows");
+                            if let Ok(settings) =
+                                state.osd_settings.lock().map(|settings| settings.clone())
+                            {
+                                AppState::apply_osd_settings(&app_handle, &settings);
+                            }
+                        }
+                    }
+
                     let profile = state
                         .active_profile
                         .lock()
                         .ok()
                         .and_then(|profile| profile.clone());
                     if let Some(profile) = profile {
-                        state.sync_feedback_values(&profile);
+                        state.settle_smoothing(&app_handle, &profile);
+
+                        // Backends with change notifications are only re-read after an
+                        // event (or the fallback interval); the rest are polled every tick.
+                        // Within the minimum gap the change flag is left set for later.
+                        let since_sync = last_audio_sync.map(|last| last.elapsed());
+                        let resync = since_sync.is_none_or(|since| since >= AUDIO_RESYNC_MIN_GAP)
+                            && (audio_changes.is_none_or(|changes| changes.take())
+                                || since_sync.is_none_or(|since| since >= AUDIO_RESYNC_INTERVAL));
+                        if resync {
+                            last_audio_sync = Some(Instant::now());
+                            state.enforce_solo(&profile);
+                            state.sync_feedback_values(&profile);
+                        }
+                        state.push_osd_meters(&app_handle, &profile, &mut osd_meters);
                         let feedback = state
                             .feedback_values
                             .lock()
                             .map(|values| values.clone())
                             .unwrap_or_default();
 
+                        let layer = state.active_layer();
+                        let mut meter_values: HashMap<BindingKey, f32> = HashMap::new();
+                        for binding in profile.bindings.iter().filter(|binding| {
+                            binding.feedback_enabled
+                                && binding.meter_feedback
+                                && on_layer(binding, layer)
+                        }) {
+                            let key = BindingKey::from_binding(binding);
+                            let interval = Duration::from_millis(binding.meter_interval_ms);
+                            let due = last_meter_polls
+                                .get(&key)
+                                .is_none_or(|last| last.elapsed() >= interval);
+                            if !due {
+                                continue;
+                            }
+                            last_meter_polls.insert(key.clone(), Instant::now());
+                            if let Some(peak) = state.target_peak(&binding.target) {
+                                meter_values.insert(key, peak);
+                            }
+                        }
+
                         if let Ok(mut midi) = state.midi.lock() {
-                            for binding in &profile.bindings {
+                            for binding in profile.bindings.iter().filter(|binding| {
+                                binding.feedback_enabled && on_layer(binding, layer)
+                            }) {
                                 let key = BindingKey::from_binding(binding);
+                                if binding.meter_feedback {
+                                    if let Some(peak) = meter_values.get(&key) {
+                                        if feedback_due(&mut last_sent, &key, *peak) {
+                                            let _ = midi.send_binding_feedback(binding, *peak);
+                                        }
+                                    }
+                                    continue;
+                                }
                                 if let Some(volume) = feedback.get(&key).cloned() {
-                                    // Volume Protection & Clamp Logic
-
-                                    last_known_volumes.insert(key.clone(), volume);
-
-                                    let _ = midi.send_feedback(
-                                        &binding.device_id,
-                                        binding.control.channel,
-                                        binding.control.controller,
-                                        volume,
-                                        binding.control.msg_type.clone(),
-                                    );
+                                    if feedback_due(&mut last_sent, &key, volume) {
+                                        let _ = midi.send_binding_feedback(binding, volume);
+                                    }
                                 }
                             }
                         }
                     }
 
-                    let settings_enabled = state
+                    state.check_midi_idle(&app_handle);
+
+                    let input_status = state.midi.lock().ok().and_then(|mut midi| {
+                        let connected = midi.check_input()?;
+                        Some((connected, midi.active_device().map(str::to_string)))
+                    });
+                    if let Some((connected, device_id)) = input_status {
+                        let _ = app_handle.emit(
+                            "midi_device_status",
+                            serde_json::json!({ "connected": connected, "device_id": device_id }),
+                        );
+                    }
+
+                    let output_changes = state
+                        .midi
+                        .lock()
+                        .map(|mut midi| midi.take_output_status_changes())
+                        .unwrap_or_default();
+                    if !output_changes.is_empty() {
+                        // A (re)connected output starts blank; send everything again.
+                        last_sent.clear();
+                    }
+                    for status in output_changes {
+                        let _ = app_handle.emit("midi_output_status", &status);
+                    }
+
+                    let heartbeats = state
+                        .app_settings
+                        .lock()
+                        .map(|settings| settings.midi_heartbeats.clone())
+                        .unwrap_or_default();
+                    if !heartbeats.is_empty() {
+                        if let Ok(mut midi) = state.midi.lock() {
+                            midi.send_heartbeats(&heartbeats);
+                        }
+                    }
+
+                    let (settings_enabled, meter_labels) = state
                         .osd_settings
                         .lock()
-                        .map(|settings| settings.enabled)
-                        .unwrap_or(true);
+                        .map(|settings| (settings.enabled, meter_osd_labels(&settings)))
+                        .unwrap_or((true, Vec::new()));
                     if settings_enabled {
                         let should_hide = state
                             .osd_last_update
@@ -984,8 +2848,10 @@ fn main() {
                             })
                             .unwrap_or(false);
                         if should_hide {
-                            if let Some(osd_window) = app_handle.get_webview_window("osd") {
-                                let _ = osd_window.hide();
+                            for osd_window in osd_windows(&app_handle) {
+                                if !meter_labels.iter().any(|label| label == osd_window.label()) {
+                                    let _ = osd_window.hide();
+                                }
                             }
                             if let Ok(mut guard) = state.osd_last_update.lock() {
                                 *guard = None;
@@ -993,7 +2859,24 @@ fn main() {
                         }
                     }
 
-                    sleep(Duration::from_millis(50)).await;
+                    match audio_changes {
+                        Some(changes) => {
+                            tokio::select! {
+                                _ = changes.changed() => {
+                                    // Let further notifications pile up on the dirty
+                                    // flag instead of waking the loop for each one.
+                                    let wait = last_audio_sync.and_then(|last| {
+                                        AUDIO_RESYNC_MIN_GAP.checked_sub(last.elapsed())
+                                    });
+                                    if let Some(wait) = wait {
+                                        sleep(wait).await;
+                                    }
+                                }
+                                _ = sleep(Duration::from_millis(50)) => {}
+                            }
+                        }
+                        None => sleep(Duration::from_millis(50)).await,
+                    }
                 }
             });
 
@@ -1004,32 +2887,65 @@ fn main() {
             list_midi_output_devices,
             start_midi_device,
             stop_midi_device,
+            last_midi_activity,
+            midi_output_status,
+            set_midi_reconnect_policy,
+            set_midi_heartbeat,
+            send_raw_midi,
+            set_midi_passthrough,
             list_sessions,
             list_monitors,
             get_osd_settings,
+            get_log_level,
+            set_log_level,
+            set_osd_instances,
             update_osd_settings,
+            update_osd_appearance,
+            report_osd_height,
             get_app_settings,
             update_app_settings,
+            set_hotkeys,
+            set_session_filter,
             reset_app_data,
             list_playback_devices,
             list_recording_devices,
             set_master_volume,
+            set_system_sounds_volume,
             set_session_volume,
+            get_target_state,
             set_application_volume,
             set_device_volume,
+            set_channel_volume,
+            set_balance,
             set_master_mute,
+            set_system_sounds_mute,
             set_session_mute,
             set_application_mute,
             set_device_mute,
+            set_application_output_device,
+            set_default_device,
             list_profiles,
             load_profile,
+            set_profile_auto_switch,
+            set_profile_midi_devices,
+            duplicate_profile,
+            export_profile,
+            import_profile,
             save_profile,
+            merge_profile,
             delete_profile,
             get_active_profile,
+            set_target_alias,
+            set_plugin_setting,
             start_midi_learn,
             consume_learned_control,
+            start_unlearn,
+            cancel_unlearn,
             add_binding,
+            find_binding_conflict,
+            update_binding,
             remove_binding,
+            reorder_bindings,
             update_midi_feedback,
             set_binding_feedback,
             get_plugins_dir,
@@ -1041,9 +2957,12 @@ fn main() {
             set_plugin_enabled,
             ws_open,
             ws_send,
+            ws_send_binary,
             ws_close,
             fetch_store_catalog,
             install_store_plugin,
+            check_plugin_updates,
+            set_store_catalog_urls,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
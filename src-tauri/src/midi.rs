@@ -1,261 +1,918 @@
-use crate::model::{DeviceInfo, MidiEvent};
+use crate::app_settings::MidiHeartbeat;
+use crate::bindings::{volume_to_position, PITCH_BEND_CENTER};
+use crate::model::{Binding, BindingAction, DeviceInfo, MidiEvent, MidiMessageType, MidiMode};
 use anyhow::{anyhow, Result};
 use midir::{
     Ignore, MidiInput, MidiInputConnection, MidiInputPort, MidiOutput, MidiOutputConnection,
     MidiOutputPort,
 };
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 const MIDI_PORT_PREFIX: &str = "midi:";
-const LOG_MIDI_MESSAGES: bool = false;
+/// Ids are `midi:name:<sanitized port name>`, with `#2`, `#3`... for repeated names.
+/// Plain `midi:<index>` ids from older releases are still accepted when connecting.
+const MIDI_PORT_NAME_PREFIX: &str = "midi:name:";
+/// How often the active input port is looked up while connected or waiting for a replug.
+const INPUT_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+/// Minimum wait between attempts to reopen a replugged input.
+const INPUT_RECONNECT_COOLDOWN: Duration = Duration::from_secs(5);
+/// Default minimum wait between attempts to reopen a failing output.
+const OUTPUT_RECONNECT_COOLDOWN: Duration = Duration::from_secs(5);
+/// Default number of consecutive failed reopens before an output is given up on.
+const MAX_OUTPUT_RECONNECT_FAILURES: u32 = 3;
+/// Raw bytes an output accepts per `RAW_SEND_WINDOW`, so a runaway plugin
+/// can't flood a controller's input buffer. Counted in bytes so a stream of
+/// large SysEx frames is throttled as much as a stream of short messages.
+const MAX_RAW_BYTES_PER_WINDOW: usize = 8 * 1024;
+const RAW_SEND_WINDOW: Duration = Duration::from_secs(1);
+/// Longest SysEx frame `send_raw` accepts, `F0` and `F7` included.
+const MAX_SYSEX_LEN: usize = 4 * 1024;
+
+type EventCallback = Arc<dyn Fn(MidiEvent) + Send + Sync>;
+type RawCallback = Arc<dyn Fn(MidiRawMessage) + Send + Sync>;
+type HeardControl = (String, MidiMessageType, u8);
 
 pub struct MidiManager {
     input_connection: Option<MidiInputConnection<()>>,
-    output_connections: Vec<MidiOutputConnection>,
+    output_connections: HashMap<String, OutputConnection>,
     active_device: Option<String>,
-    active_output_device: Option<String>,
+    connected_at: Option<std::time::Instant>,
+    high_res: Arc<Mutex<HighResCc>>,
+    /// Kept so a replugged input can be reconnected to the same handler.
+    on_event: Option<EventCallback>,
+    last_input_check: Option<Instant>,
+    last_input_reconnect: Option<Instant>,
+    reconnect_policy: ReconnectPolicy,
+    /// Receives every incoming message, including ones no binding can use, while
+    /// MIDI passthrough is on.
+    raw_listener: Arc<Mutex<Option<RawCallback>>>,
+    /// Channel each control (by device, message type and number) was last heard
+    /// on, so feedback for an any-channel binding goes back where the controller
+    /// sent from. Cleared when the active device changes.
+    heard_channels: Arc<Mutex<HashMap<HeardControl, u8>>>,
+}
+
+/// How persistently a failing output is reopened.
+#[derive(Debug, Clone, Copy)]
+pub struct ReconnectPolicy {
+    pub cooldown: Duration,
+    pub max_failures: u32,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            cooldown: OUTPUT_RECONNECT_COOLDOWN,
+            max_failures: MAX_OUTPUT_RECONNECT_FAILURES,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputHealth {
+    Connected,
+    /// Not open right now; the next send tries to reopen it.
+    Reconnecting,
+    /// Gave up after `ReconnectPolicy::max_failures` attempts.
+    Failed,
+}
+
+/// An incoming message exactly as received.
+#[derive(Debug, Clone, Serialize)]
+pub struct MidiRawMessage {
+    pub device_id: String,
+    pub status: u8,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MidiOutputStatus {
+    pub output_device_id: String,
+    pub health: OutputHealth,
+    pub reconnect_failures: u32,
+}
+
+/// A 14-bit CC pair: `msb` carries the coarse value and `lsb` the fine one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighResPair {
+    pub device_id: String,
+    pub channel: u8,
+    pub msb: u8,
+    pub lsb: u8,
+}
+
+/// Parser state for combining configured MSB/LSB CC pairs.
+#[derive(Default)]
+struct HighResCc {
+    pairs: Vec<HighResPair>,
+    msb_values: HashMap<(u8, u8), u8>,
+}
+
+struct OutputConnection {
+    connection: Option<MidiOutputConnection>,
+    last_send: Option<std::time::Instant>,
     last_reconnect_attempt: Option<std::time::Instant>,
     reconnect_failures: u32,
+    health: OutputHealth,
+    /// Set on every health transition until `take_output_status_changes` reports it.
+    health_changed: bool,
+    /// Start of the current raw-send rate window and the bytes sent in it.
+    raw_window: Option<(Instant, usize)>,
+    /// True for the device's configured outputs, which receive unrouted feedback.
+    /// Ports opened only because a binding routes to them keep this false.
+    mirror: bool,
 }
 
 impl MidiManager {
     pub fn new() -> Self {
         Self {
             input_connection: None,
-            output_connections: Vec::new(),
+            output_connections: HashMap::new(),
+            high_res: Arc::new(Mutex::new(HighResCc::default())),
             active_device: None,
-            active_output_device: None,
-            last_reconnect_attempt: None,
-            reconnect_failures: 0,
+            connected_at: None,
+            on_event: None,
+            last_input_check: None,
+            last_input_reconnect: None,
+            reconnect_policy: ReconnectPolicy::default(),
+            raw_listener: Arc::new(Mutex::new(None)),
+            heard_channels: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
+    pub fn set_raw_listener<F>(&self, listener: F)
+    where
+        F: Fn(MidiRawMessage) + Send + Sync + 'static,
+    {
+        if let Ok(mut raw_listener) = self.raw_listener.lock() {
+            *raw_listener = Some(Arc::new(listener));
+        }
+    }
+
+    pub fn clear_raw_listener(&self) {
+        if let Ok(mut raw_listener) = self.raw_listener.lock() {
+            *raw_listener = None;
+        }
+    }
+
+    pub fn reconnect_policy(&self) -> ReconnectPolicy {
+        self.reconnect_policy
+    }
+
+    pub fn set_reconnect_policy(&mut self, policy: ReconnectPolicy) {
+        self.reconnect_policy = policy;
+    }
+
+    pub fn output_status(&self) -> Vec<MidiOutputStatus> {
+        let mut statuses: Vec<MidiOutputStatus> = self
+            .output_connections
+            .iter()
+            .map(|(output_id, output)| output.status(output_id))
+            .collect();
+        statuses.sort_by(|a, b| a.output_device_id.cmp(&b.output_device_id));
+        statuses
+    }
+
+    /// Outputs whose health changed since the last call.
+    pub fn take_output_status_changes(&mut self) -> Vec<MidiOutputStatus> {
+        self.output_connections
+            .iter_mut()
+            .filter(|(_, output)| output.health_changed)
+            .map(|(output_id, output)| {
+                output.health_changed = false;
+                output.status(output_id)
+            })
+            .collect()
+    }
+
+    pub fn connected_at(&self) -> Option<std::time::Instant> {
+        self.connected_at
+    }
+
+    pub fn active_device(&self) -> Option<&str> {
+        self.active_device.as_deref()
+    }
+
+    /// The device's configured outputs, sorted. Ports opened only for routed
+    /// bindings are left out.
+    pub fn output_device_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .output_connections
+            .iter()
+            .filter(|(_, output)| output.mirror)
+            .map(|(output_id, _)| output_id.clone())
+            .collect();
+        ids.sort();
+        ids
+    }
+
     pub fn list_devices(&self) -> Result<Vec<DeviceInfo>> {
         let midi_in = MidiInput::new("MIDIMaster")?;
-        let ports = midi_in.ports();
-        println!("Found {} MIDI input ports", ports.len());
-        let mut devices = Vec::new();
-        for (index, port) in ports.iter().enumerate() {
-            let name = midi_in
-                .port_name(port)
-                .unwrap_or_else(|_| format!("Device {}", index));
-            println!("MIDI port {}: {}", index, name);
-            devices.push(DeviceInfo {
-                id: format!("{}{}", MIDI_PORT_PREFIX, index),
-                name,
-            });
-        }
-        if devices.is_empty() {
-            println!("MIDI: retrying device enumeration");
+        let mut names = input_port_names(&midi_in);
+        log_debug!("Found {} MIDI input ports", names.len());
+        if names.is_empty() {
+            log_debug!("MIDI: retrying device enumeration");
             let midi_in_retry = MidiInput::new("MIDIMaster")?;
-            let ports = midi_in_retry.ports();
-            for (index, port) in ports.iter().enumerate() {
-                let name = midi_in_retry
-                    .port_name(port)
-                    .unwrap_or_else(|_| format!("Device {}", index));
-                devices.push(DeviceInfo {
-                    id: format!("{}{}", MIDI_PORT_PREFIX, index),
-                    name,
-                });
-            }
+            names = input_port_names(&midi_in_retry);
         }
-        Ok(devices)
+        for (index, name) in names.iter().enumerate() {
+            log_debug!("MIDI port {}: {}", index, name);
+        }
+        Ok(device_infos(names))
     }
 
     pub fn list_output_devices(&self) -> Result<Vec<DeviceInfo>> {
         let midi_out = MidiOutput::new("MIDIMaster")?;
-        let ports = midi_out.ports();
-        println!("Found {} MIDI output ports", ports.len());
-        let mut devices = Vec::new();
-        for (index, port) in ports.iter().enumerate() {
-            let name = midi_out
-                .port_name(port)
-                .unwrap_or_else(|_| format!("Output {}", index));
-            println!("MIDI output port {}: {}", index, name);
-            devices.push(DeviceInfo {
-                id: format!("{}{}", MIDI_PORT_PREFIX, index),
-                name,
-            });
+        let names = output_port_names(&midi_out);
+        log_debug!("Found {} MIDI output ports", names.len());
+        for (index, name) in names.iter().enumerate() {
+            log_debug!("MIDI output port {}: {}", index, name);
         }
-        Ok(devices)
+        Ok(device_infos(names))
     }
 
-    fn connect_output(&mut self, output_device_id: &str) -> Result<()> {
-        // Clear existing output connections first
-        self.output_connections.clear();
-
-        let output_port_index = output_device_id
-            .strip_prefix(MIDI_PORT_PREFIX)
-            .ok_or_else(|| anyhow!("Invalid output device id"))?
-            .parse::<usize>()?;
+    fn open_output(output_device_id: &str) -> Result<MidiOutputConnection> {
         let midi_out = MidiOutput::new("MIDIMaster")?;
+        let output_port_index = resolve_port(&output_port_names(&midi_out), output_device_id)?;
         let output_port = find_output_port(&midi_out, output_port_index)?;
-        let output_connection = midi_out
+        midi_out
             .connect(&output_port, "midimaster-output")
-            .map_err(|e| anyhow!("Failed to connect to output: {}", e))?;
+            .map_err(|e| anyhow!("Failed to connect to output: {}", e))
+    }
 
-        self.output_connections = vec![output_connection];
-        self.active_output_device = Some(output_device_id.to_string());
-        self.reconnect_failures = 0; // Reset failure count on successful connect
-        println!("MIDI Output connected: {}", output_device_id);
+    fn connect_outputs(&mut self, output_device_ids: &[String]) -> Result<()> {
+        // Clear existing output connections first
+        self.output_connections.clear();
+
+        for (index, output_device_id) in output_device_ids.iter().enumerate() {
+            if self.output_connections.contains_key(output_device_id) {
+                continue;
+            }
+            // Only the primary output is required. A dead secondary port is kept as a
+            // disconnected entry so the others still come up and `send` retries it later.
+            let connection = match Self::open_output(output_device_id) {
+                Ok(connection) => {
+                    log_info!("MIDI Output connected: {}", output_device_id);
+                    Some(connection)
+                }
+                Err(err) if index == 0 => return Err(err),
+                Err(err) => {
+                    log_warn!("MIDI Output {} unavailable: {}", output_device_id, err);
+                    None
+                }
+            };
+            self.output_connections.insert(
+                output_device_id.clone(),
+                OutputConnection::new(connection, true),
+            );
+        }
         Ok(())
     }
 
+    /// Connects the input and outputs, returning the stable id of the input port
+    /// (which differs from `input_device_id` when a legacy index id was passed).
     pub fn start_device<F>(
         &mut self,
         input_device_id: &str,
-        output_device_id: &str,
+        output_device_ids: &[String],
         on_event: F,
-    ) -> Result<()>
+    ) -> Result<String>
     where
-        F: Fn(MidiEvent) + Send + 'static,
+        F: Fn(MidiEvent) + Send + Sync + 'static,
     {
         // Clear existing input connection first
         self.input_connection = None;
 
-        // Input setup
-        let input_port_index = input_device_id
-            .strip_prefix(MIDI_PORT_PREFIX)
-            .ok_or_else(|| anyhow!("Invalid input device id"))?
-            .parse::<usize>()?;
+        let on_event: EventCallback = Arc::new(on_event);
+        let (connection, stable_id) = self.open_input(input_device_id, on_event.clone())?;
+
+        // Output setup
+        self.connect_outputs(output_device_ids)?;
+
+        self.input_connection = Some(connection);
+        if self.active_device.as_deref() != Some(stable_id.as_str()) {
+            self.clear_heard_channels();
+        }
+        self.active_device = Some(stable_id.clone()); // we use input device ID as the primary ID for the session
+        self.connected_at = Some(std::time::Instant::now());
+        self.on_event = Some(on_event);
+        self.last_input_check = None;
+        self.last_input_reconnect = None;
+
+        Ok(stable_id)
+    }
+
+    fn open_input(
+        &self,
+        input_device_id: &str,
+        on_event: EventCallback,
+    ) -> Result<(MidiInputConnection<()>, String)> {
         let mut midi_in = MidiInput::new("MIDIMaster")?;
         midi_in.ignore(Ignore::None);
+        let input_names = input_port_names(&midi_in);
+        let input_port_index = resolve_port(&input_names, input_device_id)?;
         let input_port = find_input_port(&midi_in, input_port_index)?;
+        let stable_id = stable_port_ids(&input_names).swap_remove(input_port_index);
 
-        // Output setup
-        self.connect_output(output_device_id)?;
-
-        let event_device_id = input_device_id.to_string();
-        let active_device = input_device_id.to_string(); // we use input device ID as the primary ID for the session
+        let event_device_id = stable_id.clone();
+        let high_res = self.high_res.clone();
+        let raw_listener = self.raw_listener.clone();
+        let heard_channels = self.heard_channels.clone();
 
         let connection = midi_in.connect(
             &input_port,
             "midimaster-input",
             move |_timestamp, message, _| {
-                if LOG_MIDI_MESSAGES {
-                    println!("MIDI message: {:?}", message);
+                log_trace!("MIDI message: {:?}", message);
+                let listener = raw_listener
+                    .lock()
+                    .ok()
+                    .and_then(|listener| listener.clone());
+                if let (Some(listener), Some((&status, data))) = (listener, message.split_first()) {
+                    listener(MidiRawMessage {
+                        device_id: event_device_id.clone(),
+                        status,
+                        data: data.to_vec(),
+                    });
                 }
-                if let Some(event) = parse_midi_message(&event_device_id, message) {
+                let event = match high_res.lock() {
+                    Ok(mut high_res) => {
+                        parse_midi_message(&event_device_id, message, Some(&mut high_res))
+                    }
+                    Err(_) => parse_midi_message(&event_device_id, message, None),
+                };
+                if let Some(event) = event {
+                    if let Ok(mut heard) = heard_channels.lock() {
+                        heard.insert(
+                            (
+                                event.device_id.clone(),
+                                event.msg_type.clone(),
+                                event.controller,
+                            ),
+                            event.channel,
+                        );
+                    }
                     on_event(event);
                 }
             },
             (),
         )?;
 
-        self.input_connection = Some(connection);
-        self.active_device = Some(active_device);
+        Ok((connection, stable_id))
+    }
 
-        Ok(())
+    /// Watches the active input port: drops the connection when the port
+    /// disappears and reopens it once it is back. Returns the new connected
+    /// state when it changed.
+    pub fn check_input(&mut self) -> Option<bool> {
+        let device_id = self.active_device.clone()?;
+        let on_event = self.on_event.clone()?;
+        if self
+            .last_input_check
+            .map(|time| time.elapsed() < INPUT_CHECK_INTERVAL)
+            .unwrap_or(false)
+        {
+            return None;
+        }
+        self.last_input_check = Some(Instant::now());
+
+        let present = MidiInput::new("MIDIMaster")
+            .map(|midi_in| resolve_port(&input_port_names(&midi_in), &device_id).is_ok())
+            .unwrap_or(false);
+
+        if self.input_connection.is_some() {
+            if present {
+                return None;
+            }
+            log_warn!("MIDI: Input {} disconnected", device_id);
+            self.input_connection = None;
+            self.connected_at = None;
+            return Some(false);
+        }
+
+        if !present
+            || self
+                .last_input_reconnect
+                .map(|time| time.elapsed() < INPUT_RECONNECT_COOLDOWN)
+                .unwrap_or(false)
+        {
+            return None;
+        }
+        self.last_input_reconnect = Some(Instant::now());
+        match self.open_input(&device_id, on_event) {
+            Ok((connection, _)) => {
+                log_info!("MIDI: Reconnected to input {}", device_id);
+                self.input_connection = Some(connection);
+                self.connected_at = Some(Instant::now());
+                Some(true)
+            }
+            Err(err) => {
+                log_warn!("MIDI: Input reconnect failed: {}", err);
+                None
+            }
+        }
+    }
+
+    /// Replaces the configured 14-bit CC pairs; stale MSB values are dropped.
+    pub fn set_high_res_pairs(&mut self, pairs: Vec<HighResPair>) {
+        if let Ok(mut high_res) = self.high_res.lock() {
+            if high_res.pairs != pairs {
+                high_res.pairs = pairs;
+                high_res.msb_values.clear();
+            }
+        }
+    }
+
+    fn clear_heard_channels(&self) {
+        if let Ok(mut heard) = self.heard_channels.lock() {
+            heard.clear();
+        }
     }
 
     pub fn stop(&mut self) {
         self.input_connection.take();
+        self.clear_heard_channels();
         self.output_connections.clear();
         self.active_device = None;
-        self.active_output_device = None;
+        self.connected_at = None;
+        self.on_event = None;
     }
 
-    pub fn send_feedback(
-        &mut self,
-        device_id: &str,
-        channel: u8,
-        controller: u8,
-        value: f32, // volume or mute state (1.0 = on/muted, 0.0 = off/unmuted)
-        msg_type: crate::model::MidiMessageType,
-    ) -> Result<()> {
+    /// Sends each configured keep-alive to outputs that have been idle for its interval.
+    pub fn send_heartbeats(&mut self, heartbeats: &[MidiHeartbeat]) {
+        for heartbeat in heartbeats {
+            if heartbeat.interval_ms == 0 {
+                continue;
+            }
+            let Some(output) = self.output_connections.get_mut(&heartbeat.output_device_id) else {
+                continue;
+            };
+            let idle = output
+                .last_send
+                .map(|time| time.elapsed().as_millis() as u64 >= heartbeat.interval_ms)
+                .unwrap_or(true);
+            if !idle {
+                continue;
+            }
+            let message: &[u8] = if heartbeat.message.is_empty() {
+                &[0xFE]
+            } else {
+                &heartbeat.message
+            };
+            output.send(&heartbeat.output_device_id, message, self.reconnect_policy);
+        }
+    }
+
+    /// Sends feedback for a binding, applying its SysEx template or CC feedback table.
+    pub fn send_binding_feedback(&mut self, binding: &Binding, value: f32) -> Result<()> {
+        match self.feedback_frame(binding, value) {
+            Some(messages) => self.send_feedback_messages(binding, &messages),
+            None => Ok(()),
+        }
+    }
+
+    /// Turns a binding's LED off on shutdown with its `led_off_value`, sent as-is
+    /// rather than through its volume curve or feedback table.
+    pub fn send_binding_off(&mut self, binding: &Binding) -> Result<()> {
+        match self.off_frame(binding) {
+            Some(messages) => self.send_feedback_messages(binding, &messages),
+            None => Ok(()),
+        }
+    }
+
+    /// The messages `send_binding_feedback` would send for `value`, or None when
+    /// the binding has nothing to send.
+    fn feedback_frame(&self, binding: &Binding, value: f32) -> Option<Vec<Vec<u8>>> {
+        if !binding.feedback_enabled {
+            return None;
+        }
+        // Volume feedback is expressed as a control position; button states pass through.
+        let value = if binding.action == BindingAction::Volume {
+            let position = volume_to_position(binding, value);
+            if binding.invert && matches!(binding.mode, MidiMode::Absolute) {
+                1.0 - position
+            } else {
+                position
+            }
+        } else {
+            value
+        };
+        if let Some(frame) = binding
+            .feedback_sysex
+            .as_ref()
+            .and_then(|template| template.render(value))
+        {
+            return Some(vec![frame]);
+        }
+        if !binding.control.msg_type.accepts_feedback() {
+            return None;
+        }
+        let (channel, controller) = self.feedback_address(binding);
+        Some(feedback_messages(
+            channel,
+            controller,
+            value,
+            binding.control.msg_type.clone(),
+            binding.control.lsb_controller,
+            &binding.feedback_table,
+            binding.pitch_bend_centered,
+        ))
+    }
+
+    /// The messages `send_binding_off` would send.
+    fn off_frame(&self, binding: &Binding) -> Option<Vec<Vec<u8>>> {
+        let Some(off_value) = binding.led_off_value else {
+            return self.feedback_frame(binding, 0.0);
+        };
+        if !binding.feedback_enabled {
+            return None;
+        }
+        let value = off_value.min(127) as f32 / 127.0;
+        if let Some(frame) = binding
+            .feedback_sysex
+            .as_ref()
+            .and_then(|template| template.render(value))
+        {
+            return Some(vec![frame]);
+        }
+        if !binding.control.msg_type.accepts_feedback() {
+            return None;
+        }
+        let (channel, controller) = self.feedback_address(binding);
+        Some(feedback_messages(
+            channel,
+            controller,
+            value,
+            binding.control.msg_type.clone(),
+            None,
+            &[],
+            binding.pitch_bend_centered,
+        ))
+    }
+
+    /// Where feedback for `binding` goes. An any-channel control is answered on
+    /// the channel it was last heard on, since that's the mode the controller is
+    /// in; an explicit `feedback_channel` still wins.
+    fn feedback_address(&self, binding: &Binding) -> (u8, u8) {
+        let (channel, controller) = binding.feedback_address();
+        if !binding.control.any_channel || binding.feedback_channel.is_some() {
+            return (channel, controller);
+        }
+        let heard = self.heard_channels.lock().ok().and_then(|heard| {
+            heard
+                .get(&(
+                    binding.device_id.clone(),
+                    binding.control.msg_type.clone(),
+                    binding.control.controller,
+                ))
+                .copied()
+        });
+        (heard.unwrap_or(channel), controller)
+    }
+
+    /// Sends one complete MIDI message, as given, to an open output.
+    pub fn send_raw(&mut self, output_id: &str, message: &[u8]) -> Result<()> {
+        validate_midi_message(message)?;
+        let output = self
+            .output_connections
+            .get_mut(output_id)
+            .ok_or_else(|| anyhow!("MIDI output {} is not open", output_id))?;
+        let now = Instant::now();
+        let sent = match output.raw_window {
+            Some((start, sent)) if now.duration_since(start) < RAW_SEND_WINDOW => sent,
+            _ => {
+                output.raw_window = Some((now, 0));
+                0
+            }
+        };
+        if sent + message.len() > MAX_RAW_BYTES_PER_WINDOW {
+            return Err(anyhow!("Too much MIDI data sent to {}", output_id));
+        }
+        if let Some((_, bytes)) = output.raw_window.as_mut() {
+            *bytes = sent + message.len();
+        }
+        output.send(output_id, message, self.reconnect_policy);
+        Ok(())
+    }
+
+    fn send_feedback_messages(&mut self, binding: &Binding, messages: &[Vec<u8>]) -> Result<()> {
         // We only send feedback if the requested device matches our active ONE
-        if self.active_device.as_deref() != Some(device_id) {
+        if self.active_device.as_deref() != Some(binding.device_id.as_str()) {
             return Ok(());
         }
 
-        let clamped = value.clamp(0.0, 1.0);
-
-        let message = match msg_type {
-            crate::model::MidiMessageType::Note => {
-                let status = 0x90 | (channel & 0x0F);
-                let velocity = (clamped * 127.0).round() as u8;
-                vec![status, controller, velocity]
+        // Routed bindings go to their own port, opened on first use; the rest are
+        // mirrored to every configured output.
+        if let Some(output_id) = binding.output_device_id.as_deref() {
+            let output = self
+                .output_connections
+                .entry(output_id.to_string())
+                .or_insert_with(|| OutputConnection::new(None, false));
+            for message in messages {
+                output.send(output_id, message, self.reconnect_policy);
             }
-            crate::model::MidiMessageType::PitchBend => {
-                let status = 0xE0 | (channel & 0x0F);
+            return Ok(());
+        }
+        for (output_id, output) in self.output_connections.iter_mut() {
+            if output.mirror {
+                for message in messages {
+                    output.send(output_id, message, self.reconnect_policy);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Accepts exactly one well-formed message: a channel or system message of the
+/// length its status byte calls for, or a complete `F0 ... F7` SysEx frame.
+pub(crate) fn validate_midi_message(message: &[u8]) -> Result<()> {
+    let (&status, data) = message
+        .split_first()
+        .ok_or_else(|| anyhow!("MIDI message is empty"))?;
+    if status == 0xF0 {
+        if message.len() > MAX_SYSEX_LEN {
+            return Err(anyhow!(
+                "SysEx message is longer than {} bytes",
+                MAX_SYSEX_LEN
+            ));
+        }
+        let Some((&0xF7, body)) = data.split_last() else {
+            return Err(anyhow!("SysEx message must end with F7"));
+        };
+        if body.iter().any(|byte| *byte >= 0x80) {
+            return Err(anyhow!("SysEx data bytes must be below 0x80"));
+        }
+        return Ok(());
+    }
+    let length = match status {
+        0x80..=0xBF | 0xE0..=0xEF | 0xF2 => 3,
+        0xC0..=0xDF | 0xF1 | 0xF3 => 2,
+        0xF6 | 0xF8 | 0xFA..=0xFC | 0xFE | 0xFF => 1,
+        0x00..=0x7F => return Err(anyhow!("MIDI message must start with a status byte")),
+        _ => return Err(anyhow!("Unsupported MIDI status byte {:02X}", status)),
+    };
+    if message.len() != length {
+        return Err(anyhow!(
+            "MIDI message with status {:02X} must be {} bytes",
+            status,
+            length
+        ));
+    }
+    if data.iter().any(|byte| *byte >= 0x80) {
+        return Err(anyhow!("MIDI data bytes must be below 0x80"));
+    }
+    Ok(())
+}
+
+/// Renders feedback as complete channel messages. A 14-bit CC is two messages,
+/// MSB then LSB: WinMM only sends SysEx frames longer than 3 bytes.
+fn feedback_messages(
+    channel: u8,
+    controller: u8,
+    value: f32,
+    msg_type: crate::model::MidiMessageType,
+    lsb_controller: Option<u8>,
+    table: &[u8],
+    pitch_bend_centered: bool,
+) -> Vec<Vec<u8>> {
+    let clamped = value.clamp(0.0, 1.0);
+
+    match msg_type {
+        crate::model::MidiMessageType::Note => {
+            let status = 0x90 | (channel & 0x0F);
+            let velocity = (clamped * 127.0).round() as u8;
+            vec![vec![status, controller, velocity]]
+        }
+        crate::model::MidiMessageType::PitchBend => {
+            let status = 0xE0 | (channel & 0x0F);
+            let value14 = if pitch_bend_centered {
+                (PITCH_BEND_CENTER + clamped * (16383.0 - PITCH_BEND_CENTER)).round() as u16
+            } else {
+                (clamped * 16383.0).round() as u16
+            };
+            let lsb = (value14 & 0x7F) as u8;
+            let msb = ((value14 >> 7) & 0x7F) as u8;
+            vec![vec![status, lsb, msb]]
+        }
+        crate::model::MidiMessageType::ControlChange => {
+            let status = 0xB0 | (channel & 0x0F);
+            if let (Some(lsb_controller), true) = (lsb_controller, table.is_empty()) {
                 let value14 = (clamped * 16383.0).round() as u16;
                 let lsb = (value14 & 0x7F) as u8;
                 let msb = ((value14 >> 7) & 0x7F) as u8;
-                vec![status, lsb, msb]
-            }
-            crate::model::MidiMessageType::ControlChange => {
-                let status = 0xB0 | (channel & 0x0F);
-                let value7 = (clamped * 127.0).round() as u8;
-                vec![status, controller, value7]
+                return vec![
+                    vec![status, controller, msb],
+                    vec![status, lsb_controller, lsb],
+                ];
             }
+            let value7 = if table.is_empty() {
+                (clamped * 127.0).round() as u8
+            } else {
+                let level = (clamped * (table.len() - 1) as f32).round() as usize;
+                table[level.min(table.len() - 1)] & 0x7F
+            };
+            vec![vec![status, controller, value7]]
+        }
+        crate::model::MidiMessageType::ProgramChange
+        | crate::model::MidiMessageType::ChannelPressure
+        | crate::model::MidiMessageType::PolyAftertouch => Vec::new(),
+    }
+}
+
+impl OutputConnection {
+    fn new(connection: Option<MidiOutputConnection>, mirror: bool) -> Self {
+        let health = if connection.is_some() {
+            OutputHealth::Connected
+        } else {
+            OutputHealth::Reconnecting
         };
+        Self {
+            connection,
+            last_send: None,
+            last_reconnect_attempt: None,
+            reconnect_failures: 0,
+            health,
+            health_changed: true,
+            raw_window: None,
+            mirror,
+        }
+    }
 
-        // Early exit if no output is connected yet (prevents spam on startup)
-        if self.active_output_device.is_none() {
-            return Ok(());
+    fn status(&self, output_id: &str) -> MidiOutputStatus {
+        MidiOutputStatus {
+            output_device_id: output_id.to_string(),
+            health: self.health,
+            reconnect_failures: self.reconnect_failures,
         }
+    }
+
+    fn set_health(&mut self, health: OutputHealth) {
+        if self.health != health {
+            self.health = health;
+            self.health_changed = true;
+        }
+    }
 
-        let mut send_success = false;
-        if let Some(conn) = self.output_connections.get_mut(0) {
-            if conn.send(&message).is_ok() {
-                send_success = true;
+    fn send(&mut self, output_id: &str, message: &[u8], policy: ReconnectPolicy) {
+        self.last_send = Some(std::time::Instant::now());
+        if let Some(conn) = self.connection.as_mut() {
+            if conn.send(message).is_ok() {
+                return;
             }
         }
 
-        if !send_success {
-            // Rate limit reconnection attempts: wait at least 5 seconds between attempts
-            // and give up after 3 consecutive failures
-            const RECONNECT_COOLDOWN_SECS: u64 = 5;
-            const MAX_RECONNECT_FAILURES: u32 = 3;
+        // Rate limit reconnection attempts: wait at least `policy.cooldown` between
+        // attempts and give up after `policy.max_failures` consecutive failures
+        let should_attempt = self
+            .last_reconnect_attempt
+            .map(|t| t.elapsed() >= policy.cooldown)
+            .unwrap_or(true);
 
-            let should_attempt = self
-                .last_reconnect_attempt
-                .map(|t| t.elapsed().as_secs() >= RECONNECT_COOLDOWN_SECS)
-                .unwrap_or(true);
+        if self.reconnect_failures >= policy.max_failures {
+            self.set_health(OutputHealth::Failed);
+            return;
+        }
+        self.set_health(OutputHealth::Reconnecting);
+        if !should_attempt {
+            // Too soon after the last attempt
+            return;
+        }
 
-            if !should_attempt || self.reconnect_failures >= MAX_RECONNECT_FAILURES {
-                // Silently skip reconnection - either too soon or too many failures
-                return Ok(());
-            }
+        self.last_reconnect_attempt = Some(std::time::Instant::now());
+        log_warn!("MIDI: Output {} failed, attempting reconnect...", output_id);
 
-            self.last_reconnect_attempt = Some(std::time::Instant::now());
-            println!("MIDI: Output failed, attempting reconnect...");
-
-            if let Some(output_id) = self.active_output_device.clone() {
-                // Clear old connections first to release the port
-                self.output_connections.clear();
-
-                match self.connect_output(&output_id) {
-                    Ok(_) => {
-                        println!("MIDI: Reconnected to output {}", output_id);
-                        if let Some(conn) = self.output_connections.get_mut(0) {
-                            if let Err(e) = conn.send(&message) {
-                                println!("MIDI: Retry send failed: {}", e);
-                            } else {
-                                println!("MIDI: Retry send successful");
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        self.reconnect_failures += 1;
-                        if self.reconnect_failures >= MAX_RECONNECT_FAILURES {
-                            println!(
-                                "MIDI: Reconnection failed after {} attempts, giving up: {}",
-                                self.reconnect_failures, e
-                            );
-                        } else {
-                            println!(
-                                "MIDI: Reconnection failed (attempt {}): {}",
-                                self.reconnect_failures, e
-                            );
-                        }
-                    }
+        // Drop the old connection first to release the port
+        self.connection = None;
+
+        match MidiManager::open_output(output_id) {
+            Ok(mut conn) => {
+                log_info!("MIDI: Reconnected to output {}", output_id);
+                self.reconnect_failures = 0; // Reset failure count on successful connect
+                self.set_health(OutputHealth::Connected);
+                if let Err(e) = conn.send(message) {
+                    log_warn!("MIDI: Retry send failed: {}", e);
+                } else {
+                    log_debug!("MIDI: Retry send successful");
+                }
+                self.connection = Some(conn);
+            }
+            Err(e) => {
+                self.reconnect_failures += 1;
+                if self.reconnect_failures >= policy.max_failures {
+                    self.set_health(OutputHealth::Failed);
+                    log_error!(
+                        "MIDI: Reconnection to {} failed after {} attempts, giving up: {}",
+                        output_id,
+                        self.reconnect_failures,
+                        e
+                    );
+                } else {
+                    log_warn!(
+                        "MIDI: Reconnection to {} failed (attempt {}): {}",
+                        output_id,
+                        self.reconnect_failures,
+                        e
+                    );
                 }
             }
         }
-        Ok(())
     }
 }
 
+fn input_port_names(midi_in: &MidiInput) -> Vec<String> {
+    midi_in
+        .ports()
+        .iter()
+        .enumerate()
+        .map(|(index, port)| {
+            midi_in
+                .port_name(port)
+                .unwrap_or_else(|_| format!("Device {}", index))
+        })
+        .collect()
+}
+
+fn output_port_names(midi_out: &MidiOutput) -> Vec<String> {
+    midi_out
+        .ports()
+        .iter()
+        .enumerate()
+        .map(|(index, port)| {
+            midi_out
+                .port_name(port)
+                .unwrap_or_else(|_| format!("Output {}", index))
+        })
+        .collect()
+}
+
+fn device_infos(names: Vec<String>) -> Vec<DeviceInfo> {
+    stable_port_ids(&names)
+        .into_iter()
+        .zip(names)
+        .enumerate()
+        .map(|(index, (id, name))| DeviceInfo {
+            id,
+            name,
+            legacy_id: format!("{}{}", MIDI_PORT_PREFIX, index),
+        })
+        .collect()
+}
+
+fn sanitize_port_name(name: &str) -> String {
+    let mut sanitized = String::new();
+    for ch in name.trim().chars() {
+        if ch.is_alphanumeric() {
+            sanitized.extend(ch.to_lowercase());
+        } else if !sanitized.is_empty() && !sanitized.ends_with('-') {
+            sanitized.push('-');
+        }
+    }
+    sanitized.trim_end_matches('-').to_string()
+}
+
+/// Name-based ids for ports in enumeration order; repeated names are numbered
+/// by their order among the ports sharing that name.
+fn stable_port_ids(names: &[String]) -> Vec<String> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    names
+        .iter()
+        .map(|name| {
+            let sanitized = sanitize_port_name(name);
+            let count = seen.entry(sanitized.clone()).or_insert(0);
+            *count += 1;
+            if *count == 1 {
+                format!("{}{}", MIDI_PORT_NAME_PREFIX, sanitized)
+            } else {
+                format!("{}{}#{}", MIDI_PORT_NAME_PREFIX, sanitized, count)
+            }
+        })
+        .collect()
+}
+
+/// Maps a device id to the current port index. Name-based ids never fall back
+/// to another port when the named one is absent.
+fn resolve_port(names: &[String], device_id: &str) -> Result<usize> {
+    if device_id.starts_with(MIDI_PORT_NAME_PREFIX) {
+        return stable_port_ids(names)
+            .iter()
+            .position(|id| id == device_id)
+            .ok_or_else(|| anyhow!("MIDI device not connected: {}", device_id));
+    }
+    let index = device_id
+        .strip_prefix(MIDI_PORT_PREFIX)
+        .ok_or_else(|| anyhow!("Invalid MIDI device id: {}", device_id))?
+        .parse::<usize>()?;
+    if index >= names.len() {
+        return Err(anyhow!("MIDI device not connected: {}", device_id));
+    }
+    Ok(index)
+}
+
 fn find_input_port(midi_in: &MidiInput, index: usize) -> Result<MidiInputPort> {
     midi_in
         .ports()
@@ -272,23 +929,42 @@ fn find_output_port(midi_out: &MidiOutput, index: usize) -> Result<MidiOutputPor
         .ok_or_else(|| anyhow!("MIDI output port not found"))
 }
 
-fn parse_midi_message(device_id: &str, message: &[u8]) -> Option<MidiEvent> {
-    if message.len() < 3 {
-        return None;
-    }
-    let status = message[0];
+fn parse_midi_message(
+    device_id: &str,
+    message: &[u8],
+    high_res: Option<&mut HighResCc>,
+) -> Option<MidiEvent> {
+    let status = *message.first()?;
     let command = status & 0xF0;
     let channel = status & 0x0F;
+    // Program Change and Channel Pressure carry a single data byte.
+    let expected_len = if matches!(command, 0xC0 | 0xD0) { 2 } else { 3 };
+    if message.len() < expected_len {
+        return None;
+    }
 
     match command {
-        0xB0 => Some(MidiEvent {
-            device_id: device_id.to_string(),
-            channel,
-            controller: message[1],
-            value: message[2],
-            value_14: None,
-            msg_type: crate::model::MidiMessageType::ControlChange,
-        }),
+        0xB0 => {
+            let controller = message[1];
+            let value = message[2];
+            if let Some(high_res) = high_res {
+                if let Some(event) =
+                    parse_high_res_cc(high_res, device_id, channel, controller, value)
+                {
+                    return Some(event);
+                }
+            }
+            Some(MidiEvent {
+                device_id: device_id.to_string(),
+                channel,
+                controller,
+                value,
+                value_14: None,
+                msg_type: crate::model::MidiMessageType::ControlChange,
+                is_note_off: false,
+                release_velocity: None,
+            })
+        }
         0x90 | 0x80 => Some(MidiEvent {
             device_id: device_id.to_string(),
             channel,
@@ -296,6 +972,8 @@ fn parse_midi_message(device_id: &str, message: &[u8]) -> Option<MidiEvent> {
             value: if command == 0x80 { 0 } else { message[2] }, // Note Off = velocity 0
             value_14: None,
             msg_type: crate::model::MidiMessageType::Note,
+            is_note_off: command == 0x80,
+            release_velocity: (command == 0x80).then_some(message[2]),
         }),
         0xE0 => {
             let lsb = message[1] as u16;
@@ -308,8 +986,163 @@ fn parse_midi_message(device_id: &str, message: &[u8]) -> Option<MidiEvent> {
                 value: message[2],
                 value_14: Some(value_14),
                 msg_type: crate::model::MidiMessageType::PitchBend,
+                is_note_off: false,
+                release_velocity: None,
             })
         }
+        0xA0 => Some(MidiEvent {
+            device_id: device_id.to_string(),
+            channel,
+            controller: message[1], // Note number
+            value: message[2],      // Pressure
+            value_14: None,
+            msg_type: crate::model::MidiMessageType::PolyAftertouch,
+            is_note_off: false,
+            release_velocity: None,
+        }),
+        0xC0 => Some(MidiEvent {
+            device_id: device_id.to_string(),
+            channel,
+            controller: message[1], // Program number
+            value: 127,
+            value_14: None,
+            msg_type: crate::model::MidiMessageType::ProgramChange,
+            is_note_off: false,
+            release_velocity: None,
+        }),
+        0xD0 => Some(MidiEvent {
+            device_id: device_id.to_string(),
+            channel,
+            controller: 0xD0,
+            value: message[1],
+            value_14: None,
+            msg_type: crate::model::MidiMessageType::ChannelPressure,
+            is_note_off: false,
+            release_velocity: None,
+        }),
         _ => None,
     }
 }
+
+/// Combines a configured MSB/LSB CC pair into a 14-bit event reported on the MSB
+/// controller. An MSB on its own resets the fine value to 0 until the LSB arrives.
+fn parse_high_res_cc(
+    high_res: &mut HighResCc,
+    device_id: &str,
+    channel: u8,
+    controller: u8,
+    value: u8,
+) -> Option<MidiEvent> {
+    let pair = high_res.pairs.iter().find(|pair| {
+        pair.device_id == device_id
+            && pair.channel == channel
+            && (pair.msb == controller || pair.lsb == controller)
+    })?;
+    let msb_controller = pair.msb;
+    let (msb, lsb) = if controller == msb_controller {
+        high_res.msb_values.insert((channel, msb_controller), value);
+        (value, 0)
+    } else {
+        let msb = *high_res.msb_values.get(&(channel, msb_controller))?;
+        (msb, value)
+    };
+    Some(MidiEvent {
+        device_id: device_id.to_string(),
+        channel,
+        controller: msb_controller,
+        value: msb,
+        value_14: Some(((msb as u16) << 7) | lsb as u16),
+        msg_type: crate::model::MidiMessageType::ControlChange,
+        is_note_off: false,
+        release_velocity: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn binding(feedback_enabled: bool) -> Binding {
+        serde_json::from_value(json!({
+            "id": "mute",
+            "device_id": "midi:name:test",
+            "control": { "channel": 0, "controller": 16, "msg_type": "ControlChange" },
+            "action": "ToggleMute",
+            "target": "Master",
+            "mode": "Absolute",
+            "deadzone": 0.0,
+            "debounce_ms": 0,
+            "feedback_enabled": feedback_enabled,
+            "feedback_sysex": { "bytes": [0xF0, 0x00, 0x20, 0x00, 0xF7], "value_positions": [3] },
+            "led_off_value": 0,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn disabled_binding_produces_no_feedback_bytes() {
+        let manager = MidiManager::new();
+        let mut disabled = binding(false);
+        for value in [0.0, 0.5, 1.0] {
+            assert_eq!(manager.feedback_frame(&disabled, value), None);
+        }
+        assert_eq!(manager.off_frame(&disabled), None);
+
+        // Neither the plain CC path nor the default off value sneak through.
+        disabled.feedback_sysex = None;
+        disabled.led_off_value = None;
+        assert_eq!(manager.feedback_frame(&disabled, 1.0), None);
+        assert_eq!(manager.off_frame(&disabled), None);
+    }
+
+    #[test]
+    fn enabled_binding_renders_its_feedback() {
+        let manager = MidiManager::new();
+        let enabled = binding(true);
+        assert_eq!(
+            manager.feedback_frame(&enabled, 1.0),
+            Some(vec![vec![0xF0, 0x00, 0x20, 0x7F, 0xF7]])
+        );
+        assert_eq!(
+            manager.off_frame(&enabled),
+            Some(vec![vec![0xF0, 0x00, 0x20, 0x00, 0xF7]])
+        );
+    }
+
+    #[test]
+    fn feedback_is_sent_as_short_messages() {
+        use crate::model::MidiMessageType;
+
+        let high_res = feedback_messages(
+            2,
+            7,
+            1.0,
+            MidiMessageType::ControlChange,
+            Some(39),
+            &[],
+            false,
+        );
+        assert_eq!(high_res, vec![vec![0xB2, 7, 0x7F], vec![0xB2, 39, 0x7F]]);
+
+        let msg_types = [
+            MidiMessageType::Note,
+            MidiMessageType::ControlChange,
+            MidiMessageType::PitchBend,
+        ];
+        for msg_type in msg_types {
+            for lsb_controller in [None, Some(39)] {
+                for value in [0.0, 0.3, 1.0] {
+                    let messages =
+                        feedback_messages(0, 7, value, msg_type.clone(), lsb_controller, &[], true);
+                    assert!(!messages.is_empty());
+                    for message in messages {
+                        // WinMM refuses non-SysEx messages longer than 3 bytes.
+                        assert!(message.len() <= 3, "{:?}", message);
+                        validate_midi_message(&message).unwrap();
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::audio::DEFAULT_DEVICE_ID;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -5,6 +6,9 @@ use std::collections::HashMap;
 pub struct DeviceInfo {
     pub id: String,
     pub name: String,
+    /// Index-based id used before name-based ids, for migrating saved settings.
+    #[serde(default)]
+    pub legacy_id: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +21,16 @@ pub struct SessionInfo {
     pub volume: f32,
     pub is_muted: bool,
     pub is_master: bool,
+    #[serde(default)]
+    pub alias: Option<String>,
+}
+
+/// Current level of a binding target. For an application balance pair, `volume`
+/// is the balance position and `muted` means both sides are muted.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TargetState {
+    pub volume: f32,
+    pub muted: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +41,12 @@ pub struct PlaybackDeviceInfo {
     pub volume: f32,
     pub is_muted: bool,
     pub is_default: bool,
+    /// Default endpoint for the Communications role (chat apps), which can
+    /// differ from the multimedia default.
+    #[serde(default)]
+    pub is_comms_default: bool,
+    #[serde(default)]
+    pub alias: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -34,6 +54,22 @@ pub enum MidiMessageType {
     ControlChange,
     Note,
     PitchBend,
+    /// Reported with the program number as `controller`; each message is a press.
+    ProgramChange,
+    /// Reported on controller `0xD0` with the pressure as `value`.
+    ChannelPressure,
+    /// Per-note pressure, reported with the note number as `controller`.
+    PolyAftertouch,
+}
+
+impl MidiMessageType {
+    /// Whether the controller can display a value sent back as the same message type.
+    pub fn accepts_feedback(&self) -> bool {
+        matches!(
+            self,
+            MidiMessageType::ControlChange | MidiMessageType::Note | MidiMessageType::PitchBend
+        )
+    }
 }
 
 impl Default for MidiMessageType {
@@ -44,10 +80,19 @@ impl Default for MidiMessageType {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct MidiControl {
+    /// For `any_channel` controls, the channel feedback goes to until the
+    /// control has been heard on one.
     pub channel: u8,
     pub controller: u8,
     #[serde(default)]
     pub msg_type: MidiMessageType,
+    /// CC number carrying the fine half of a 14-bit value (e.g. 39 for CC 7).
+    #[serde(default)]
+    pub lsb_controller: Option<u8>,
+    /// Matches the control on every channel, for controllers that move it between
+    /// channels by mode. A binding for the exact channel takes precedence.
+    #[serde(default)]
+    pub any_channel: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,10 +101,78 @@ pub enum MidiMode {
     Relative,
 }
 
+/// How a relative encoder encodes its signed step in the 7-bit value.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RelativeMode {
+    /// Bit 6 is the direction, the low bits the magnitude (65 = -1).
+    #[default]
+    SignedBit,
+    /// 127 = -1, 64 = -64.
+    TwosComplement,
+    /// 64 is zero, 65 = +1, 63 = -1.
+    BinaryOffset,
+}
+
+/// Shape of the position-to-volume mapping for volume bindings.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum VolumeCurve {
+    #[default]
+    Linear,
+    /// Audio taper: equal fader travel gives equal dB steps down to -60 dB.
+    Logarithmic,
+    /// `volume = position ^ exponent`.
+    Power(f32),
+}
+
+/// How a ToggleMute button behaves.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ButtonMode {
+    /// Each press flips the mute state.
+    #[default]
+    Toggle,
+    /// Muted while held, unmuted on release.
+    Momentary,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum BindingAction {
     Volume,
     ToggleMute,
+    /// Unmutes the target while the button is held, re-muting `release_delay_ms` after release.
+    /// Its feedback is lit while talking (unmuted), the opposite of ToggleMute.
+    PushToTalk,
+    /// Button press routes the target application to `device_id` (empty = system default).
+    RouteToDevice {
+        device_id: String,
+    },
+    /// Button press nudges the target volume by `delta` (negative steps down).
+    VolumeStep {
+        delta: f32,
+    },
+    /// Switches the active binding layer: while held, or toggled on each press when `latch`.
+    Shift {
+        layer: u8,
+        #[serde(default)]
+        latch: bool,
+    },
+    /// Button press sets the target to a fixed volume.
+    SetVolume {
+        #[serde(deserialize_with = "deserialize_unit_volume")]
+        value: f32,
+    },
+    /// Button press drops the target to `level`; the next press restores the
+    /// volume it had before, even if it was changed while ducked.
+    Duck {
+        #[serde(deserialize_with = "deserialize_unit_volume")]
+        level: f32,
+    },
+    /// Button press makes the next active playback device the system default.
+    CycleDefaultDevice,
+    /// Fader pans a Master or Device target's left/right channels, centred at mid-travel.
+    Balance,
+    /// Button press mutes every other application in the binding's `mute_group`;
+    /// a second press restores the previous mute states.
+    Solo,
 }
 
 impl Default for BindingAction {
@@ -72,15 +185,43 @@ impl Default for BindingAction {
 pub enum BindingTarget {
     Master,
     Focus,
+    /// Windows' notification-sound session, hidden from the application list.
+    SystemSounds,
+    /// Whatever the default recording device is when the binding fires.
+    DefaultMicrophone,
+    /// Whatever the default playback device is when the binding fires.
+    DefaultSpeakers,
+    /// One audio session. `session_id` includes the process id, so once the app
+    /// restarts it's matched by the rest of the id (see
+    /// `app_match::stable_session_key`) and, when known, the executable path.
     Session {
         session_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
     },
     Application {
         name: String,
+        /// Full executable path captured when the target was picked. A running
+        /// app at exactly this path is preferred, so two installs of the same
+        /// app can be told apart; `name` still matches when no app has it (e.g.
+        /// the profile was made on another machine).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
     },
     Device {
         device_id: String,
     },
+    /// Constant-power balance between two applications: the fader shifts
+    /// emphasis from `left` to `right` without a loudness dip at center.
+    ApplicationBalance {
+        left: String,
+        right: String,
+    },
+    /// Several applications moved and muted together, e.g. every browser on one
+    /// fader. Members that aren't running are skipped.
+    ApplicationGroup {
+        names: Vec<String>,
+    },
     /// Generic integration target.
     ///
     /// This is the stable extensibility point for third-party integration plugins.
@@ -103,6 +244,60 @@ pub enum BindingTarget {
 // Older profiles stored OBS/WaveLink targets as dedicated enum variants.
 // We now collapse those into `BindingTarget::Integration` so new profiles remain
 // forward-compatible with the runtime plugin system.
+impl BindingTarget {
+    /// Stable key used to look up user aliases; `None` for targets whose
+    /// display name is not fixed (Focus, integrations, unset).
+    pub fn alias_key(&self) -> Option<String> {
+        match self {
+            BindingTarget::Master => Some("master".to_string()),
+            BindingTarget::SystemSounds => Some("system_sounds".to_string()),
+            BindingTarget::DefaultMicrophone => Some("default_microphone".to_string()),
+            BindingTarget::DefaultSpeakers => Some("default_speakers".to_string()),
+            BindingTarget::Session { session_id, .. } => Some(format!("session:{}", session_id)),
+            BindingTarget::Application { name, .. } => Some(format!("app:{}", name.to_lowercase())),
+            BindingTarget::Device { device_id } => {
+                let raw = device_id.strip_prefix("playback:").unwrap_or(device_id);
+                Some(format!("device:{}", raw))
+            }
+            BindingTarget::ApplicationBalance { left, right } => Some(format!(
+                "balance:{}:{}",
+                left.to_lowercase(),
+                right.to_lowercase()
+            )),
+            BindingTarget::ApplicationGroup { names } => {
+                let mut names: Vec<String> = names.iter().map(|name| name.to_lowercase()).collect();
+                names.sort();
+                Some(format!("group:{}", names.join(",")))
+            }
+            BindingTarget::Focus | BindingTarget::Integration { .. } | BindingTarget::Unset => None,
+        }
+    }
+
+    /// Key used by OSD instance filters; extends `alias_key` to Focus and integrations.
+    pub fn osd_key(&self) -> Option<String> {
+        match self {
+            BindingTarget::Focus => Some("focus".to_string()),
+            BindingTarget::Integration { integration_id, .. } => {
+                Some(format!("integration:{}", integration_id))
+            }
+            _ => self.alias_key(),
+        }
+    }
+
+    /// The `Device` target a default-device target resolves to; the backends map
+    /// `DEFAULT_DEVICE_ID` to the current default endpoint on every call.
+    pub fn default_device(&self) -> Option<BindingTarget> {
+        let prefix = match self {
+            BindingTarget::DefaultMicrophone => "recording",
+            BindingTarget::DefaultSpeakers => "playback",
+            _ => return None,
+        };
+        Some(BindingTarget::Device {
+            device_id: format!("{}:{}", prefix, DEFAULT_DEVICE_ID),
+        })
+    }
+}
+
 impl<'de> Deserialize<'de> for BindingTarget {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -119,6 +314,9 @@ fn binding_target_from_value(v: serde_json::Value) -> Result<BindingTarget, Stri
         return match s {
             "Master" => Ok(BindingTarget::Master),
             "Focus" => Ok(BindingTarget::Focus),
+            "SystemSounds" => Ok(BindingTarget::SystemSounds),
+            "DefaultMicrophone" => Ok(BindingTarget::DefaultMicrophone),
+            "DefaultSpeakers" => Ok(BindingTarget::DefaultSpeakers),
             "Unset" => Ok(BindingTarget::Unset),
             other => Err(format!("Unknown BindingTarget string: {}", other)),
         };
@@ -160,13 +358,21 @@ fn binding_target_from_value(v: serde_json::Value) -> Result<BindingTarget, Stri
         // Core targets
         "Master" => Ok(BindingTarget::Master),
         "Focus" => Ok(BindingTarget::Focus),
+        "SystemSounds" => Ok(BindingTarget::SystemSounds),
+        "DefaultMicrophone" => Ok(BindingTarget::DefaultMicrophone),
+        "DefaultSpeakers" => Ok(BindingTarget::DefaultSpeakers),
         "Session" => {
             let session_id = val
                 .get("session_id")
                 .and_then(|v| v.as_str())
                 .ok_or_else(|| "Session.session_id missing".to_string())?
                 .to_string();
-            Ok(BindingTarget::Session { session_id })
+            let path = val
+                .get("path")
+                .and_then(|v| v.as_str())
+                .filter(|path| !path.is_empty())
+                .map(|path| path.to_string());
+            Ok(BindingTarget::Session { session_id, path })
         }
         "Application" => {
             let name = val
@@ -174,7 +380,12 @@ fn binding_target_from_value(v: serde_json::Value) -> Result<BindingTarget, Stri
                 .and_then(|v| v.as_str())
                 .ok_or_else(|| "Application.name missing".to_string())?
                 .to_string();
-            Ok(BindingTarget::Application { name })
+            let path = val
+                .get("path")
+                .and_then(|v| v.as_str())
+                .filter(|path| !path.is_empty())
+                .map(|path| path.to_string());
+            Ok(BindingTarget::Application { name, path })
         }
         "Device" => {
             let device_id = val
@@ -184,6 +395,30 @@ fn binding_target_from_value(v: serde_json::Value) -> Result<BindingTarget, Stri
                 .to_string();
             Ok(BindingTarget::Device { device_id })
         }
+        "ApplicationBalance" => {
+            let left = val
+                .get("left")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "ApplicationBalance.left missing".to_string())?
+                .to_string();
+            let right = val
+                .get("right")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "ApplicationBalance.right missing".to_string())?
+                .to_string();
+            Ok(BindingTarget::ApplicationBalance { left, right })
+        }
+        "ApplicationGroup" => {
+            let names = val
+                .get("names")
+                .and_then(|v| v.as_array())
+                .ok_or_else(|| "ApplicationGroup.names missing".to_string())?
+                .iter()
+                .filter_map(|name| name.as_str())
+                .map(|name| name.to_string())
+                .collect();
+            Ok(BindingTarget::ApplicationGroup { names })
+        }
         "Unset" => Ok(BindingTarget::Unset),
 
         // New generic integration target
@@ -307,13 +542,190 @@ pub struct Binding {
     #[serde(default)]
     pub name: String,
     pub device_id: String,
+    /// Output port that receives this binding's feedback; `None` mirrors it to every output.
+    #[serde(default)]
+    pub output_device_id: Option<String>,
+    #[serde(default)]
+    pub button_mode: ButtonMode,
+    /// Action run instead of `action` when the button is held for `long_press_ms`.
+    #[serde(default)]
+    pub long_action: Option<BindingAction>,
+    #[serde(default = "default_long_press_ms")]
+    pub long_press_ms: u64,
+    /// Action run when the button is pressed twice within the double-tap window.
+    /// Ignored when `long_action` is set.
+    #[serde(default)]
+    pub double_action: Option<BindingAction>,
+    /// Bank this binding belongs to; only bindings on the active layer respond.
+    /// Shift bindings respond on every layer.
+    #[serde(default)]
+    pub layer: u8,
+    /// Applications targeted by bindings sharing a group are what a Solo binding
+    /// in that group mutes. A Solo binding without a group mutes every application.
+    #[serde(default)]
+    pub mute_group: Option<String>,
     pub control: MidiControl,
     pub target: BindingTarget,
     #[serde(default)]
     pub action: BindingAction,
     pub mode: MidiMode,
+    #[serde(default)]
+    pub relative_mode: RelativeMode,
+    /// Extra step multiplier for fast relative encoder turns; 0 disables it.
+    #[serde(default)]
+    pub acceleration: f32,
+    /// Volume range the control's full travel maps onto.
+    #[serde(default)]
+    pub min_volume: f32,
+    #[serde(default = "default_max_volume")]
+    pub max_volume: f32,
+    #[serde(default)]
+    pub volume_curve: VolumeCurve,
+    /// Flips absolute controls that report their maximum at the bottom of travel.
+    #[serde(default)]
+    pub invert: bool,
+    /// Pitch-bend controls that rest at the 8192 center: the center reads (and
+    /// is fed back) as 0.0 and the top as 1.0, with anything below the center
+    /// also 0.0. Otherwise the full 0..16383 range maps to 0.0..1.0.
+    #[serde(default)]
+    pub pitch_bend_centered: bool,
     pub deadzone: f32,
+    /// Low-pass filter for noisy absolute faders, from 0 (off) to 0.9. Each
+    /// message moves the output this much less than the raw change.
+    #[serde(default)]
+    pub smoothing: f32,
     pub debounce_ms: u64,
+    /// Raw value a button must exceed to count as pressed.
+    ///
+    /// When unset, any non-zero value is a press and every press triggers.
+    /// When set, only the transition across the threshold triggers, which
+    /// keeps decaying pressure/aftertouch controls from re-firing.
+    #[serde(default)]
+    pub press_threshold: Option<u8>,
+    /// Push-to-talk only: how long the target stays unmuted after release.
+    #[serde(default)]
+    pub release_delay_ms: u64,
+    /// Push-to-talk only: re-mutes after the button has been held this long, in
+    /// case its release message was lost. 0 never re-mutes on its own.
+    #[serde(default = "default_ptt_max_hold_ms")]
+    pub ptt_max_hold_ms: u64,
+    /// CC values to send for each feedback level, lowest first, for LED rings
+    /// whose segments don't map linearly. Empty sends `value * 127`.
+    #[serde(default)]
+    pub feedback_table: Vec<u8>,
+    /// Sent instead of the regular feedback message when set.
+    #[serde(default)]
+    pub feedback_sysex: Option<SysexTemplate>,
+    /// Feedback shows the target's live audio peak instead of its volume.
+    /// Supported for Master, Focus, Session and Application targets.
+    #[serde(default)]
+    pub meter_feedback: bool,
+    /// How often meter feedback is polled; each poll costs an audio API round-trip.
+    #[serde(default = "default_meter_interval_ms")]
+    pub meter_interval_ms: u64,
+    /// When false nothing is ever sent back to this control, for jittery faders
+    /// or controls that share LEDs with another binding.
+    #[serde(default = "default_feedback_enabled")]
+    pub feedback_enabled: bool,
+    /// Channel feedback is sent on, for controllers that light their LEDs on a
+    /// different channel than they transmit on. Unset uses `control.channel`.
+    #[serde(default)]
+    pub feedback_channel: Option<u8>,
+    /// Note or controller number feedback is sent to. Unset uses `control.controller`.
+    #[serde(default)]
+    pub feedback_controller: Option<u8>,
+    /// Raw 7-bit value sent to turn the control's LED off when MIDIMaster quits,
+    /// for controllers where zero feedback doesn't mean "off". Unset sends zero.
+    #[serde(default)]
+    pub led_off_value: Option<u8>,
+    /// When false the OSD stays hidden for this binding's changes, e.g. for a
+    /// background app nobody needs to watch.
+    #[serde(default = "default_show_osd")]
+    pub show_osd: bool,
+}
+
+impl Binding {
+    /// Channel and note/controller number that feedback for this binding goes to.
+    pub fn feedback_address(&self) -> (u8, u8) {
+        (
+            self.feedback_channel.unwrap_or(self.control.channel),
+            self.feedback_controller.unwrap_or(self.control.controller),
+        )
+    }
+}
+
+/// A complete `F0 ... F7` frame whose bytes at `value_positions` are replaced
+/// with the feedback value scaled to `0..=value_max`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SysexTemplate {
+    pub bytes: Vec<u8>,
+    #[serde(default)]
+    pub value_positions: Vec<usize>,
+    #[serde(default = "default_sysex_value_max")]
+    pub value_max: u8,
+}
+
+fn deserialize_unit_volume<'de, D>(deserializer: D) -> Result<f32, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = f32::deserialize(deserializer)?;
+    if value.is_nan() {
+        return Err(serde::de::Error::custom("volume must be a number"));
+    }
+    Ok(value.clamp(0.0, 1.0))
+}
+
+fn default_long_press_ms() -> u64 {
+    500
+}
+
+fn default_meter_interval_ms() -> u64 {
+    100
+}
+
+fn default_feedback_enabled() -> bool {
+    true
+}
+
+fn default_show_osd() -> bool {
+    true
+}
+
+fn default_ptt_max_hold_ms() -> u64 {
+    60_000
+}
+
+fn default_max_volume() -> f32 {
+    1.0
+}
+
+fn default_sysex_value_max() -> u8 {
+    127
+}
+
+impl SysexTemplate {
+    /// Fills every placeholder; `None` if the frame is not a valid SysEx message.
+    pub fn render(&self, value: f32) -> Option<Vec<u8>> {
+        let len = self.bytes.len();
+        if len < 2 || self.bytes[0] != 0xF0 || self.bytes[len - 1] != 0xF7 {
+            return None;
+        }
+        let mut frame = self.bytes.clone();
+        let max = self.value_max.min(127) as f32;
+        let scaled = (value.clamp(0.0, 1.0) * max).round() as u8;
+        for &position in &self.value_positions {
+            if position == 0 || position >= len - 1 {
+                return None;
+            }
+            frame[position] = scaled & 0x7F;
+        }
+        // Data bytes between the frame markers must be 7-bit.
+        if frame[1..len - 1].iter().any(|byte| *byte > 0x7F) {
+            return None;
+        }
+        Some(frame)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -324,7 +736,27 @@ pub struct OsdSettings {
     pub monitor_name: Option<String>,
     #[serde(default)]
     pub monitor_id: Option<String>,
+    /// EDID-derived monitor id; unlike `monitor_id` it survives a replug.
+    #[serde(default)]
+    pub monitor_edid: Option<String>,
+    /// `x,y,WxH` of the monitor when it was picked, matched when neither id is found.
+    #[serde(default)]
+    pub monitor_geometry: Option<String>,
     pub anchor: String,
+    /// Extra OSD windows, each with its own placement and target filter.
+    #[serde(default)]
+    pub instances: Vec<OsdInstance>,
+    /// Window size in logical pixels; instances share the primary's appearance.
+    #[serde(default = "default_osd_width")]
+    pub width: f64,
+    #[serde(default = "default_osd_height")]
+    pub height: f64,
+    /// Card background opacity, 0..1.
+    #[serde(default = "default_osd_opacity")]
+    pub opacity: f32,
+    /// CSS color for the level bar; `None` keeps the built-in gradient.
+    #[serde(default)]
+    pub accent_color: Option<String>,
 }
 
 impl Default for OsdSettings {
@@ -334,7 +766,86 @@ impl Default for OsdSettings {
             monitor_index: 0,
             monitor_name: None,
             monitor_id: None,
+            monitor_edid: None,
+            monitor_geometry: None,
             anchor: "top-right".to_string(),
+            instances: Vec::new(),
+            width: default_osd_width(),
+            height: default_osd_height(),
+            opacity: default_osd_opacity(),
+            accent_color: None,
+        }
+    }
+}
+
+fn default_osd_width() -> f64 {
+    320.0
+}
+
+fn default_osd_height() -> f64 {
+    800.0
+}
+
+fn default_osd_opacity() -> f32 {
+    0.94
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsdInstance {
+    pub id: String,
+    #[serde(default)]
+    pub monitor_index: usize,
+    #[serde(default)]
+    pub monitor_name: Option<String>,
+    #[serde(default)]
+    pub monitor_id: Option<String>,
+    #[serde(default)]
+    pub monitor_edid: Option<String>,
+    #[serde(default)]
+    pub monitor_geometry: Option<String>,
+    pub anchor: String,
+    /// Target keys (see `BindingTarget::osd_key`) shown by this instance.
+    #[serde(default)]
+    pub targets: Vec<String>,
+    #[serde(default)]
+    pub binding_ids: Vec<String>,
+    /// Stays visible and shows the live level of every matching volume binding
+    /// (e.g. as a streaming overlay) instead of popping up on changes.
+    #[serde(default)]
+    pub meter: bool,
+}
+
+impl OsdInstance {
+    pub fn has_filter(&self) -> bool {
+        !self.targets.is_empty() || !self.binding_ids.is_empty()
+    }
+
+    /// An instance without a filter mirrors every update.
+    pub fn matches(&self, binding: &Binding) -> bool {
+        if !self.has_filter() || self.binding_ids.contains(&binding.id) {
+            return true;
+        }
+        binding
+            .target
+            .osd_key()
+            .map(|key| self.targets.contains(&key))
+            .unwrap_or(false)
+    }
+
+    pub fn placement(&self, primary: &OsdSettings) -> OsdSettings {
+        OsdSettings {
+            enabled: primary.enabled,
+            monitor_index: self.monitor_index,
+            monitor_name: self.monitor_name.clone(),
+            monitor_id: self.monitor_id.clone(),
+            monitor_edid: self.monitor_edid.clone(),
+            monitor_geometry: self.monitor_geometry.clone(),
+            anchor: self.anchor.clone(),
+            instances: Vec::new(),
+            width: primary.width,
+            height: primary.height,
+            opacity: primary.opacity,
+            accent_color: primary.accent_color.clone(),
         }
     }
 }
@@ -347,11 +858,61 @@ pub struct Profile {
     pub osd_settings: OsdSettings,
     #[serde(default)]
     pub plugin_settings: HashMap<String, serde_json::Value>,
+    /// User display names keyed by `BindingTarget::alias_key`.
+    #[serde(default)]
+    pub target_aliases: HashMap<String, String>,
+    /// Executables (e.g. `obs64.exe`) that load this profile when they gain focus.
+    #[serde(default)]
+    pub auto_switch_processes: Vec<String>,
+    /// MIDI input started when this profile loads. Unset keeps whatever is running.
+    #[serde(default)]
+    pub midi_input_device_id: Option<String>,
+    #[serde(default)]
+    pub midi_output_device_ids: Vec<String>,
+}
+
+impl Profile {
+    pub fn alias_for_target(&self, target: &BindingTarget) -> Option<String> {
+        target
+            .alias_key()
+            .and_then(|key| self.target_aliases.get(&key).cloned())
+    }
+
+    pub fn alias_for_session(&self, session: &SessionInfo) -> Option<String> {
+        if session.is_master {
+            return self.alias_for_target(&BindingTarget::Master);
+        }
+        let by_session = BindingTarget::Session {
+            session_id: session.id.clone(),
+            path: None,
+        };
+        self.alias_for_target(&by_session).or_else(|| {
+            let stem = session
+                .process_path
+                .as_deref()
+                .or(session.process_name.as_deref())
+                .and_then(|path| std::path::Path::new(path).file_stem())
+                .and_then(|stem| stem.to_str())?;
+            self.alias_for_target(&BindingTarget::Application {
+                name: stem.to_string(),
+                path: None,
+            })
+        })
+    }
+
+    pub fn alias_for_device(&self, device_id: &str, recording: bool) -> Option<String> {
+        let prefix = if recording { "recording:" } else { "playback:" };
+        self.alias_for_target(&BindingTarget::Device {
+            device_id: format!("{}{}", prefix, device_id),
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProfileSummary {
     pub name: String,
+    #[serde(default)]
+    pub auto_switch_processes: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -363,6 +924,13 @@ pub struct MidiEvent {
     pub value_14: Option<u16>,
     #[serde(default)]
     pub msg_type: MidiMessageType,
+    /// Set for a `0x8n` Note-off, as opposed to a Note-on with velocity 0 (which
+    /// running-status devices send instead). `value` is 0 for both.
+    #[serde(default)]
+    pub is_note_off: bool,
+    /// Release velocity a Note-off carried.
+    #[serde(default)]
+    pub release_velocity: Option<u8>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -372,4 +940,15 @@ pub struct LearnedControl {
     pub controller: u8,
     #[serde(default)]
     pub msg_type: MidiMessageType,
+    /// Inferred from earlier input and the learn observation window, if known.
+    #[serde(default, alias = "kind")]
+    pub control_kind: Option<ControlKind>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ControlKind {
+    Button,
+    Fader,
+    /// Touch-sense note sent by a motorized fader alongside its movement.
+    Touch,
 }
@@ -0,0 +1,150 @@
+/// Gap between the OSD window and the monitor edge, in logical pixels.
+pub const OSD_EDGE_PADDING: f64 = 24.0;
+
+/// A monitor's bounds in physical pixels, as reported by the OS, plus its scale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonitorGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Start,
+    Center,
+    End,
+}
+
+/// Splits an anchor such as `bottom-center` into (vertical, horizontal) alignment.
+/// Unknown anchors fall back to `top-right`.
+fn parse_anchor(anchor: &str) -> (Align, Align) {
+    match anchor {
+        "top-left" => (Align::Start, Align::Start),
+        "top-center" => (Align::Start, Align::Center),
+        "top-right" => (Align::Start, Align::End),
+        "center-left" => (Align::Center, Align::Start),
+        "center" => (Align::Center, Align::Center),
+        "center-right" => (Align::Center, Align::End),
+        "bottom-left" => (Align::End, Align::Start),
+        "bottom-center" => (Align::End, Align::Center),
+        "bottom-right" => (Align::End, Align::End),
+        _ => (Align::Start, Align::End),
+    }
+}
+
+fn place(align: Align, origin: f64, extent: f64, size: f64, padding: f64) -> f64 {
+    let offset = match align {
+        Align::Start => padding,
+        Align::Center => (extent - size) / 2.0,
+        Align::End => extent - size - padding,
+    };
+    // Never push the window past the top/left edge, even if it is larger than the monitor.
+    origin + offset.max(padding)
+}
+
+/// Physical top-left position of an OSD window of `width` x `height` logical
+/// pixels anchored on `monitor`.
+///
+/// Everything is computed in the target monitor's physical pixels so the result
+/// does not depend on the scale factor of whichever monitor the window is on
+/// right now; logical coordinates are converted with that monitor's scale,
+/// which puts the window in the wrong place on mixed-DPI setups.
+pub fn osd_window_position(
+    monitor: &MonitorGeometry,
+    anchor: &str,
+    width: f64,
+    height: f64,
+) -> (i32, i32) {
+    let scale = monitor.scale_factor;
+    let padding = OSD_EDGE_PADDING * scale;
+    let (vertical, horizontal) = parse_anchor(anchor);
+    let x = place(
+        horizontal,
+        monitor.x as f64,
+        monitor.width as f64,
+        width * scale,
+        padding,
+    );
+    let y = place(
+        vertical,
+        monitor.y as f64,
+        monitor.height as f64,
+        height * scale,
+        padding,
+    );
+    (x.round() as i32, y.round() as i32)
+}
+
+/// Physical size of an OSD window of `width` x `height` logical pixels on `monitor`.
+pub fn osd_window_size(monitor: &MonitorGeometry, width: f64, height: f64) -> (u32, u32) {
+    let scale = monitor.scale_factor;
+    (
+        (width * scale).round().max(1.0) as u32,
+        (height * scale).round().max(1.0) as u32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(x: i32, y: i32, width: u32, height: u32, scale_factor: f64) -> MonitorGeometry {
+        MonitorGeometry {
+            x,
+            y,
+            width,
+            height,
+            scale_factor,
+        }
+    }
+
+    #[test]
+    fn anchors_in_physical_pixels_at_150_percent() {
+        let secondary = monitor(1920, 0, 3840, 2160, 1.5);
+        // 300x80 logical is 450x120 physical, with a 36px gap to the edges.
+        assert_eq!(
+            osd_window_position(&secondary, "bottom-right", 300.0, 80.0),
+            (5274, 2004)
+        );
+        assert_eq!(
+            osd_window_position(&secondary, "bottom-center", 300.0, 80.0),
+            (3615, 2004)
+        );
+        assert_eq!(
+            osd_window_position(&secondary, "top-left", 300.0, 80.0),
+            (1956, 36)
+        );
+        assert_eq!(osd_window_size(&secondary, 300.0, 80.0), (450, 120));
+    }
+
+    #[test]
+    fn anchors_on_a_monitor_left_of_and_above_the_primary_at_125_percent() {
+        let left = monitor(-2400, -200, 2400, 1350, 1.25);
+        // 300x80 logical is 375x100 physical, with a 30px gap to the edges.
+        assert_eq!(
+            osd_window_position(&left, "bottom-right", 300.0, 80.0),
+            (-405, 1020)
+        );
+        assert_eq!(
+            osd_window_position(&left, "top-left", 300.0, 80.0),
+            (-2370, -170)
+        );
+        assert_eq!(osd_window_size(&left, 301.0, 81.0), (376, 101));
+    }
+
+    #[test]
+    fn unknown_anchor_and_oversized_window_stay_on_screen() {
+        let primary = monitor(0, 0, 1920, 1080, 1.25);
+        assert_eq!(
+            osd_window_position(&primary, "nowhere", 300.0, 80.0),
+            osd_window_position(&primary, "top-right", 300.0, 80.0)
+        );
+        assert_eq!(
+            osd_window_position(&primary, "bottom-right", 4000.0, 4000.0),
+            (30, 30)
+        );
+    }
+}
@@ -9,9 +9,14 @@ use std::{
 use tauri::AppHandle;
 
 use crate::app_paths::app_data_root_dir;
+use crate::plugin_settings::SettingsSchema;
+use crate::store_api::verify_signature;
 
 const BUNDLED_PLUGIN_IDS: &[&str] = &["obs", "wavelink"];
 
+/// Optional detached signature shipped next to `manifest.json` in a package.
+const PACKAGE_SIGNATURE_FILE: &str = "signature.json";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginManifest {
     pub id: String,
@@ -21,6 +26,10 @@ pub struct PluginManifest {
     pub entry: String,
     #[serde(default)]
     pub icon: Option<String>,
+    /// Declared settings, validated by `set_plugin_setting`. Without it a
+    /// plugin's settings are stored as-is.
+    #[serde(default)]
+    pub settings: Option<SettingsSchema>,
 
     // Augmented fields computed by MIDIMaster.
     #[serde(default)]
@@ -115,6 +124,15 @@ pub fn ensure_builtin_plugin(
     }
 }
 
+/// Reads an installed plugin's manifest.
+pub fn load_plugin_manifest(app: &AppHandle, plugin_id: &str) -> Result<PluginManifest, String> {
+    validate_plugin_id(plugin_id)?;
+    let root = plugins_root_dir(app)?;
+    let text = fs::read_to_string(root.join(plugin_id).join("manifest.json"))
+        .map_err(|_| format!("Plugin \"{}\" is not installed", plugin_id))?;
+    serde_json::from_str(&text).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_plugins_dir(app: AppHandle) -> Result<String, String> {
     let root = plugins_root_dir(&app)?;
@@ -198,6 +216,96 @@ pub struct InstalledPluginInfo {
     pub manifest: PluginManifest,
     #[serde(default)]
     pub replaced_existing: bool,
+    /// Whether the package was signed by a trusted key. Unsigned packages still
+    /// install; the UI warns about them.
+    #[serde(default)]
+    pub signed: bool,
+    #[serde(default)]
+    pub signature_key_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PackageSignature {
+    key_id: String,
+    signature: String,
+}
+
+/// SHA-256 over every file in the package except the signature itself, in path
+/// order, each as `path`, a NUL byte, the length as u64 LE, then the contents.
+fn package_digest<R: std::io::Read + std::io::Seek>(
+    zip: &mut zip::ZipArchive<R>,
+    prefix: &str,
+) -> Result<String, String> {
+    use sha2::{Digest, Sha256};
+    use std::io::Read;
+
+    let mut files = Vec::new();
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i).map_err(|e| e.to_string())?;
+        let name = file.name().to_string();
+        if name.ends_with('/') {
+            continue;
+        }
+        let stripped = name
+            .strip_prefix(prefix)
+            .unwrap_or(name.as_str())
+            .to_string();
+        if stripped.is_empty() || stripped == PACKAGE_SIGNATURE_FILE {
+            continue;
+        }
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+        files.push((stripped, bytes));
+    }
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut hasher = Sha256::new();
+    for (path, bytes) in &files {
+        hasher.update(path.as_bytes());
+        hasher.update([0u8]);
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Message signed by a package's `signature.json`. Distinct from the store
+/// release message so neither signature verifies as the other.
+fn sideload_message(plugin_id: &str, version: &str, digest_hex: &str) -> String {
+    format!(
+        "MIDIMaster Sideload Package v1\nid={}\nversion={}\nsha256={}\n",
+        plugin_id, version, digest_hex
+    )
+}
+
+/// Verifies the package's `signature.json`, if it has one. Returns the key id
+/// it was signed with, or `None` for an unsigned package. A signature that is
+/// present but doesn't verify is an error: the package was altered after signing.
+fn verify_package_signature<R: std::io::Read + std::io::Seek>(
+    zip: &mut zip::ZipArchive<R>,
+    prefix: &str,
+    manifest: &PluginManifest,
+) -> Result<Option<String>, String> {
+    use std::io::Read;
+
+    let text = {
+        let Ok(mut file) = zip.by_name(&format!("{}{}", prefix, PACKAGE_SIGNATURE_FILE)) else {
+            return Ok(None);
+        };
+        let mut text = String::new();
+        file.read_to_string(&mut text).map_err(|e| e.to_string())?;
+        text
+    };
+    let signature: PackageSignature = serde_json::from_str(&text)
+        .map_err(|e| format!("Invalid {}: {}", PACKAGE_SIGNATURE_FILE, e))?;
+    let digest = package_digest(zip, prefix)?;
+    verify_signature(
+        &sideload_message(&manifest.id, &manifest.version, &digest),
+        &signature.signature,
+        &signature.key_id,
+    )
+    .map_err(|e| format!("Package signature check failed: {}", e))?;
+    Ok(Some(signature.key_id))
 }
 
 fn validate_plugin_id(id: &str) -> Result<(), String> {
@@ -276,11 +384,22 @@ pub fn install_plugin_package(
     let bytes = base64::engine::general_purpose::STANDARD
         .decode(bytes_base64.as_bytes())
         .map_err(|e| e.to_string())?;
+    install_plugin_bytes(&app, bytes, None)
+}
+
+/// Installs a decoded package. `verified_key_id` is the key a caller already
+/// verified the whole package with (store installs); otherwise the package's
+/// own `signature.json` is checked.
+pub fn install_plugin_bytes(
+    app: &AppHandle,
+    bytes: Vec<u8>,
+    verified_key_id: Option<String>,
+) -> Result<InstalledPluginInfo, String> {
     if bytes.len() > 60_000_000 {
         return Err("Plugin package is too large".to_string());
     }
 
-    let root = plugins_root_dir(&app)?;
+    let root = plugins_root_dir(app)?;
     fs::create_dir_all(&root).map_err(|e| e.to_string())?;
 
     // Read zip
@@ -350,6 +469,11 @@ pub fn install_plugin_package(
         return Err("Entry file not found in package".to_string());
     }
 
+    let signature_key_id = match verified_key_id {
+        Some(key_id) => Some(key_id),
+        None => verify_package_signature(&mut zip, &prefix, &manifest)?,
+    };
+
     // Extract to temp directory
     let installing_root = root.join(".installing");
     let _ = fs::create_dir_all(&installing_root);
@@ -394,9 +518,9 @@ pub fn install_plugin_package(
     fs::rename(&temp_dir, &target_dir).map_err(|e| e.to_string())?;
 
     // Enable by default
-    let mut state = load_plugins_state(&app);
+    let mut state = load_plugins_state(app);
     state.disabled.retain(|id| id != &manifest.id);
-    let _ = save_plugins_state(&app, &state);
+    let _ = save_plugins_state(app, &state);
 
     manifest.bundled = false;
     manifest.enabled = true;
@@ -404,5 +528,7 @@ pub fn install_plugin_package(
     Ok(InstalledPluginInfo {
         manifest,
         replaced_existing,
+        signed: signature_key_id.is_some(),
+        signature_key_id,
     })
 }
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Settings a plugin declares under `settings` in its manifest, keyed by name.
+pub type SettingsSchema = BTreeMap<String, SettingSpec>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingType {
+    String,
+    Number,
+    Integer,
+    Boolean,
+    Object,
+    Array,
+}
+
+/// A JSON-schema-like description of one plugin setting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingSpec {
+    #[serde(rename = "type")]
+    pub kind: SettingType,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub default: Option<Value>,
+    /// Allowed values; empty allows anything of the right type.
+    #[serde(default, rename = "enum")]
+    pub allowed: Vec<Value>,
+    /// Inclusive bounds for numbers and integers.
+    #[serde(default)]
+    pub minimum: Option<f64>,
+    #[serde(default)]
+    pub maximum: Option<f64>,
+    /// Longest allowed string, in characters. JSON Schema spells it `maxLength`.
+    #[serde(default, alias = "maxLength")]
+    pub max_length: Option<usize>,
+}
+
+fn type_name(kind: SettingType) -> &'static str {
+    match kind {
+        SettingType::String => "a string",
+        SettingType::Number => "a number",
+        SettingType::Integer => "an integer",
+        SettingType::Boolean => "true or false",
+        SettingType::Object => "an object",
+        SettingType::Array => "a list",
+    }
+}
+
+fn has_type(kind: SettingType, value: &Value) -> bool {
+    match kind {
+        SettingType::String => value.is_string(),
+        SettingType::Number => value.is_number(),
+        SettingType::Integer => value.is_i64() || value.is_u64(),
+        SettingType::Boolean => value.is_boolean(),
+        SettingType::Object => value.is_object(),
+        SettingType::Array => value.is_array(),
+    }
+}
+
+/// Checks `value` against the schema entry for `key`. A `null` value clears the
+/// setting and is always accepted for declared keys.
+pub fn validate_setting(schema: &SettingsSchema, key: &str, value: &Value) -> Result<(), String> {
+    let spec = schema
+        .get(key)
+        .ok_or_else(|| format!("Unknown setting \"{}\"", key))?;
+    if value.is_null() {
+        return Ok(());
+    }
+    if !has_type(spec.kind, value) {
+        return Err(format!(
+            "Setting \"{}\" must be {}",
+            key,
+            type_name(spec.kind)
+        ));
+    }
+    if !spec.allowed.is_empty() && !spec.allowed.contains(value) {
+        let allowed = spec
+            .allowed
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(format!("Setting \"{}\" must be one of {}", key, allowed));
+    }
+    if let Some(number) = value.as_f64() {
+        if let Some(minimum) = spec.minimum.filter(|minimum| number < *minimum) {
+            return Err(format!("Setting \"{}\" must be at least {}", key, minimum));
+        }
+        if let Some(maximum) = spec.maximum.filter(|maximum| number > *maximum) {
+            return Err(format!("Setting \"{}\" must be at most {}", key, maximum));
+        }
+    }
+    if let (Some(text), Some(max_length)) = (value.as_str(), spec.max_length) {
+        if text.chars().count() > max_length {
+            return Err(format!(
+                "Setting \"{}\" must be at most {} characters",
+                key, max_length
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn max_length_accepts_json_schema_spelling() {
+        let schema: SettingsSchema = serde_json::from_value(json!({
+            "snake": { "type": "string", "max_length": 3 },
+            "camel": { "type": "string", "maxLength": 3 },
+        }))
+        .unwrap();
+        for key in ["snake", "camel"] {
+            assert!(validate_setting(&schema, key, &json!("abc")).is_ok());
+            assert!(validate_setting(&schema, key, &json!("abcd")).is_err());
+        }
+    }
+}
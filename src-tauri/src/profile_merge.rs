@@ -0,0 +1,189 @@
+use crate::model::{Binding, Profile};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Existing bindings and settings win; only non-conflicting incoming bindings are added.
+    KeepMine,
+    /// Incoming bindings and settings win; existing bindings survive only if they don't conflict.
+    PreferTheirs,
+    /// Bindings with the same id are replaced in place, new ids are appended.
+    MergeById,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileMerge {
+    pub profile: Profile,
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+fn same_control(a: &Binding, b: &Binding) -> bool {
+    a.device_id == b.device_id && a.control == b.control && a.layer == b.layer
+}
+
+fn same_binding(a: &Binding, b: &Binding) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// Copies `extra` bindings into `base` unless their id or control is already taken.
+fn append_missing(
+    base: &mut Vec<Binding>,
+    extra: &[Binding],
+    added: &mut Vec<String>,
+    skipped: &mut Vec<String>,
+) {
+    for binding in extra {
+        let taken = base
+            .iter()
+            .any(|existing| existing.id == binding.id || same_control(existing, binding));
+        if taken {
+            skipped.push(binding.id.clone());
+        } else {
+            base.push(binding.clone());
+            added.push(binding.id.clone());
+        }
+    }
+}
+
+/// Shallow-merges plugin settings objects; `winner` takes precedence per field.
+fn merge_plugin_settings(
+    loser: &HashMap<String, serde_json::Value>,
+    winner: &HashMap<String, serde_json::Value>,
+) -> HashMap<String, serde_json::Value> {
+    let mut merged = loser.clone();
+    for (plugin_id, value) in winner {
+        match (merged.get_mut(plugin_id), value) {
+            (Some(serde_json::Value::Object(existing)), serde_json::Value::Object(incoming)) => {
+                for (field, field_value) in incoming {
+                    existing.insert(field.clone(), field_value.clone());
+                }
+            }
+            _ => {
+                merged.insert(plugin_id.clone(), value.clone());
+            }
+        }
+    }
+    merged
+}
+
+fn merge_aliases(
+    loser: &HashMap<String, String>,
+    winner: &HashMap<String, String>,
+) -> HashMap<String, String> {
+    let mut merged = loser.clone();
+    merged.extend(
+        winner
+            .iter()
+            .map(|(key, alias)| (key.clone(), alias.clone())),
+    );
+    merged
+}
+
+pub fn merge_profiles(mine: &Profile, theirs: &Profile, strategy: MergeStrategy) -> ProfileMerge {
+    let mut added = Vec::new();
+    let mut updated = Vec::new();
+    let mut skipped = Vec::new();
+
+    let profile = match strategy {
+        MergeStrategy::KeepMine => {
+            let mut bindings = mine.bindings.clone();
+            append_missing(&mut bindings, &theirs.bindings, &mut added, &mut skipped);
+            Profile {
+                name: mine.name.clone(),
+                bindings,
+                osd_settings: mine.osd_settings.clone(),
+                auto_switch_processes: mine.auto_switch_processes.clone(),
+                midi_input_device_id: mine.midi_input_device_id.clone(),
+                midi_output_device_ids: mine.midi_output_device_ids.clone(),
+                plugin_settings: merge_plugin_settings(
+                    &theirs.plugin_settings,
+                    &mine.plugin_settings,
+                ),
+                target_aliases: merge_aliases(&theirs.target_aliases, &mine.target_aliases),
+            }
+        }
+        MergeStrategy::PreferTheirs => {
+            let mut bindings = theirs.bindings.clone();
+            for binding in &theirs.bindings {
+                match mine
+                    .bindings
+                    .iter()
+                    .find(|existing| existing.id == binding.id)
+                {
+                    Some(existing) if !same_binding(existing, binding) => {
+                        updated.push(binding.id.clone())
+                    }
+                    Some(_) => {}
+                    None => added.push(binding.id.clone()),
+                }
+            }
+            // Bindings kept from mine are not reported as added.
+            let mut kept = Vec::new();
+            append_missing(&mut bindings, &mine.bindings, &mut kept, &mut skipped);
+            skipped.retain(|id| !theirs.bindings.iter().any(|binding| &binding.id == id));
+            Profile {
+                name: mine.name.clone(),
+                bindings,
+                osd_settings: theirs.osd_settings.clone(),
+                auto_switch_processes: theirs.auto_switch_processes.clone(),
+                midi_input_device_id: theirs.midi_input_device_id.clone(),
+                midi_output_device_ids: theirs.midi_output_device_ids.clone(),
+                plugin_settings: merge_plugin_settings(
+                    &mine.plugin_settings,
+                    &theirs.plugin_settings,
+                ),
+                target_aliases: merge_aliases(&mine.target_aliases, &theirs.target_aliases),
+            }
+        }
+        MergeStrategy::MergeById => {
+            let mut bindings = mine.bindings.clone();
+            for binding in &theirs.bindings {
+                if let Some(index) = bindings
+                    .iter()
+                    .position(|existing| existing.id == binding.id)
+                {
+                    if !same_binding(&bindings[index], binding) {
+                        bindings[index] = binding.clone();
+                        updated.push(binding.id.clone());
+                    }
+                    continue;
+                }
+                // An incoming binding on an already-bound control replaces the old one,
+                // matching how `add_binding` keeps one binding per control.
+                if let Some(index) = bindings
+                    .iter()
+                    .position(|existing| same_control(existing, binding))
+                {
+                    bindings[index] = binding.clone();
+                    updated.push(binding.id.clone());
+                    continue;
+                }
+                bindings.push(binding.clone());
+                added.push(binding.id.clone());
+            }
+            Profile {
+                name: mine.name.clone(),
+                bindings,
+                osd_settings: mine.osd_settings.clone(),
+                auto_switch_processes: mine.auto_switch_processes.clone(),
+                midi_input_device_id: mine.midi_input_device_id.clone(),
+                midi_output_device_ids: mine.midi_output_device_ids.clone(),
+                plugin_settings: merge_plugin_settings(
+                    &mine.plugin_settings,
+                    &theirs.plugin_settings,
+                ),
+                target_aliases: merge_aliases(&mine.target_aliases, &theirs.target_aliases),
+            }
+        }
+    };
+
+    ProfileMerge {
+        profile,
+        added,
+        updated,
+        skipped,
+    }
+}
@@ -1,9 +1,73 @@
 use crate::model::{Profile, ProfileSummary};
-use anyhow::Context;
+use anyhow::{anyhow, bail, Context};
+use serde::{Deserialize, Serialize};
 use std::{fs, path::PathBuf};
 
 type Result<T> = anyhow::Result<T>;
 
+/// Identifies a single exported profile file.
+const PROFILE_EXPORT_FORMAT: &str = "midimaster-profile";
+/// Bump when the exported layout changes and add a step to `migrate_export`.
+const PROFILE_EXPORT_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct ProfileExport<'a> {
+    format: &'static str,
+    version: u32,
+    profile: &'a Profile,
+}
+
+#[derive(Deserialize)]
+struct ProfileImport {
+    format: String,
+    version: u32,
+    profile: serde_json::Value,
+}
+
+/// Upgrades an exported profile from `version` to the current layout.
+fn migrate_export(version: u32, profile: serde_json::Value) -> Result<serde_json::Value> {
+    match version {
+        0 => bail!("Unsupported profile export version 0"),
+        PROFILE_EXPORT_VERSION => Ok(profile),
+        newer => bail!(
+            "Profile was exported by a newer MIDIMaster (format version {})",
+            newer
+        ),
+    }
+}
+
+/// Parses an exported profile. A bare `Profile` object (for example one copied
+/// out of `profiles.json`) is accepted as well.
+fn parse_profile_export(json: &str) -> Result<Profile> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).context("Profile file is not valid JSON")?;
+    let profile = if value.get("format").is_some() {
+        let export: ProfileImport =
+            serde_json::from_value(value).context("Profile file header is invalid")?;
+        if export.format != PROFILE_EXPORT_FORMAT {
+            bail!("Not a MIDIMaster profile (format \"{}\")", export.format);
+        }
+        migrate_export(export.version, export.profile)?
+    } else {
+        value
+    };
+    let mut profile: Profile =
+        serde_json::from_value(profile).context("Profile file has an invalid structure")?;
+    profile.name = profile.name.trim().to_string();
+    if profile.name.is_empty() {
+        bail!("Profile file has no name");
+    }
+    Ok(profile)
+}
+
+/// First of `name (2)`, `name (3)`, ... that is not taken.
+fn unique_profile_name(profiles: &[Profile], name: &str) -> String {
+    (2..)
+        .map(|suffix| format!("{} ({})", name, suffix))
+        .find(|candidate| !profiles.iter().any(|profile| &profile.name == candidate))
+        .unwrap_or_else(|| name.to_string())
+}
+
 #[derive(Clone)]
 pub struct ProfileStore {
     path: PathBuf,
@@ -19,7 +83,10 @@ impl ProfileStore {
         let profiles = self.load_all()?;
         Ok(profiles
             .into_iter()
-            .map(|profile| ProfileSummary { name: profile.name })
+            .map(|profile| ProfileSummary {
+                name: profile.name,
+                auto_switch_processes: profile.auto_switch_processes,
+            })
             .collect())
     }
 
@@ -50,6 +117,63 @@ impl ProfileStore {
         self.write_all(&profiles)
     }
 
+    /// Copies `source` (bindings, OSD and plugin settings, aliases) to `new_name`.
+    /// Auto-switch rules stay with the original so an app keeps loading one profile.
+    pub fn duplicate_profile(&self, source: &str, new_name: &str) -> Result<Profile> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            bail!("Profile name is empty");
+        }
+        let mut profiles = self.load_all()?;
+        if profiles.iter().any(|profile| profile.name == new_name) {
+            bail!("Profile \"{}\" already exists", new_name);
+        }
+        let mut profile = profiles
+            .iter()
+            .find(|profile| profile.name == source)
+            .cloned()
+            .ok_or_else(|| anyhow!("Profile not found"))?;
+        profile.name = new_name.to_string();
+        profile.auto_switch_processes.clear();
+        profiles.push(profile.clone());
+        self.write_all(&profiles)?;
+        Ok(profile)
+    }
+
+    /// Serializes one profile with a format header, for sharing as a single file.
+    pub fn export_profile(&self, name: &str) -> Result<String> {
+        let profile = self
+            .load_profile(name)?
+            .ok_or_else(|| anyhow!("Profile not found"))?;
+        let export = ProfileExport {
+            format: PROFILE_EXPORT_FORMAT,
+            version: PROFILE_EXPORT_VERSION,
+            profile: &profile,
+        };
+        Ok(serde_json::to_string_pretty(&export)?)
+    }
+
+    /// Stores a profile from `export_profile` output. On a name collision the
+    /// import is renamed with a numeric suffix, or replaces the existing profile
+    /// when `rename_if_conflict` is false.
+    pub fn import_profile(&self, json: &str, rename_if_conflict: bool) -> Result<Profile> {
+        let mut profile = parse_profile_export(json)?;
+        let mut profiles = self.load_all()?;
+        let existing = profiles
+            .iter()
+            .position(|existing| existing.name == profile.name);
+        match existing {
+            Some(_) if rename_if_conflict => {
+                profile.name = unique_profile_name(&profiles, &profile.name);
+                profiles.push(profile.clone());
+            }
+            Some(index) => profiles[index] = profile.clone(),
+            None => profiles.push(profile.clone()),
+        }
+        self.write_all(&profiles)?;
+        Ok(profile)
+    }
+
     pub fn clear_all(&self) -> Result<()> {
         if self.path.exists() {
             fs::remove_file(&self.path)
@@ -58,7 +182,7 @@ impl ProfileStore {
         Ok(())
     }
 
-    fn load_all(&self) -> Result<Vec<Profile>> {
+    pub fn load_all(&self) -> Result<Vec<Profile>> {
         if !self.path.exists() {
             return Ok(Vec::new());
         }
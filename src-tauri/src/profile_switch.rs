@@ -0,0 +1,119 @@
+use crate::model::Profile;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// The foreground app must keep focus this long before profiles are switched,
+/// so alt-tabbing through windows doesn't load every profile on the way.
+pub const AUTO_SWITCH_DEBOUNCE: Duration = Duration::from_millis(750);
+
+/// How often the foreground app is checked.
+pub const FOREGROUND_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Loaded when the foreground app matches no rule.
+pub const FALLBACK_PROFILE: &str = "Default";
+
+/// Debounces foreground app changes into settled ones.
+#[derive(Default)]
+pub struct ForegroundTracker {
+    last_poll: Option<Instant>,
+    current: Option<String>,
+    changed_at: Option<Instant>,
+    settled: bool,
+}
+
+impl ForegroundTracker {
+    pub fn poll_due(&self) -> bool {
+        self.last_poll
+            .is_none_or(|last| last.elapsed() >= FOREGROUND_POLL_INTERVAL)
+    }
+
+    /// Records the current foreground executable. `None` (nothing focused, or
+    /// MIDIMaster itself) keeps the previous app, so opening the UI to tweak a
+    /// profile doesn't switch away from it.
+    pub fn observe(&mut self, process_path: Option<String>) {
+        self.last_poll = Some(Instant::now());
+        let Some(process_path) = process_path else {
+            return;
+        };
+        if self.current.as_deref() != Some(process_path.as_str()) {
+            self.current = Some(process_path);
+            self.changed_at = Some(Instant::now());
+            self.settled = false;
+        }
+    }
+
+    /// Returns the foreground executable once, after it has kept focus for
+    /// [`AUTO_SWITCH_DEBOUNCE`].
+    pub fn take_settled(&mut self) -> Option<String> {
+        if self.settled {
+            return None;
+        }
+        let stable = self
+            .changed_at
+            .is_some_and(|changed| changed.elapsed() >= AUTO_SWITCH_DEBOUNCE);
+        if !stable {
+            return None;
+        }
+        self.settled = true;
+        self.current.clone()
+    }
+}
+
+/// Matches a rule such as `obs64.exe`, `obs64` or a full path against an
+/// executable path, ignoring case.
+fn rule_matches(rule: &str, process_path: &str) -> bool {
+    let rule = rule.trim();
+    if rule.is_empty() {
+        return false;
+    }
+    if rule.eq_ignore_ascii_case(process_path) {
+        return true;
+    }
+    let path = Path::new(process_path);
+    let file_name = path.file_name().and_then(|name| name.to_str());
+    let stem = path.file_stem().and_then(|stem| stem.to_str());
+    [file_name, stem]
+        .into_iter()
+        .flatten()
+        .any(|name| name.eq_ignore_ascii_case(rule))
+}
+
+pub fn profile_for_process<'a>(profiles: &'a [Profile], process_path: &str) -> Option<&'a Profile> {
+    profiles.iter().find(|profile| {
+        profile
+            .auto_switch_processes
+            .iter()
+            .any(|rule| rule_matches(rule, process_path))
+    })
+}
+
+/// Profile to load for a newly focused app, or `None` to stay on `active`.
+///
+/// Without a matching rule, the fallback profile is only loaded when the active
+/// profile was itself picked by a rule; a profile chosen by hand stays loaded.
+pub fn auto_switch_target(
+    profiles: &[Profile],
+    active: Option<&Profile>,
+    process_path: &str,
+) -> Option<String> {
+    if profiles
+        .iter()
+        .all(|profile| profile.auto_switch_processes.is_empty())
+    {
+        return None;
+    }
+    let target = match profile_for_process(profiles, process_path) {
+        Some(profile) => profile.name.clone(),
+        None if active.is_some_and(|profile| !profile.auto_switch_processes.is_empty()) => {
+            FALLBACK_PROFILE.to_string()
+        }
+        None => return None,
+    };
+    if active.is_some_and(|profile| profile.name == target) {
+        return None;
+    }
+    profiles
+        .iter()
+        .any(|profile| profile.name == target)
+        .then_some(target)
+}
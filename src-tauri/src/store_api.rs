@@ -2,9 +2,13 @@ use base64::Engine;
 use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use tauri::AppHandle;
+use std::cmp::Ordering;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, State};
 
-use crate::plugin_api::{install_plugin_package, InstalledPluginInfo};
+use crate::plugin_api::{install_plugin_bytes, list_plugins, InstalledPluginInfo};
+use crate::AppState;
 
 // Official store URL.
 //
@@ -23,6 +27,49 @@ pub const TRUSTED_KEYS: &[(&str, &str)] = &[(
     "/a99SbJ8PwG4zpPXkpCAAndQ7hZWmb2eSYIFE3lCLts=",
 )];
 
+// Long-lived root keys (hardcoded, kept offline). They only sign `keys.json`,
+// which adds release keys on top of TRUSTED_KEYS so those can be rotated
+// without shipping a new binary. Never used to verify packages directly.
+// key_id -> base64(ed25519 public key bytes)
+//
+// Empty until the root key pair is generated; while it is, `keys.json` is
+// never fetched and only TRUSTED_KEYS are honoured.
+pub const ROOT_KEYS: &[(&str, &str)] = &[];
+
+/// Published next to the official catalog.
+const KEYS_FILE_NAME: &str = "keys.json";
+/// `keys.json` format this build understands.
+const KEYS_FILE_VERSION: u32 = 1;
+/// How long a failed `keys.json` fetch is remembered before trying again.
+const KEYS_RETRY_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+enum DownloadedKeys {
+    Loaded(Vec<TrustedKey>),
+    Failed(Instant),
+}
+
+/// Release keys from a verified `keys.json`, fetched on the first lookup of a
+/// key id that isn't hardcoded. Failures are retried after KEYS_RETRY_INTERVAL.
+static DOWNLOADED_KEYS: Mutex<Option<DownloadedKeys>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedKey {
+    pub key_id: String,
+    /// base64(ed25519 public key bytes)
+    pub public_key: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TrustedKeysFile {
+    version: u32,
+    /// Unix seconds after which the file is no longer trusted.
+    expires: u64,
+    #[serde(default)]
+    keys: Vec<TrustedKey>,
+    signature: String,
+    signature_key_id: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoreCatalog {
     pub schema_version: u32,
@@ -30,6 +77,9 @@ pub struct StoreCatalog {
     pub generated_at: Option<String>,
     #[serde(default)]
     pub plugins: Vec<StorePlugin>,
+    /// Catalog URLs that could not be fetched or parsed; their plugins are missing.
+    #[serde(default)]
+    pub failed_sources: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +95,17 @@ pub struct StorePlugin {
     #[serde(default)]
     pub icon_url: Option<String>,
     pub latest: StorePluginRelease,
+
+    // Filled in by MIDIMaster when merging catalogs.
+    /// Catalog URL this entry came from.
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub official: bool,
+    /// Other catalogs that also list this id. Their entries are hidden: the
+    /// official catalog wins, then whichever source is listed first.
+    #[serde(default)]
+    pub conflicts: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,6 +120,26 @@ pub struct StorePluginRelease {
     pub signature_key_id: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginUpdateStatus {
+    UpToDate,
+    UpdateAvailable,
+    NotInStore,
+    /// The installed or store version isn't a version number we can compare.
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginUpdateInfo {
+    pub id: String,
+    pub name: String,
+    pub installed_version: String,
+    pub status: PluginUpdateStatus,
+    /// The catalog entry, present unless the plugin is not in the store.
+    pub store: Option<StorePlugin>,
+}
+
 fn canonical_message(plugin_id: &str, version: &str, sha256_hex: &str) -> String {
     format!(
         "MIDIMaster Plugin Package v1\nid={}\nversion={}\nsha256={}\n",
@@ -66,17 +147,15 @@ fn canonical_message(plugin_id: &str, version: &str, sha256_hex: &str) -> String
     )
 }
 
-fn find_key_b64(key_id: &str) -> Option<&'static str> {
-    TRUSTED_KEYS
-        .iter()
+fn find_key_b64(keys: &[(&str, &str)], key_id: &str) -> Option<String> {
+    keys.iter()
         .find(|(id, _)| id.eq_ignore_ascii_case(key_id))
-        .map(|(_, k)| *k)
+        .map(|(_, k)| k.to_string())
 }
 
-fn decode_pubkey(key_id: &str) -> Result<VerifyingKey, String> {
-    let b64 = find_key_b64(key_id).ok_or_else(|| "Unknown signature key id".to_string())?;
+fn parse_pubkey(b64: &str) -> Result<VerifyingKey, String> {
     let bytes = base64::engine::general_purpose::STANDARD
-        .decode(b64.as_bytes())
+        .decode(b64.trim().as_bytes())
         .map_err(|e| e.to_string())?;
     let arr: [u8; 32] = bytes
         .try_into()
@@ -84,6 +163,102 @@ fn decode_pubkey(key_id: &str) -> Result<VerifyingKey, String> {
     VerifyingKey::from_bytes(&arr).map_err(|e| e.to_string())
 }
 
+/// Looks a release key up in TRUSTED_KEYS, then in the downloaded `keys.json`.
+fn decode_pubkey(key_id: &str) -> Result<VerifyingKey, String> {
+    let b64 = find_key_b64(TRUSTED_KEYS, key_id)
+        .or_else(|| find_downloaded_key(key_id))
+        .ok_or_else(|| "Unknown signature key id".to_string())?;
+    parse_pubkey(&b64)
+}
+
+fn find_downloaded_key(key_id: &str) -> Option<String> {
+    let lookup = |keys: &[TrustedKey]| {
+        keys.iter()
+            .find(|key| key.key_id.eq_ignore_ascii_case(key_id))
+            .map(|key| key.public_key.clone())
+    };
+    match &*DOWNLOADED_KEYS.lock().ok()? {
+        Some(DownloadedKeys::Loaded(keys)) => return lookup(keys),
+        Some(DownloadedKeys::Failed(at)) if at.elapsed() < KEYS_RETRY_INTERVAL => return None,
+        _ => {}
+    }
+
+    // Fetched without holding the lock so a slow download doesn't block other
+    // lookups; a concurrent miss may fetch twice, which is harmless.
+    let (entry, found) = match fetch_trusted_keys() {
+        Ok(keys) => {
+            let found = lookup(&keys);
+            (DownloadedKeys::Loaded(keys), found)
+        }
+        Err(err) => {
+            log_warn!("Store: trusted keys unavailable: {}", err);
+            (DownloadedKeys::Failed(Instant::now()), None)
+        }
+    };
+    if let Ok(mut cached) = DOWNLOADED_KEYS.lock() {
+        *cached = Some(entry);
+    }
+    found
+}
+
+fn keys_message(file: &TrustedKeysFile) -> String {
+    let mut msg = format!(
+        "MIDIMaster Trusted Keys v1\nversion={}\nexpires={}\n",
+        file.version, file.expires
+    );
+    for key in &file.keys {
+        msg.push_str(&format!(
+            "key_id={}\npublic_key={}\n",
+            key.key_id,
+            key.public_key.trim()
+        ));
+    }
+    msg
+}
+
+/// Downloads `keys.json` from the official catalog host and checks it against
+/// ROOT_KEYS. Keys that fail to parse are dropped rather than failing the file.
+fn fetch_trusted_keys() -> Result<Vec<TrustedKey>, String> {
+    if ROOT_KEYS.is_empty() {
+        return Err("No root keys configured".to_string());
+    }
+    let url = url::Url::parse(&official_store_url())
+        .and_then(|catalog| catalog.join(KEYS_FILE_NAME))
+        .map_err(|e| e.to_string())?;
+    let bytes = download_bytes(url.as_str(), 200_000)?;
+    let file: TrustedKeysFile = serde_json::from_slice(&bytes).map_err(|e| e.to_string())?;
+
+    let root_b64 = find_key_b64(ROOT_KEYS, &file.signature_key_id)
+        .ok_or_else(|| format!("Unknown root key id {}", file.signature_key_id))?;
+    let root = parse_pubkey(&root_b64)?;
+    let sig_bytes = base64::engine::general_purpose::STANDARD
+        .decode(file.signature.trim().as_bytes())
+        .map_err(|e| e.to_string())?;
+    let sig = Signature::from_slice(&sig_bytes).map_err(|e| e.to_string())?;
+    root.verify(keys_message(&file).as_bytes(), &sig)
+        .map_err(|_| format!("{} signature verification failed", KEYS_FILE_NAME))?;
+
+    if file.version != KEYS_FILE_VERSION {
+        return Err(format!(
+            "Unsupported {} version {}",
+            KEYS_FILE_NAME, file.version
+        ));
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    if now >= file.expires {
+        return Err(format!("{} has expired", KEYS_FILE_NAME));
+    }
+
+    Ok(file
+        .keys
+        .into_iter()
+        .filter(|key| parse_pubkey(&key.public_key).is_ok())
+        .collect())
+}
+
 fn verify_release_signature(plugin: &StorePlugin, bytes: &[u8]) -> Result<String, String> {
     let mut hasher = Sha256::new();
     hasher.update(bytes);
@@ -94,17 +269,87 @@ fn verify_release_signature(plugin: &StorePlugin, bytes: &[u8]) -> Result<String
         return Err("SHA256 mismatch".to_string());
     }
 
-    let msg = canonical_message(&plugin.id, &plugin.latest.version, &sha_hex);
+    verify_signature(
+        &canonical_message(&plugin.id, &plugin.latest.version, &sha_hex),
+        &plugin.latest.signature,
+        &plugin.latest.signature_key_id,
+    )?;
+
+    Ok(sha_hex)
+}
+
+/// Checks a base64 ed25519 signature over `msg` against the trusted key
+/// `key_id`. Callers build `msg` with their own domain prefix so a signature
+/// made for one purpose can't be replayed for another.
+pub fn verify_signature(msg: &str, signature_b64: &str, key_id: &str) -> Result<(), String> {
     let sig_bytes = base64::engine::general_purpose::STANDARD
-        .decode(plugin.latest.signature.as_bytes())
+        .decode(signature_b64.trim().as_bytes())
         .map_err(|e| e.to_string())?;
     let sig = Signature::from_slice(&sig_bytes).map_err(|e| e.to_string())?;
 
-    let key = decode_pubkey(&plugin.latest.signature_key_id)?;
+    let key = decode_pubkey(key_id)?;
     key.verify(msg.as_bytes(), &sig)
-        .map_err(|_| "Signature verification failed".to_string())?;
+        .map_err(|_| "Signature verification failed".to_string())
+}
 
-    Ok(sha_hex)
+/// Parses `major.minor.patch[-pre][+build]`, with an optional leading `v`.
+/// Missing minor/patch parts count as 0.
+fn parse_version(version: &str) -> Option<([u64; 3], Vec<&str>)> {
+    let version = version.trim();
+    let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+    let version = version.split('+').next().unwrap_or(version);
+    let (core, pre) = match version.split_once('-') {
+        Some((core, pre)) => (core, pre.split('.').collect()),
+        None => (version, Vec::new()),
+    };
+    let mut numbers = [0u64; 3];
+    let mut parts = core.split('.');
+    for number in numbers.iter_mut() {
+        if let Some(part) = parts.next() {
+            *number = part.parse().ok()?;
+        }
+    }
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((numbers, pre))
+}
+
+/// Semver precedence: a pre-release sorts before its release, and numeric
+/// pre-release identifiers sort numerically and before alphanumeric ones.
+fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    let (a_core, a_pre) = parse_version(a)?;
+    let (b_core, b_pre) = parse_version(b)?;
+    let ordering = a_core
+        .cmp(&b_core)
+        .then_with(|| match (a_pre.is_empty(), b_pre.is_empty()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => {
+                for (x, y) in a_pre.iter().zip(b_pre.iter()) {
+                    let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+                        (Ok(x), Ok(y)) => x.cmp(&y),
+                        (Ok(_), Err(_)) => Ordering::Less,
+                        (Err(_), Ok(_)) => Ordering::Greater,
+                        (Err(_), Err(_)) => x.cmp(y),
+                    };
+                    if ordering != Ordering::Equal {
+                        return ordering;
+                    }
+                }
+                a_pre.len().cmp(&b_pre.len())
+            }
+        });
+    Some(ordering)
+}
+
+fn update_status(installed: &str, latest: &str) -> PluginUpdateStatus {
+    match compare_versions(installed, latest) {
+        Some(Ordering::Less) => PluginUpdateStatus::UpdateAvailable,
+        Some(_) => PluginUpdateStatus::UpToDate,
+        None => PluginUpdateStatus::Unknown,
+    }
 }
 
 fn is_https(url: &str) -> bool {
@@ -135,21 +380,120 @@ fn download_bytes(url: &str, max_bytes: usize) -> Result<Vec<u8>, String> {
     Ok(out)
 }
 
-#[tauri::command]
-pub fn fetch_store_catalog() -> Result<StoreCatalog, String> {
-    let url = official_store_url();
-    let bytes = download_bytes(&url, 2_000_000)?;
+fn download_catalog(url: &str) -> Result<StoreCatalog, String> {
+    let bytes = download_bytes(url, 2_000_000)?;
     let text = String::from_utf8(bytes).map_err(|_| "Invalid UTF-8".to_string())?;
-    let catalog: StoreCatalog = serde_json::from_str(&text).map_err(|e| e.to_string())?;
-    Ok(catalog)
+    serde_json::from_str(&text).map_err(|e| e.to_string())
+}
+
+/// Fetches the official catalog and every extra source, merging them into one.
+/// A source that fails is reported in `failed_sources`; only when every source
+/// fails is the whole fetch an error.
+fn fetch_merged_catalog(extra_urls: &[String]) -> Result<StoreCatalog, String> {
+    let official = official_store_url();
+    let mut sources = vec![official.clone()];
+    for url in extra_urls {
+        let url = url.trim();
+        if !url.is_empty() && !sources.iter().any(|source| source == url) {
+            sources.push(url.to_string());
+        }
+    }
+
+    let mut merged = StoreCatalog {
+        schema_version: 1,
+        generated_at: None,
+        plugins: Vec::new(),
+        failed_sources: Vec::new(),
+    };
+    let mut first_error = None;
+    let source_count = sources.len();
+    for source in sources {
+        let catalog = match download_catalog(&source) {
+            Ok(catalog) => catalog,
+            Err(err) => {
+                log_warn!("Store: catalog {} unavailable: {}", source, err);
+                first_error.get_or_insert(err);
+                merged.failed_sources.push(source);
+                continue;
+            }
+        };
+        let official_source = source == official;
+        if official_source {
+            merged.schema_version = catalog.schema_version;
+            merged.generated_at = catalog.generated_at;
+        }
+        for mut plugin in catalog.plugins {
+            match merged.plugins.iter_mut().find(|p| p.id == plugin.id) {
+                // Sources are fetched official first, so an existing entry always wins.
+                Some(existing) => existing.conflicts.push(source.clone()),
+                None => {
+                    plugin.source = source.clone();
+                    plugin.official = official_source;
+                    plugin.conflicts.clear();
+                    merged.plugins.push(plugin);
+                }
+            }
+        }
+    }
+
+    match first_error {
+        Some(err) if merged.failed_sources.len() == source_count => Err(err),
+        _ => Ok(merged),
+    }
+}
+
+fn store_catalog_urls(state: &AppState) -> Vec<String> {
+    state
+        .app_settings
+        .lock()
+        .map(|settings| settings.store_catalog_urls.clone())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn fetch_store_catalog(state: State<AppState>) -> Result<StoreCatalog, String> {
+    fetch_merged_catalog(&store_catalog_urls(&state))
+}
+
+#[tauri::command]
+pub fn set_store_catalog_urls(
+    state: State<AppState>,
+    urls: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for url in urls {
+        let url = url.trim().to_string();
+        if url.is_empty() || cleaned.contains(&url) {
+            continue;
+        }
+        if !is_https(&url) {
+            return Err(format!("Catalog URL must use https://: {}", url));
+        }
+        cleaned.push(url);
+    }
+
+    let mut settings = state
+        .app_settings
+        .lock()
+        .map_err(|_| "Lock poisoned".to_string())?;
+    settings.store_catalog_urls = cleaned.clone();
+    let updated = settings.clone();
+    drop(settings);
+
+    state
+        .app_settings_store
+        .save(&updated)
+        .map_err(|err| err.to_string())?;
+    Ok(cleaned)
 }
 
 #[tauri::command]
 pub fn install_store_plugin(
     app: AppHandle,
+    state: State<AppState>,
     plugin_id: String,
 ) -> Result<InstalledPluginInfo, String> {
-    let catalog = fetch_store_catalog()?;
+    let catalog = fetch_merged_catalog(&store_catalog_urls(&state))?;
     let plugin = catalog
         .plugins
         .iter()
@@ -165,6 +509,110 @@ pub fn install_store_plugin(
     let pkg = download_bytes(&plugin.latest.download_url, 60_000_000)?;
     let _sha_hex = verify_release_signature(&plugin, &pkg)?;
 
-    let b64 = base64::engine::general_purpose::STANDARD.encode(pkg);
-    install_plugin_package(app, format!("{}.midimaster", plugin.id), b64)
+    install_plugin_bytes(&app, pkg, Some(plugin.latest.signature_key_id))
+}
+
+/// Compares every installed, non-bundled plugin against the store catalog.
+#[tauri::command]
+pub fn check_plugin_updates(
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<Vec<PluginUpdateInfo>, String> {
+    let installed = list_plugins(app)?;
+    let catalog = fetch_merged_catalog(&store_catalog_urls(&state))?;
+    Ok(installed
+        .into_iter()
+        .filter(|manifest| !manifest.bundled)
+        .map(|manifest| {
+            let store = catalog
+                .plugins
+                .iter()
+                .find(|plugin| plugin.id == manifest.id)
+                .cloned();
+            let status = match &store {
+                None => PluginUpdateStatus::NotInStore,
+                Some(plugin) => update_status(&manifest.version, &plugin.latest.version),
+            };
+            PluginUpdateInfo {
+                id: manifest.id,
+                name: manifest.name,
+                installed_version: manifest.version,
+                status,
+                store,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prerelease_sorts_before_release() {
+        assert_eq!(
+            compare_versions("1.2.0-beta", "1.2.0"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare_versions("1.2.0", "1.2.0-rc.1"),
+            Some(Ordering::Greater)
+        );
+    }
+
+    #[test]
+    fn prerelease_identifiers_follow_semver() {
+        assert_eq!(
+            compare_versions("1.0.0-alpha", "1.0.0-alpha.1"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare_versions("1.0.0-alpha.1", "1.0.0-alpha.beta"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare_versions("1.0.0-beta.2", "1.0.0-beta.11"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare_versions("1.0.0-beta.11", "1.0.0-rc.1"),
+            Some(Ordering::Less)
+        );
+    }
+
+    #[test]
+    fn missing_parts_count_as_zero() {
+        assert_eq!(compare_versions("1.2", "1.2.0"), Some(Ordering::Equal));
+        assert_eq!(compare_versions("v2", "1.9.9"), Some(Ordering::Greater));
+        assert_eq!(compare_versions("1.2", "1.2.1"), Some(Ordering::Less));
+    }
+
+    #[test]
+    fn build_metadata_is_ignored() {
+        assert_eq!(
+            compare_versions("1.0.0+abc", "1.0.0+def"),
+            Some(Ordering::Equal)
+        );
+    }
+
+    #[test]
+    fn unparseable_version_is_unknown() {
+        assert_eq!(compare_versions("1.2.3.4", "1.2.3"), None);
+        assert_eq!(
+            update_status("nightly", "1.0.0"),
+            PluginUpdateStatus::Unknown
+        );
+        assert_eq!(
+            update_status("1.0.0", "latest"),
+            PluginUpdateStatus::Unknown
+        );
+        assert_eq!(
+            update_status("1.0.0", "1.1.0"),
+            PluginUpdateStatus::UpdateAvailable
+        );
+        assert_eq!(
+            update_status("1.1.0", "1.1.0"),
+            PluginUpdateStatus::UpToDate
+        );
+    }
 }
@@ -36,7 +36,7 @@ pub fn display_device_id(raw_name: &str) -> Option<String> {
 }
 
 #[cfg(target_os = "windows")]
-fn read_edid_name(device_id: &str) -> Option<String> {
+fn read_edid(device_id: &str) -> Option<Vec<u8>> {
     let sub_key = format!(
         "SYSTEM\\CurrentControlSet\\Enum\\{}\\Device Parameters",
         device_id
@@ -88,7 +88,12 @@ fn read_edid_name(device_id: &str) -> Option<String> {
     if query_result.is_err() || data.len() < 128 {
         return None;
     }
+    Some(data)
+}
 
+#[cfg(target_os = "windows")]
+fn read_edid_name(device_id: &str) -> Option<String> {
+    let data = read_edid(device_id)?;
     for idx in 0..4 {
         let start = 54 + idx * 18;
         if data[start] == 0x00
@@ -115,6 +120,26 @@ pub fn monitor_display_name(raw_name: &str) -> Option<String> {
     read_edid_name(&device_id)
 }
 
+/// Manufacturer, product code and serial number from the monitor's EDID, e.g.
+/// `GSM-5B08-0001E240`. Unlike the device id it stays the same when the
+/// monitor is unplugged and plugged back in.
+#[cfg(target_os = "windows")]
+pub fn monitor_edid_id(raw_name: &str) -> Option<String> {
+    let data = read_edid(&display_device_id(raw_name)?)?;
+    let vendor = u16::from_be_bytes([data[8], data[9]]);
+    let letter = |shift: u16| (b'A' - 1 + ((vendor >> shift) & 0x1F) as u8) as char;
+    let product = u16::from_le_bytes([data[10], data[11]]);
+    let serial = u32::from_le_bytes([data[12], data[13], data[14], data[15]]);
+    Some(format!(
+        "{}{}{}-{:04X}-{:08X}",
+        letter(10),
+        letter(5),
+        letter(0),
+        product,
+        serial
+    ))
+}
+
 #[cfg(not(target_os = "windows"))]
 pub fn display_device_id(_raw_name: &str) -> Option<String> {
     None
@@ -124,3 +149,8 @@ pub fn display_device_id(_raw_name: &str) -> Option<String> {
 pub fn monitor_display_name(_raw_name: &str) -> Option<String> {
     None
 }
+
+#[cfg(not(target_os = "windows"))]
+pub fn monitor_edid_id(_raw_name: &str) -> Option<String> {
+    None
+}
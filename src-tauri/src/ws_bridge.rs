@@ -1,22 +1,208 @@
 use base64::Engine;
+use futures_util::stream::SplitStream;
 use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::{collections::HashMap, sync::Arc, time::Duration};
 use tauri::{AppHandle, Emitter, State};
 use tokio::sync::mpsc;
+use tokio::time::{Interval, MissedTickBehavior};
 use tokio_tungstenite::tungstenite::client::IntoClientRequest;
 use tokio_tungstenite::tungstenite::http::HeaderValue;
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::{
+    connect_async, tungstenite::protocol::Message, MaybeTlsStream, WebSocketStream,
+};
 use url::Url;
 
+type WsStream = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// First retry delay after a dropped connection; doubles per failed attempt.
+const RECONNECT_INITIAL_DELAY: Duration = Duration::from_millis(500);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+/// How long a plugin-initiated close waits for the peer's close frame.
+const CLOSE_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Where a connection goes, kept so it can be dialled again after a drop.
+struct WsTarget {
+    url: String,
+    headers: HashMap<String, String>,
+    connect_timeout: Duration,
+    /// Keepalive period. A ping still unanswered when the next one is due means
+    /// the connection is dead (e.g. dropped across sleep/resume).
+    ping_interval: Option<Duration>,
+}
+
+impl WsTarget {
+    async fn connect(&self) -> Result<WsStream, String> {
+        let parsed = Url::parse(&self.url).map_err(|e| e.to_string())?;
+        let mut req = parsed.into_client_request().map_err(|e| e.to_string())?;
+        {
+            let h = req.headers_mut();
+            for (k, v) in &self.headers {
+                let name = tokio_tungstenite::tungstenite::http::header::HeaderName::from_bytes(
+                    k.as_bytes(),
+                )
+                .map_err(|e| e.to_string())?;
+                let value = HeaderValue::from_bytes(v.as_bytes()).map_err(|e| e.to_string())?;
+                h.insert(name, value);
+            }
+        }
+
+        let connect_fut = async { connect_async(req).await.map_err(|e| e.to_string()) };
+        let (ws_stream, _resp) = tokio::time::timeout(self.connect_timeout, connect_fut)
+            .await
+            .map_err(|_| "WebSocket connect timed out".to_string())??;
+        Ok(ws_stream)
+    }
+}
+
+async fn next_ping(ping: &mut Option<Interval>) {
+    match ping {
+        Some(ping) => {
+            ping.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Waits for the peer to answer our close frame, dropping any data that was
+/// already in flight, so the socket isn't torn down mid-handshake.
+async fn await_peer_close(read: &mut SplitStream<WsStream>) {
+    let _ = tokio::time::timeout(CLOSE_HANDSHAKE_TIMEOUT, async {
+        while let Some(Ok(msg)) = read.next().await {
+            if matches!(msg, Message::Close(_)) {
+                break;
+            }
+        }
+    })
+    .await;
+}
+
+/// Pumps one socket until it drops. Returns true when the plugin closed it (or
+/// the hub went away), false when the peer or network did.
+async fn run_session(
+    app: &AppHandle,
+    id: u64,
+    ws_stream: WsStream,
+    ping_interval: Option<Duration>,
+    rx: &mut mpsc::UnboundedReceiver<Message>,
+) -> bool {
+    let (mut write, mut read) = ws_stream.split();
+    let mut ping = ping_interval.map(|period| {
+        let mut ping = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        ping.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ping
+    });
+    let mut awaiting_pong = false;
+    loop {
+        tokio::select! {
+          _ = next_ping(&mut ping) => {
+            if awaiting_pong {
+              return false;
+            }
+            if write.send(Message::Ping(Vec::new())).await.is_err() {
+              return false;
+            }
+            awaiting_pong = true;
+          }
+          msg_result = read.next() => {
+            // Any frame from the peer shows the connection is alive, not just a pong.
+            awaiting_pong = false;
+            match msg_result {
+              Some(Ok(Message::Text(text))) => {
+                let _ = app.emit("ws_message", serde_json::json!({"id": id, "type": "text", "data": text }));
+              }
+              Some(Ok(Message::Binary(bytes))) => {
+                let b64 = base64::engine::general_purpose::STANDARD.encode(bytes);
+                let _ = app.emit("ws_message", serde_json::json!({"id": id, "type": "binary", "data": b64 }));
+              }
+              Some(Ok(Message::Close(_))) => {
+                return false;
+              }
+              Some(Err(_)) => {
+                return false;
+              }
+              None => {
+                return false;
+              }
+              _ => {}
+            }
+          }
+          outgoing = rx.recv() => {
+            match outgoing {
+              Some(msg) => {
+                let closing = matches!(msg, Message::Close(_));
+                if write.send(msg).await.is_err() {
+                  return closing;
+                }
+                if closing {
+                  await_peer_close(&mut read).await;
+                  return true;
+                }
+              }
+              None => return true,
+            }
+          }
+        }
+    }
+}
+
+/// Redials `target` with capped exponential backoff, up to `max_attempts` times.
+/// `ws_send` is refused while this runs; a close request gives up.
+async fn reconnect(
+    app: &AppHandle,
+    id: u64,
+    target: &WsTarget,
+    max_attempts: u32,
+    rx: &mut mpsc::UnboundedReceiver<Message>,
+) -> Option<WsStream> {
+    let mut delay = RECONNECT_INITIAL_DELAY;
+    for attempt in 1..=max_attempts {
+        let _ = app.emit(
+            "ws_reconnecting",
+            serde_json::json!({"id": id, "attempt": attempt, "delay_ms": delay.as_millis() as u64}),
+        );
+        let mut sleep = std::pin::pin!(tokio::time::sleep(delay));
+        loop {
+            tokio::select! {
+              _ = &mut sleep => break,
+              outgoing = rx.recv() => {
+                match outgoing {
+                  Some(Message::Close(_)) | None => return None,
+                  Some(_) => {}
+                }
+              }
+            }
+        }
+        if let Ok(stream) = target.connect().await {
+            let _ = app.emit(
+                "ws_reconnected",
+                serde_json::json!({"id": id, "attempt": attempt}),
+            );
+            return Some(stream);
+        }
+        delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+    }
+    None
+}
+
 #[derive(Clone, Default)]
 pub struct WsHub {
     inner: Arc<WsHubInner>,
 }
 
+struct WsConn {
+    tx: mpsc::UnboundedSender<Message>,
+    /// Set while the socket is down and being redialled.
+    reconnecting: Arc<AtomicBool>,
+}
+
 #[derive(Default)]
 struct WsHubInner {
     next_id: std::sync::atomic::AtomicU64,
-    conns: tokio::sync::Mutex<HashMap<u64, mpsc::UnboundedSender<Message>>>,
+    conns: tokio::sync::Mutex<HashMap<u64, WsConn>>,
 }
 
 impl WsHub {
@@ -30,76 +216,49 @@ impl WsHub {
         url: String,
         headers: HashMap<String, String>,
         connect_timeout_ms: u64,
+        ping_interval_ms: u64,
+        reconnect_attempts: u32,
     ) -> Result<u64, String> {
-        let parsed = Url::parse(&url).map_err(|e| e.to_string())?;
-        let mut req = parsed.into_client_request().map_err(|e| e.to_string())?;
-        {
-            let h = req.headers_mut();
-            for (k, v) in headers {
-                let name = tokio_tungstenite::tungstenite::http::header::HeaderName::from_bytes(
-                    k.as_bytes(),
-                )
-                .map_err(|e| e.to_string())?;
-                let value = HeaderValue::from_bytes(v.as_bytes()).map_err(|e| e.to_string())?;
-                h.insert(name, value);
-            }
-        }
-
-        let connect_fut = async { connect_async(req).await.map_err(|e| e.to_string()) };
-        let (ws_stream, _resp) =
-            tokio::time::timeout(Duration::from_millis(connect_timeout_ms), connect_fut)
-                .await
-                .map_err(|_| "WebSocket connect timed out".to_string())??;
+        let target = WsTarget {
+            url,
+            headers,
+            connect_timeout: Duration::from_millis(connect_timeout_ms),
+            ping_interval: (ping_interval_ms > 0).then(|| Duration::from_millis(ping_interval_ms)),
+        };
+        let ws_stream = target.connect().await?;
 
         let id = self
             .inner
             .next_id
             .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
             .saturating_add(1);
-        let (mut write, mut read) = ws_stream.split();
         let (tx, mut rx) = mpsc::unbounded_channel::<Message>();
+        let reconnecting = Arc::new(AtomicBool::new(false));
 
         {
             let mut conns = self.inner.conns.lock().await;
-            conns.insert(id, tx);
+            conns.insert(
+                id,
+                WsConn {
+                    tx,
+                    reconnecting: reconnecting.clone(),
+                },
+            );
         }
 
         let hub = self.clone();
         tauri::async_runtime::spawn(async move {
+            let mut ws_stream = ws_stream;
             loop {
-                tokio::select! {
-                  msg_result = read.next() => {
-                    match msg_result {
-                      Some(Ok(Message::Text(text))) => {
-                        let _ = app.emit("ws_message", serde_json::json!({"id": id, "type": "text", "data": text }));
-                      }
-                      Some(Ok(Message::Binary(bytes))) => {
-                        let b64 = base64::engine::general_purpose::STANDARD.encode(bytes);
-                        let _ = app.emit("ws_message", serde_json::json!({"id": id, "type": "binary", "data": b64 }));
-                      }
-                      Some(Ok(Message::Close(_))) => {
-                        break;
-                      }
-                      Some(Err(_)) => {
-                        break;
-                      }
-                      None => {
-                        break;
-                      }
-                      _ => {}
-                    }
-                  }
-                  outgoing = rx.recv() => {
-                    match outgoing {
-                      Some(msg) => {
-                        if let Err(_) = write.send(msg).await {
-                          break;
-                        }
-                      }
-                      None => break,
-                    }
-                  }
+                if run_session(&app, id, ws_stream, target.ping_interval, &mut rx).await {
+                    break;
                 }
+                reconnecting.store(true, Ordering::Release);
+                match reconnect(&app, id, &target, reconnect_attempts, &mut rx).await {
+                    Some(stream) => ws_stream = stream,
+                    None => break,
+                }
+                reconnecting.store(false, Ordering::Release);
             }
 
             {
@@ -112,38 +271,71 @@ impl WsHub {
         Ok(id)
     }
 
-    pub async fn send_text(&self, id: u64, text: String) -> Result<(), String> {
+    /// Queues a data message. Fails with "reconnecting" while the socket is
+    /// being redialled rather than silently dropping it.
+    async fn send_data(&self, id: u64, msg: Message) -> Result<(), String> {
         let conns = self.inner.conns.lock().await;
-        let tx = conns
+        let conn = conns
             .get(&id)
             .ok_or_else(|| "Unknown WebSocket id".to_string())?;
-        tx.send(Message::Text(text))
+        if conn.reconnecting.load(Ordering::Acquire) {
+            return Err("reconnecting".to_string());
+        }
+        conn.tx
+            .send(msg)
             .map_err(|_| "WebSocket send failed".to_string())
     }
 
-    pub async fn close(&self, id: u64) -> Result<(), String> {
+    pub async fn send_text(&self, id: u64, text: String) -> Result<(), String> {
+        self.send_data(id, Message::Text(text)).await
+    }
+
+    pub async fn send_binary(&self, id: u64, bytes: Vec<u8>) -> Result<(), String> {
+        self.send_data(id, Message::Binary(bytes)).await
+    }
+
+    pub async fn close(&self, id: u64, frame: Option<CloseFrame<'static>>) -> Result<(), String> {
         let conns = self.inner.conns.lock().await;
-        let tx = conns
+        let conn = conns
             .get(&id)
             .ok_or_else(|| "Unknown WebSocket id".to_string())?;
-        tx.send(Message::Close(None))
+        conn.tx
+            .send(Message::Close(frame))
             .map_err(|_| "WebSocket close failed".to_string())
     }
 }
 
+/// Connection options for `ws_open`. Everything is optional.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct WsOpenOptions {
+    pub headers: HashMap<String, String>,
+    pub connect_timeout_ms: Option<u64>,
+    pub ping_interval_ms: Option<u64>,
+    pub auto_reconnect: bool,
+    pub max_reconnect_attempts: Option<u32>,
+}
+
 #[tauri::command]
 pub async fn ws_open(
     app: AppHandle,
     hub: State<'_, WsHub>,
     url: String,
-    headers: Option<HashMap<String, String>>,
-    connect_timeout_ms: Option<u64>,
+    options: Option<WsOpenOptions>,
 ) -> Result<u64, String> {
+    let options = options.unwrap_or_default();
+    let reconnect_attempts = if options.auto_reconnect {
+        options.max_reconnect_attempts.unwrap_or(10)
+    } else {
+        0
+    };
     hub.open(
         app,
         url,
-        headers.unwrap_or_default(),
-        connect_timeout_ms.unwrap_or(500),
+        options.headers,
+        options.connect_timeout_ms.unwrap_or(500),
+        options.ping_interval_ms.unwrap_or(0),
+        reconnect_attempts,
     )
     .await
 }
@@ -154,6 +346,34 @@ pub async fn ws_send(hub: State<'_, WsHub>, id: u64, text: String) -> Result<(),
 }
 
 #[tauri::command]
-pub async fn ws_close(hub: State<'_, WsHub>, id: u64) -> Result<(), String> {
-    hub.close(id).await
+pub async fn ws_send_binary(hub: State<'_, WsHub>, id: u64, base64: String) -> Result<(), String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(base64.as_bytes())
+        .map_err(|e| e.to_string())?;
+    hub.send_binary(id, bytes).await
+}
+
+#[tauri::command]
+pub async fn ws_close(
+    hub: State<'_, WsHub>,
+    id: u64,
+    code: Option<u16>,
+    reason: Option<String>,
+) -> Result<(), String> {
+    // A reason can only travel with a code; default to a normal closure.
+    let frame = match (code, reason) {
+        (None, None) => None,
+        (code, reason) => {
+            let reason = reason.unwrap_or_default();
+            // Control frame payloads are capped at 125 bytes, 2 of them for the code.
+            if reason.len() > 123 {
+                return Err("WebSocket close reason is too long".to_string());
+            }
+            Some(CloseFrame {
+                code: CloseCode::from(code.unwrap_or(1000)),
+                reason: reason.into(),
+            })
+        }
+    };
+    hub.close(id, frame).await
 }
@@ -39,6 +39,51 @@ function normalizeSessionKey(session) {
   return session?.display_name?.toLowerCase() || "";
 }
 
+// Raw device id the backend resolves to the current Communications default.
+// Session ids are `[device|]pid:identifier`; the process id changes on every
+// launch, so saved Session targets are matched without it (see app_match.rs).
+function stableSessionKey(sessionId) {
+  const stripPid = (id) => {
+    const match = /^(\d+):(.*)$/s.exec(id);
+    return match ? match[2] : null;
+  };
+  const id = String(sessionId ?? "");
+  const direct = stripPid(id);
+  if (direct != null) return direct;
+  const split = id.indexOf("|");
+  if (split === -1) return null;
+  const inner = stripPid(id.slice(split + 1));
+  return inner != null ? `${id.slice(0, split)}|${inner}` : null;
+}
+
+export function findSession(sessions, sessionId, path) {
+  const exact = sessions.find((item) => String(item.id) === String(sessionId));
+  if (exact) return exact;
+  const samePath = (item) => Boolean(path && item.process_path
+    && item.process_path.toLowerCase() === String(path).toLowerCase());
+  const key = stableSessionKey(sessionId);
+  if (key == null) return path ? sessions.find(samePath) : undefined;
+  return sessions.find((item) => (!path || samePath(item)) && stableSessionKey(item.id) === key);
+}
+
+export const COMMUNICATIONS_DEVICE_ID = "communications";
+
+export const DEFAULT_DEVICE_ID = "default";
+
+// DefaultMicrophone/DefaultSpeakers stand for a Device target on whichever
+// endpoint is currently the default.
+export function defaultDeviceId(target) {
+  if (target === "DefaultMicrophone" || target?.DefaultMicrophone != null) return `recording:${DEFAULT_DEVICE_ID}`;
+  if (target === "DefaultSpeakers" || target?.DefaultSpeakers != null) return `playback:${DEFAULT_DEVICE_ID}`;
+  return null;
+}
+
+function findDevice(devices, rawId) {
+  return devices.find((device) => device.id === rawId
+    || (rawId === COMMUNICATIONS_DEVICE_ID && device.is_comms_default)
+    || (rawId === DEFAULT_DEVICE_ID && device.is_default));
+}
+
 export function createTargetCore({
   masterIconData,
   focusIconData,
@@ -61,7 +106,7 @@ export function createTargetCore({
     if (!target) {
       return { label: "Volume", icon_data: masterIconData };
     }
-    const focusName = focusSession?.display_name?.trim();
+    const focusName = (focusSession?.alias || focusSession?.display_name)?.trim();
     if (typeof target === "string") {
       if (target === "Master") {
         return { label: "Master", icon_data: masterIconData };
@@ -72,6 +117,18 @@ export function createTargetCore({
           icon_data: focusSession?.icon_data ?? focusIconData,
         };
       }
+      if (target === "SystemSounds") {
+        return { label: "System Sounds", icon_data: masterIconData };
+      }
+      if (target === "DefaultMicrophone" || target === "DefaultSpeakers") {
+        const recording = target === "DefaultMicrophone";
+        const device = findDevice(recording ? getRecording() : getPlayback(), DEFAULT_DEVICE_ID);
+        const label = recording ? "Default Microphone" : "Default Speakers";
+        return {
+          label: device ? `${label} (${device.alias || device.display_name})` : label,
+          icon_data: device?.icon_data ?? null,
+        };
+      }
       if (target === "Unset") {
         return null;
       }
@@ -87,6 +144,30 @@ export function createTargetCore({
       };
     }
 
+    const balance = target.ApplicationBalance || target.applicationBalance;
+    if (balance && balance.left && balance.right) {
+      const appLabel = (name) => {
+        const session = sessions.find((item) => normalizeSessionKey(item) === String(name).toLowerCase());
+        return session?.alias || session?.display_name || name;
+      };
+      return {
+        label: `${appLabel(balance.left)} ⇄ ${appLabel(balance.right)}`,
+        icon_data: null,
+      };
+    }
+
+    const group = target.ApplicationGroup || target.applicationGroup;
+    if (group && Array.isArray(group.names) && group.names.length > 0) {
+      const appLabel = (name) => {
+        const session = sessions.find((item) => normalizeSessionKey(item) === String(name).toLowerCase());
+        return session?.alias || session?.display_name || name;
+      };
+      return {
+        label: group.names.map(appLabel).join(", "),
+        icon_data: null,
+      };
+    }
+
     const appContainer = target.Application || target.application || (targetType === "Application" ? target : null);
     const appName = (typeof appContainer === "string")
       ? appContainer
@@ -95,7 +176,7 @@ export function createTargetCore({
     if (appName) {
       const session = sessions.find((item) => normalizeSessionKey(item) === appName.toLowerCase());
       return {
-        label: session?.display_name || appName,
+        label: session?.alias || session?.display_name || appName,
         icon_data: session?.icon_data ?? null,
       };
     }
@@ -106,9 +187,9 @@ export function createTargetCore({
       sessionId = sessionContainer;
     }
     if (sessionId) {
-      const session = sessions.find((item) => String(item.id) === String(sessionId));
+      const session = findSession(sessions, sessionId, sessionContainer?.path);
       return {
-        label: session?.display_name || "Application",
+        label: session?.alias || session?.display_name || "Application",
         icon_data: session?.icon_data ?? null,
       };
     }
@@ -128,9 +209,15 @@ export function createTargetCore({
         rawId = deviceId.slice("playback:".length);
       }
       const deviceList = kind === "recording" ? recordingDevices : playbackDevices;
-      const device = deviceList.find((item) => item.id === rawId);
+      const device = findDevice(deviceList, rawId);
+      if (rawId === COMMUNICATIONS_DEVICE_ID) {
+        return {
+          label: device ? `Communications (${device.alias || device.display_name})` : "Communications Device",
+          icon_data: device?.icon_data ?? null,
+        };
+      }
       return {
-        label: device?.display_name || "Audio Device",
+        label: device?.alias || device?.display_name || "Audio Device",
         icon_data: device?.icon_data ?? null,
       };
     }
@@ -169,6 +256,9 @@ export function createTargetCore({
     if (!target) return null;
     if (target === "Master" || target.Master !== undefined) return "::master::";
     if (target === "Focus" || target.Focus !== undefined) return "::focus::";
+    if (target === "SystemSounds" || target.SystemSounds !== undefined) return "::system_sounds::";
+    if (target === "DefaultMicrophone" || target.DefaultMicrophone !== undefined) return "::default_microphone::";
+    if (target === "DefaultSpeakers" || target.DefaultSpeakers !== undefined) return "::default_speakers::";
 
     const integration = target.Integration || target.integration;
     if (integration && integration.integration_id) {
@@ -178,6 +268,17 @@ export function createTargetCore({
       }
     }
 
+    const balance = target.ApplicationBalance || target.applicationBalance;
+    if (balance && balance.left && balance.right) {
+      return `balance:${String(balance.left).toLowerCase()}:${String(balance.right).toLowerCase()}`;
+    }
+
+    const group = target.ApplicationGroup || target.applicationGroup;
+    if (group && Array.isArray(group.names) && group.names.length > 0) {
+      const names = group.names.map((name) => String(name).toLowerCase()).sort();
+      return `group:${names.join(",")}`;
+    }
+
     const appContainer = target.Application || target.application;
     if (appContainer) {
       if (typeof appContainer === "string") return appContainer.toLowerCase();
@@ -204,7 +305,7 @@ export function createTargetCore({
       if (typeof sessionId === "object" && sessionId !== null) {
         sessionId = sessionId.id ?? sessionId.value ?? sessionId;
       }
-      const session = sessions.find((s) => String(s.id) === String(sessionId));
+      const session = findSession(sessions, sessionId, sessionContainer?.path);
       if (session) return normalizeSessionKey(session);
       return `session:${sessionId}`;
     }
@@ -246,6 +347,8 @@ export function createTargetCore({
   }
 
   function resolveTargetVolume(target) {
+    const defaultDevice = defaultDeviceId(target);
+    if (defaultDevice) target = { Device: { device_id: defaultDevice } };
     const sessions = getSess();
     const playbackDevices = getPlayback();
     const recordingDevices = getRecording();
@@ -274,7 +377,7 @@ export function createTargetCore({
       sessionId = sessionContainer;
     }
     if (sessionId) {
-      const session = sessions.find((item) => String(item.id) === String(sessionId));
+      const session = findSession(sessions, sessionId, sessionContainer?.path);
       return session?.volume ?? null;
     }
 
@@ -293,7 +396,7 @@ export function createTargetCore({
         rawId = deviceId.slice("playback:".length);
       }
       const deviceList = kind === "recording" ? recordingDevices : playbackDevices;
-      const device = deviceList.find((item) => item.id === rawId);
+      const device = findDevice(deviceList, rawId);
       return device?.volume ?? null;
     }
 
@@ -301,6 +404,8 @@ export function createTargetCore({
   }
 
   function getVolumeForTarget(target) {
+    const defaultDevice = defaultDeviceId(target);
+    if (defaultDevice) target = { Device: { device_id: defaultDevice } };
     const sessions = getSess();
     const playbackDevices = getPlayback();
     const recordingDevices = getRecording();
@@ -327,7 +432,7 @@ export function createTargetCore({
     const sessionContainer = target.Session || target.session;
     const sessionId = sessionContainer?.session_id ?? sessionContainer?.sessionId ?? target.session_id;
     if (sessionId) {
-      const session = sessions.find((item) => String(item.id) === String(sessionId));
+      const session = findSession(sessions, sessionId, sessionContainer?.path);
       return session ? session.volume : null;
     }
 
@@ -345,7 +450,7 @@ export function createTargetCore({
         }
       }
       const deviceList = kind === "recording" ? recordingDevices : playbackDevices;
-      const device = deviceList.find((d) => d.id === rawId);
+      const device = findDevice(deviceList, rawId);
       return device ? device.volume : null;
     }
 
@@ -353,6 +458,8 @@ export function createTargetCore({
   }
 
   function getMuteForTarget(target) {
+    const defaultDevice = defaultDeviceId(target);
+    if (defaultDevice) target = { Device: { device_id: defaultDevice } };
     const sessions = getSess();
     const playbackDevices = getPlayback();
     const recordingDevices = getRecording();
@@ -378,7 +485,7 @@ export function createTargetCore({
     const sessionContainer = target.Session || target.session;
     const sessionId = sessionContainer?.session_id ?? sessionContainer?.sessionId ?? target.session_id;
     if (sessionId) {
-      const session = sessions.find((item) => item.id === sessionId);
+      const session = findSession(sessions, sessionId, sessionContainer?.path);
       return session ? session.muted : false;
     }
 
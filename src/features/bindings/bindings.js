@@ -1,3 +1,5 @@
+import { defaultDeviceId } from "../../core/target_core.js";
+
 export function createBindingsFeature({
   invoke,
   dom,
@@ -192,7 +194,7 @@ export function createBindingsFeature({
             const trimmedName = nameInput.value.trim();
             binding.name = trimmedName || fallbackName;
             setEditingId(null);
-            await invoke("add_binding", { binding });
+            await invoke("update_binding", { binding });
             await saveProfile();
             renderBindings();
           });
@@ -207,7 +209,8 @@ export function createBindingsFeature({
         const controlInfo = document.createElement("div");
         controlInfo.textContent = labelForControl(binding.control);
 
-        const isButton = binding.control?.msg_type === "Note";
+        const isButton = binding.control?.msg_type === "Note"
+          || binding.control?.msg_type === "ProgramChange";
         console.log(
           "renderBindings binding:",
           binding.id,
@@ -221,26 +224,50 @@ export function createBindingsFeature({
 
         const modeSelect = document.createElement("select");
         if (isButton) {
-          const option = document.createElement("option");
-          option.value = "Toggle";
-          option.textContent = "Toggle";
-          modeSelect.appendChild(option);
-          modeSelect.disabled = true;
-          modeSelect.title = "Button bindings toggle mute state";
+          [
+            ["Toggle", "Toggle"],
+            ["Momentary", "Momentary"],
+          ].forEach(([value, label]) => {
+            const option = document.createElement("option");
+            option.value = value;
+            option.textContent = label;
+            if ((binding.button_mode || "Toggle") === value) {
+              option.selected = true;
+            }
+            modeSelect.appendChild(option);
+          });
+          modeSelect.title = "Toggle flips mute on each press; Momentary mutes while held";
+          modeSelect.addEventListener("change", () => {
+            binding.button_mode = modeSelect.value;
+            invoke("update_binding", { binding });
+            saveProfile();
+          });
         } else {
-          ["Absolute", "Relative"].forEach((mode) => {
+          const currentMode = binding.mode === "Relative"
+            ? `Relative:${binding.relative_mode || "SignedBit"}`
+            : "Absolute";
+          [
+            ["Absolute", "Absolute"],
+            ["Relative:SignedBit", "Relative"],
+            ["Relative:TwosComplement", "Relative (2's comp.)"],
+            ["Relative:BinaryOffset", "Relative (offset)"],
+          ].forEach(([value, label]) => {
             const option = document.createElement("option");
-            option.value = mode;
-            option.textContent = mode;
-            if (binding.mode === mode) {
+            option.value = value;
+            option.textContent = label;
+            if (currentMode === value) {
               option.selected = true;
             }
             modeSelect.appendChild(option);
           });
 
           modeSelect.addEventListener("change", () => {
-            binding.mode = modeSelect.value;
-            invoke("add_binding", { binding });
+            const [mode, relativeMode] = modeSelect.value.split(":");
+            binding.mode = mode;
+            if (relativeMode) {
+              binding.relative_mode = relativeMode;
+            }
+            invoke("update_binding", { binding });
             saveProfile();
           });
         }
@@ -267,14 +294,12 @@ export function createBindingsFeature({
           }
 
           if (isButton) {
-            binding.action = targetSelect.dataset.action || binding.action || "ToggleMute";
+            binding.action = targetSelect.dataset.action === "PushToTalk" ? "PushToTalk" : "ToggleMute";
           } else {
-            binding.action = "Volume";
+            binding.action = targetSelect.dataset.action === "Balance" ? "Balance" : "Volume";
           }
 
-          if (isButton) {
-            binding.action = "ToggleMute";
-          } else {
+          if (!isButton) {
             const newVolume = getVol(binding.target);
             if (volumeSlider) {
               volumeSlider.value = newVolume;
@@ -288,7 +313,7 @@ export function createBindingsFeature({
             }
           }
 
-          invoke("add_binding", { binding });
+          invoke("update_binding", { binding });
           saveProfile();
 
           try {
@@ -330,6 +355,12 @@ export function createBindingsFeature({
               if (target === "Master" || target?.Master != null) {
                 await invoke("set_master_volume", { volume: vol });
                 invoked = true;
+              } else if (target === "SystemSounds" || target?.SystemSounds != null) {
+                await invoke("set_system_sounds_volume", { volume: vol });
+                invoked = true;
+              } else if (defaultDeviceId(target)) {
+                await invoke("set_device_volume", { deviceId: defaultDeviceId(target), volume: vol });
+                invoked = true;
               } else if (target === "Focus" || target?.Focus != null) {
                 // Focus volume not supported through this path.
               } else {
@@ -416,6 +447,12 @@ export function createBindingsFeature({
             if (target === "Master" || target?.Master != null) {
               await invoke("set_master_mute", { muted: newMuted });
               invoked = true;
+            } else if (target === "SystemSounds" || target?.SystemSounds != null) {
+              await invoke("set_system_sounds_mute", { muted: newMuted });
+              invoked = true;
+            } else if (defaultDeviceId(target)) {
+              await invoke("set_device_mute", { deviceId: defaultDeviceId(target), muted: newMuted });
+              invoked = true;
             } else if (target === "Focus" || target?.Focus != null) {
               // Focus mute not supported
             } else {
@@ -500,6 +537,48 @@ export function createBindingsFeature({
           beginBindingEdit(binding.id);
         });
 
+        const feedbackButton = document.createElement("button");
+        feedbackButton.type = "button";
+        feedbackButton.className = "binding-action";
+        feedbackButton.textContent = "\u25c9";
+        feedbackButton.title = "Send feedback (LEDs, motor position) to the control";
+        feedbackButton.classList.toggle("active", binding.feedback_enabled !== false);
+        feedbackButton.addEventListener("click", () => {
+          binding.feedback_enabled = binding.feedback_enabled === false;
+          feedbackButton.classList.toggle("active", binding.feedback_enabled);
+          invoke("update_binding", { binding });
+          saveProfile();
+        });
+
+        const osdButton = document.createElement("button");
+        osdButton.type = "button";
+        osdButton.className = "binding-action";
+        osdButton.textContent = "\u25a2";
+        osdButton.title = "Show the on-screen display when this control changes";
+        osdButton.classList.toggle("active", binding.show_osd !== false);
+        osdButton.addEventListener("click", () => {
+          binding.show_osd = binding.show_osd === false;
+          osdButton.classList.toggle("active", binding.show_osd);
+          invoke("update_binding", { binding });
+          saveProfile();
+        });
+
+        let meterButton = null;
+        if (!isButton) {
+          meterButton = document.createElement("button");
+          meterButton.type = "button";
+          meterButton.className = "binding-action";
+          meterButton.textContent = "\u2261";
+          meterButton.title = "Show the live audio level on the control's LEDs";
+          meterButton.classList.toggle("active", Boolean(binding.meter_feedback));
+          meterButton.addEventListener("click", () => {
+            binding.meter_feedback = !binding.meter_feedback;
+            meterButton.classList.toggle("active", binding.meter_feedback);
+            invoke("update_binding", { binding });
+            saveProfile();
+          });
+        }
+
         const deleteButton = document.createElement("button");
         deleteButton.type = "button";
         deleteButton.className = "binding-action delete";
@@ -519,6 +598,11 @@ export function createBindingsFeature({
 
         actions.appendChild(dragButton);
         actions.appendChild(editButton);
+        actions.appendChild(feedbackButton);
+        actions.appendChild(osdButton);
+        if (meterButton) {
+          actions.appendChild(meterButton);
+        }
         actions.appendChild(deleteButton);
 
         row.appendChild(nameField);
@@ -663,7 +747,11 @@ export function createBindingsFeature({
       next.splice(insertIndex, 0, moved);
       setB(next);
       renderBindings();
-      await saveProfile();
+      try {
+        await invoke("reorder_bindings", { orderedIds: next.map((binding) => binding.id) });
+      } catch (err) {
+        console.error("Failed to reorder bindings:", err);
+      }
     }
   }
 
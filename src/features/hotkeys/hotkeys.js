@@ -0,0 +1,182 @@
+// Presets offered in the action picker. `value` is what the backend stores.
+const HOTKEY_ACTIONS = [
+  { label: "Mute master", value: { type: "Binding", target: "Master", action: "ToggleMute" } },
+  { label: "Mute focused app", value: { type: "Binding", target: "Focus", action: "ToggleMute" } },
+  { label: "Master volume up", value: { type: "Binding", target: "Master", action: { VolumeStep: { delta: 0.05 } } } },
+  { label: "Master volume down", value: { type: "Binding", target: "Master", action: { VolumeStep: { delta: -0.05 } } } },
+  { label: "Focused app volume up", value: { type: "Binding", target: "Focus", action: { VolumeStep: { delta: 0.05 } } } },
+  { label: "Focused app volume down", value: { type: "Binding", target: "Focus", action: { VolumeStep: { delta: -0.05 } } } },
+  { label: "Next output device", value: { type: "Binding", target: "Master", action: "CycleDefaultDevice" } },
+];
+
+const MODIFIER_CODES = new Set([
+  "ControlLeft", "ControlRight", "AltLeft", "AltRight",
+  "ShiftLeft", "ShiftRight", "MetaLeft", "MetaRight",
+]);
+
+// Builds an accelerator (`Ctrl+Alt+KeyM`) from a keydown, or null while only modifiers are held.
+function shortcutFromEvent(event) {
+  if (MODIFIER_CODES.has(event.code)) return null;
+  const parts = [];
+  if (event.ctrlKey) parts.push("Ctrl");
+  if (event.altKey) parts.push("Alt");
+  if (event.shiftKey) parts.push("Shift");
+  if (event.metaKey) parts.push("Super");
+  parts.push(event.code);
+  return parts.join("+");
+}
+
+function presetIndex(action) {
+  const json = JSON.stringify({ type: action.type, target: action.target, action: action.action });
+  return HOTKEY_ACTIONS.findIndex((preset) => JSON.stringify(preset.value) === json);
+}
+
+export function createHotkeysFeature({ invoke, dom }) {
+  if (typeof invoke !== "function") {
+    throw new Error("createHotkeysFeature: invoke is required");
+  }
+  const d = (dom && typeof dom === "object") ? dom : {};
+
+  let hotkeys = [];
+  let profileNames = [];
+  let failed = new Set();
+
+  async function save() {
+    const complete = hotkeys.filter((hotkey) => hotkey.shortcut);
+    try {
+      const rejected = await invoke("set_hotkeys", { hotkeys: complete });
+      failed = new Set(Array.isArray(rejected) ? rejected : []);
+    } catch (error) {
+      console.error("Failed to save hotkeys", error);
+    }
+    render();
+  }
+
+  function actionSelect(hotkey) {
+    const select = document.createElement("select");
+    HOTKEY_ACTIONS.forEach((preset, index) => {
+      const option = document.createElement("option");
+      option.value = `preset:${index}`;
+      option.textContent = preset.label;
+      select.appendChild(option);
+    });
+    profileNames.forEach((name) => {
+      const option = document.createElement("option");
+      option.value = `profile:${name}`;
+      option.textContent = `Load profile: ${name}`;
+      select.appendChild(option);
+    });
+
+    if (hotkey.action.type === "LoadProfile") {
+      select.value = `profile:${hotkey.action.name}`;
+    } else {
+      const index = presetIndex(hotkey.action);
+      if (index >= 0) {
+        select.value = `preset:${index}`;
+      } else {
+        // Set outside the presets (e.g. by editing app_settings.json); keep it as-is.
+        const option = document.createElement("option");
+        option.value = "custom";
+        option.textContent = "Custom action";
+        select.appendChild(option);
+        select.value = "custom";
+      }
+    }
+
+    select.addEventListener("change", () => {
+      const [kind, ...rest] = select.value.split(":");
+      const param = rest.join(":");
+      if (kind === "preset") {
+        hotkey.action = structuredClone(HOTKEY_ACTIONS[Number(param)].value);
+      } else if (kind === "profile") {
+        hotkey.action = { type: "LoadProfile", name: param };
+      }
+      save();
+    });
+    return select;
+  }
+
+  function shortcutInput(hotkey) {
+    const input = document.createElement("input");
+    input.type = "text";
+    input.readOnly = true;
+    input.className = "hotkey-shortcut";
+    input.placeholder = "Press keys…";
+    input.value = hotkey.shortcut || "";
+    input.classList.toggle("invalid", failed.has(hotkey.shortcut));
+    input.title = failed.has(hotkey.shortcut)
+      ? "Could not register this shortcut; another app may be using it"
+      : "Click and press a key combination";
+    input.addEventListener("keydown", (event) => {
+      event.preventDefault();
+      event.stopPropagation();
+      const shortcut = shortcutFromEvent(event);
+      if (!shortcut) return;
+      hotkey.shortcut = shortcut;
+      input.value = shortcut;
+      save();
+    });
+    return input;
+  }
+
+  function render() {
+    if (!d.hotkeyList) return;
+    d.hotkeyList.innerHTML = "";
+    hotkeys.forEach((hotkey, index) => {
+      const row = document.createElement("div");
+      row.className = "hotkey-row";
+
+      const removeButton = document.createElement("button");
+      removeButton.type = "button";
+      removeButton.className = "delete";
+      removeButton.textContent = "×";
+      removeButton.title = "Remove hotkey";
+      removeButton.addEventListener("click", () => {
+        hotkeys.splice(index, 1);
+        save();
+      });
+
+      row.appendChild(shortcutInput(hotkey));
+      row.appendChild(actionSelect(hotkey));
+      row.appendChild(removeButton);
+      d.hotkeyList.appendChild(row);
+    });
+  }
+
+  async function load() {
+    try {
+      const settings = await invoke("get_app_settings");
+      hotkeys = Array.isArray(settings?.hotkeys) ? settings.hotkeys : [];
+    } catch (error) {
+      console.error("Failed to load hotkeys", error);
+    }
+    try {
+      const profiles = await invoke("list_profiles");
+      profileNames = (profiles || []).map((profile) => profile.name);
+    } catch {
+      profileNames = [];
+    }
+    render();
+  }
+
+  function bindUi() {
+    if (d.settingsButton) {
+      d.settingsButton.addEventListener("click", load);
+    }
+    if (d.hotkeyAdd) {
+      d.hotkeyAdd.addEventListener("click", () => {
+        hotkeys.push({ shortcut: "", action: structuredClone(HOTKEY_ACTIONS[0].value) });
+        render();
+        const inputs = d.hotkeyList ? d.hotkeyList.querySelectorAll(".hotkey-shortcut") : [];
+        if (inputs.length > 0) {
+          inputs[inputs.length - 1].focus();
+        }
+      });
+    }
+  }
+
+  return {
+    bindUi,
+    load,
+  };
+}
@@ -16,6 +16,7 @@ export function createMidiFeature({
   let autoRefreshTimer = null;
   let sessionRefreshTimer = null;
   let learnTimer = null;
+  let unlearnActive = false;
 
   function startAutoRefresh(refreshFn) {
     if (autoRefreshTimer) {
@@ -77,6 +78,10 @@ export function createMidiFeature({
       clearInterval(learnTimer);
       learnTimer = null;
     }
+    if (unlearnActive) {
+      unlearnActive = false;
+      invoke("cancel_unlearn").catch(() => { });
+    }
     closeLearnPanel();
   }
 
@@ -159,8 +164,8 @@ export function createMidiFeature({
       return;
     }
     try {
-      await invoke("start_midi_device", { inputDeviceId: inputId, outputDeviceId: outputId });
-      localStorage.setItem("midiDeviceId", inputId);
+      const connectedId = await invoke("start_midi_device", { inputDeviceId: inputId, outputDeviceId: outputId });
+      localStorage.setItem("midiDeviceId", connectedId || inputId);
       localStorage.setItem("midiOutputDeviceId", outputId);
 
       const inputName = d.midiSelect?.options?.[d.midiSelect.selectedIndex]?.textContent;
@@ -183,6 +188,26 @@ export function createMidiFeature({
     }
   }
 
+  // A profile started its own device; mirror it in the UI and for the next auto-connect.
+  function applySwitchedDevice({ device_id: inputId, output_device_ids: outputIds }) {
+    const outputId = (outputIds || [])[0] || "";
+    localStorage.setItem("midiDeviceId", inputId);
+    localStorage.setItem("midiOutputDeviceId", outputId);
+    if (d.midiSelect) {
+      d.midiSelect.value = inputId;
+    }
+    if (d.midiOutputSelect && outputId) {
+      d.midiOutputSelect.value = outputId;
+    }
+    const inputName = d.midiSelect?.options?.[d.midiSelect.selectedIndex]?.textContent;
+    const outputName = outputId
+      ? d.midiOutputSelect?.options?.[d.midiOutputSelect.selectedIndex]?.textContent
+      : null;
+    if (typeof showMain === "function") {
+      showMain(inputName || inputId, outputName);
+    }
+  }
+
   async function disconnect() {
     stopSessionRefresh();
     stopAutoRefresh();
@@ -199,7 +224,29 @@ export function createMidiFeature({
     }
   }
 
+  async function startUnlearnBinding() {
+    try {
+      if (learnTimer) {
+        clearInterval(learnTimer);
+        learnTimer = null;
+      }
+      await invoke("start_unlearn");
+      unlearnActive = true;
+      openLearnPanel("Move the control whose binding you want to remove.");
+    } catch (error) {
+      unlearnActive = false;
+      closeLearnPanel();
+    }
+  }
+
+  // Called when the backend reports the removed binding.
+  function finishUnlearn() {
+    unlearnActive = false;
+    closeLearnPanel();
+  }
+
   async function startLearnBinding() {
+    unlearnActive = false;
     try {
       await invoke("start_midi_learn");
       openLearnPanel("Move a control on your MIDI device to create a binding.");
@@ -253,14 +300,18 @@ export function createMidiFeature({
     const inputs = Array.isArray(deviceData?.inputs) ? deviceData.inputs : [];
     const outputs = Array.isArray(deviceData?.outputs) ? deviceData.outputs : [];
 
-    let inputMatch = inputs.find((device) => device.id === savedInputId);
-    let outputMatch = savedOutputId ? outputs.find((device) => device.id === savedOutputId) : null;
+    // Older releases saved index-based ids; match those by `legacy_id` and upgrade them.
+    const findDevice = (list, id) =>
+      list.find((device) => device.id === id) || list.find((device) => device.legacy_id === id);
+
+    let inputMatch = findDevice(inputs, savedInputId);
+    let outputMatch = savedOutputId ? findDevice(outputs, savedOutputId) : null;
 
     if (!inputMatch) {
       const refreshed = await refreshMidiDevices();
-      inputMatch = refreshed.inputs.find((device) => device.id === savedInputId);
+      inputMatch = findDevice(refreshed.inputs, savedInputId);
       if (savedOutputId) {
-        outputMatch = refreshed.outputs.find((device) => device.id === savedOutputId);
+        outputMatch = findDevice(refreshed.outputs, savedOutputId);
       }
     }
 
@@ -286,14 +337,17 @@ export function createMidiFeature({
     }
 
     if (d.midiSelect) {
-      d.midiSelect.value = savedInputId;
+      d.midiSelect.value = inputMatch.id;
     }
     if (d.midiOutputSelect) {
-      d.midiOutputSelect.value = savedOutputId;
+      d.midiOutputSelect.value = outputMatch.id;
     }
 
     try {
-      await invoke("start_midi_device", { inputDeviceId: savedInputId, outputDeviceId: savedOutputId });
+      // The saved (possibly legacy) input id is passed so the backend can migrate bindings.
+      const connectedId = await invoke("start_midi_device", { inputDeviceId: savedInputId, outputDeviceId: outputMatch.id });
+      localStorage.setItem("midiDeviceId", connectedId || inputMatch.id);
+      localStorage.setItem("midiOutputDeviceId", outputMatch.id);
       if (typeof showMain === "function") {
         showMain(inputMatch.name, outputMatch ? outputMatch.name : "Unknown");
       }
@@ -351,6 +405,11 @@ export function createMidiFeature({
         startLearnBinding();
       });
     }
+    if (d.bindingUnlearnFooterButton) {
+      d.bindingUnlearnFooterButton.addEventListener("click", () => {
+        startUnlearnBinding();
+      });
+    }
   }
 
   return {
@@ -361,10 +420,13 @@ export function createMidiFeature({
     startSessionRefresh: () => startSessionRefresh(refreshSessions || (async () => { }), d.mainScreen),
     stopSessionRefresh,
     startLearnBinding,
+    startUnlearnBinding,
+    finishUnlearn,
     openLearnPanel,
     closeLearnPanel,
     cancelLearnPanel,
     connectSelected,
     disconnect,
+    applySwitchedDevice,
   };
 }
@@ -6,6 +6,7 @@ export function createOsdFeature({
   resolveOsdTarget,
   createTargetIcon,
   resolveTargetKey,
+  reportContentHeight,
 }) {
   const osd = osdElement || null;
   const getSettings = (typeof getOsdSettings === "function") ? getOsdSettings : (() => ({ enabled: true }));
@@ -14,15 +15,51 @@ export function createOsdFeature({
   const keyForTarget = (typeof resolveTargetKey === "function") ? resolveTargetKey : (() => null);
 
   const activeOsdCards = new Map();
+  let heightReportPending = false;
+
+  // The backend sizes and anchors the OSD window around the visible cards, so
+  // center/bottom anchors line up with them rather than with empty space.
+  function scheduleHeightReport() {
+    if (!osd || !isOsdWindow || typeof reportContentHeight !== "function") return;
+    if (heightReportPending) return;
+    heightReportPending = true;
+    requestAnimationFrame(() => {
+      heightReportPending = false;
+      const last = osd.lastElementChild;
+      if (!last) return;
+      const style = getComputedStyle(osd);
+      const inset = (parseFloat(style.paddingBottom) || 0) + (parseFloat(style.bottom) || 0);
+      reportContentHeight(Math.ceil(osd.offsetTop + last.offsetTop + last.offsetHeight + inset));
+    });
+  }
 
   function getOsdKey(target) {
     const key = keyForTarget(target);
     if (key) return key;
     if (target === "Master" || target?.Master !== undefined) return "::master::";
     if (target === "Focus" || target?.Focus !== undefined) return "::focus::";
+    if (target === "SystemSounds" || target?.SystemSounds !== undefined) return "::system_sounds::";
+    if (target === "DefaultMicrophone" || target?.DefaultMicrophone !== undefined) return "::default_microphone::";
+    if (target === "DefaultSpeakers" || target?.DefaultSpeakers !== undefined) return "::default_speakers::";
     return "::unknown::";
   }
 
+  function withAlias(display, alias) {
+    if (!display || !alias) return display;
+    return { ...display, label: alias };
+  }
+
+  // The backend resolves names/icons from its own (fresher) session and device
+  // lists; local lookups still supply built-in icons such as Master's.
+  function withResolved(display, resolved) {
+    if (!resolved?.display_name) return display;
+    return {
+      ...(display || {}),
+      label: resolved.display_name,
+      icon_data: resolved.icon_data ?? display?.icon_data ?? null,
+    };
+  }
+
   function createOsdCard(_display) {
     const card = document.createElement("div");
     card.className = "osd-card";
@@ -69,13 +106,14 @@ export function createOsdFeature({
 
     setTimeout(() => {
       item.element.remove();
+      scheduleHeightReport();
     }, 250);
   }
 
-  function showVolumeOsd(target, volume, focusSession) {
+  function showVolumeOsd(target, volume, focusSession, alias, companionMuted, resolved) {
     if (!osd) return;
 
-    const display = resolveDisplay(target, focusSession);
+    const display = withAlias(withResolved(resolveDisplay(target, focusSession), resolved), alias);
     if (!display) return;
 
     const key = getOsdKey(target);
@@ -94,6 +132,7 @@ export function createOsdFeature({
       };
       osd.appendChild(refs.card);
       activeOsdCards.set(key, item);
+      scheduleHeightReport();
       refs.card.offsetHeight;
       refs.card.classList.add("visible");
     }
@@ -112,6 +151,10 @@ export function createOsdFeature({
     const percent = Math.round(clampedVolume * 100);
     refs.fillDiv.style.width = `${percent}%`;
     refs.valueSpan.textContent = `${percent}%`;
+    if (companionMuted) {
+      refs.fillDiv.style.backgroundColor = "#ff4444";
+      refs.valueSpan.textContent = `\ud83d\udd07 ${percent}%`;
+    }
 
     if (!osdDebugAlways) {
       item.timer = setTimeout(() => {
@@ -120,10 +163,10 @@ export function createOsdFeature({
     }
   }
 
-  function showMuteOsd(target, muted, focusSession) {
+  function showMuteOsd(target, muted, focusSession, alias, resolved) {
     if (!osd) return;
 
-    const display = resolveDisplay(target, focusSession);
+    const display = withAlias(withResolved(resolveDisplay(target, focusSession), resolved), alias);
     if (!display) return;
 
     const key = getOsdKey(target);
@@ -142,6 +185,7 @@ export function createOsdFeature({
       };
       osd.appendChild(refs.card);
       activeOsdCards.set(key, item);
+      scheduleHeightReport();
       refs.card.offsetHeight;
       refs.card.classList.add("visible");
     }
@@ -163,6 +207,96 @@ export function createOsdFeature({
     }
   }
 
+  function showDefaultDeviceOsd(label, iconData) {
+    if (!osd) return;
+
+    const key = "::default-device::";
+    let item = activeOsdCards.get(key);
+    let refs;
+
+    if (item) {
+      if (item.timer) clearTimeout(item.timer);
+      refs = item.refs;
+    } else {
+      refs = createOsdCard();
+      item = {
+        element: refs.card,
+        refs,
+        timer: null,
+      };
+      osd.appendChild(refs.card);
+      activeOsdCards.set(key, item);
+      scheduleHeightReport();
+      refs.card.offsetHeight;
+      refs.card.classList.add("visible");
+    }
+
+    refs.labelSpan.textContent = label || "Default device";
+    refs.iconDiv.innerHTML = "";
+    refs.iconDiv.appendChild(iconFor({ label: label || "", icon_data: iconData || null }));
+
+    refs.fillDiv.style.width = "100%";
+    refs.fillDiv.style.backgroundColor = "";
+    refs.valueSpan.textContent = "Default";
+    refs.valueSpan.style.fontSize = "";
+
+    if (!osdDebugAlways) {
+      item.timer = setTimeout(() => {
+        removeOsdCard(key);
+      }, 1500);
+    }
+  }
+
+  // Meter windows keep one card per binding on screen and never time out.
+  function showMeters(entries) {
+    if (!osd || !Array.isArray(entries)) return;
+
+    const shown = new Set();
+    for (const entry of entries) {
+      const display = withAlias(withResolved(resolveDisplay(entry.target, null), entry), entry.alias);
+      if (!display) continue;
+
+      const key = `::meter::${entry.binding_id}`;
+      shown.add(key);
+      let item = activeOsdCards.get(key);
+      if (!item) {
+        const refs = createOsdCard(display);
+        item = {
+          element: refs.card,
+          refs,
+          timer: null,
+          iconKey: null,
+        };
+        osd.appendChild(refs.card);
+        activeOsdCards.set(key, item);
+        scheduleHeightReport();
+        refs.card.offsetHeight;
+        refs.card.classList.add("visible");
+      }
+
+      const refs = item.refs;
+      refs.labelSpan.textContent = display.label;
+      // Rebuilding the icon on every level change makes it flicker.
+      const iconKey = `${display.label}|${display.icon_data || ""}`;
+      if (item.iconKey !== iconKey) {
+        item.iconKey = iconKey;
+        refs.iconDiv.innerHTML = "";
+        refs.iconDiv.appendChild(iconFor({ label: display.label, icon_data: display.icon_data }));
+      }
+
+      const percent = Math.round(Math.min(1, Math.max(0, Number(entry.volume) || 0)) * 100);
+      refs.fillDiv.style.width = `${percent}%`;
+      refs.fillDiv.style.backgroundColor = entry.muted ? "#ff4444" : "";
+      refs.valueSpan.textContent = entry.muted ? `\ud83d\udd07 ${percent}%` : `${percent}%`;
+    }
+
+    for (const key of [...activeOsdCards.keys()]) {
+      if (key.startsWith("::meter::") && !shown.has(key)) {
+        removeOsdCard(key);
+      }
+    }
+  }
+
   function hideVolumeOsd() {
     for (const key of activeOsdCards.keys()) {
       removeOsdCard(key);
@@ -186,15 +320,40 @@ export function createOsdFeature({
     }
 
     if (payload.action === "toggle_mute") {
-      showMuteOsd(payload.target, payload.muted, payload.focus_session);
+      showMuteOsd(payload.target, payload.muted, payload.focus_session, payload.alias, payload);
+    } else if (payload.action === "default_device") {
+      showDefaultDeviceOsd(payload.display_name, payload.icon_data);
+    } else {
+      showVolumeOsd(
+        payload.target,
+        payload.volume,
+        payload.focus_session,
+        payload.alias,
+        payload.companion_muted,
+        payload,
+      );
+    }
+  }
+
+  function applyAppearance(appearance) {
+    const root = document.documentElement;
+    const opacity = Number(appearance?.opacity);
+    if (Number.isFinite(opacity)) {
+      root.style.setProperty("--osd-opacity", String(Math.min(1, Math.max(0, opacity))));
+    }
+    if (appearance?.accent_color) {
+      root.style.setProperty("--osd-accent", appearance.accent_color);
     } else {
-      showVolumeOsd(payload.target, payload.volume, payload.focus_session);
+      root.style.removeProperty("--osd-accent");
     }
   }
 
   return {
+    applyAppearance,
     showVolumeOsd,
     showMuteOsd,
+    showDefaultDeviceOsd,
+    showMeters,
     hideVolumeOsd,
     handleOsdUpdate,
   };
@@ -106,7 +106,7 @@ export function createPluginsTabs({ invoke, getPluginHost, reloadPlugins }) {
     return installedCachePromise;
   }
 
-  function renderInstalledPlugins(listEl, setStatus, plugins, refreshFn) {
+  function renderInstalledPlugins(listEl, setStatus, plugins, refreshFn, updates) {
     if (!listEl) return;
     listEl.innerHTML = "";
 
@@ -123,6 +123,8 @@ export function createPluginsTabs({ invoke, getPluginHost, reloadPlugins }) {
       const version = String(p.version || "");
       const enabled = p.enabled !== false;
       const bundled = Boolean(p.bundled);
+      const update = updates?.get(pluginId);
+      const updateVersion = update?.status === "update_available" ? String(update.store?.latest?.version || "") : "";
       const iconRel = p.icon ? String(p.icon) : "";
       const iconKey = iconRel ? `${pluginId}:${iconRel}` : "";
       const host = getHost();
@@ -142,6 +144,7 @@ export function createPluginsTabs({ invoke, getPluginHost, reloadPlugins }) {
           </div>
         </div>
         <div class="plugins-manager-row-actions">
+          ${updateVersion ? `<button type="button" class="connection-button" data-role="update" title="Update to v${updateVersion}">Update</button>` : ""}
           ${bundled ? "" : "<button type=\"button\" class=\"connection-status plugins-manager-uninstall\" data-role=\"uninstall\" aria-label=\"Uninstall\" title=\"Uninstall\">×</button>"}
           <label class="plugins-toggle" title="Enable plugin">
             <input type="checkbox" data-role="enabled" ${enabled ? "checked" : ""} />
@@ -152,6 +155,22 @@ export function createPluginsTabs({ invoke, getPluginHost, reloadPlugins }) {
 
       const enabledInput = row.querySelector('[data-role="enabled"]');
       const uninstallBtn = row.querySelector('[data-role="uninstall"]');
+      const updateBtn = row.querySelector('[data-role="update"]');
+
+      if (updateBtn) {
+        updateBtn.addEventListener("click", async () => {
+          updateBtn.disabled = true;
+          try {
+            await invoke("install_store_plugin", { pluginId, plugin_id: pluginId });
+            setStatus(`Updated ${name} to v${updateVersion}.`, "success");
+          } catch (e) {
+            console.error("Plugin update failed", e);
+            setStatus("Update failed. The package may be untrusted or unavailable.", "error");
+          }
+          await reload();
+          await (typeof refreshFn === "function" ? refreshFn() : Promise.resolve());
+        });
+      }
 
       if (enabledInput) {
         enabledInput.addEventListener("change", async () => {
@@ -246,6 +265,17 @@ export function createPluginsTabs({ invoke, getPluginHost, reloadPlugins }) {
       installedCache = plugins;
       warmInstalledIcons(plugins).catch(() => { });
       renderInstalledPlugins(listEl, setStatus, plugins, refresh);
+
+      // The catalog is remote; render first and add update buttons once it answers.
+      try {
+        const updates = await invoke("check_plugin_updates");
+        const byId = new Map((Array.isArray(updates) ? updates : []).map((u) => [String(u.id || ""), u]));
+        if ([...byId.values()].some((u) => u.status === "update_available")) {
+          renderInstalledPlugins(listEl, setStatus, plugins, refresh, byId);
+        }
+      } catch {
+        // Offline or store unavailable; nothing to offer.
+      }
     };
 
     if (installBtn) {
@@ -255,12 +285,16 @@ export function createPluginsTabs({ invoke, getPluginHost, reloadPlugins }) {
         try {
           const buf = await file.arrayBuffer();
           const b64 = arrayBufferToBase64(buf);
-          await invoke("install_plugin_package", {
+          const info = await invoke("install_plugin_package", {
             filename: file.name,
             bytesBase64: b64,
             bytes_base64: b64,
           });
-          setStatus(`Installed ${file.name}.`, "success");
+          if (info?.signed) {
+            setStatus(`Installed ${file.name} (signed by ${info.signature_key_id}).`, "success");
+          } else {
+            setStatus(`Installed ${file.name}. This plugin is unsigned; only keep it if you trust where it came from.`, "error");
+          }
         } catch (e) {
           console.error("Failed to install plugin package", e);
           setStatus("Failed to install plugin. Check the package file and try again.", "error");
@@ -294,6 +328,12 @@ export function createPluginsTabs({ invoke, getPluginHost, reloadPlugins }) {
         <div class="plugins-store-toolbar">
           <input class="plugins-store-search" type="text" placeholder="Search plugins" data-role="q" />
         </div>
+        <details class="plugins-store-sources">
+          <summary>Catalog sources</summary>
+          <textarea rows="3" spellcheck="false" placeholder="https://plugins.example.com/catalog.json" data-role="sources"></textarea>
+          <div class="plugins-store-sources-hint">One URL per line, fetched alongside the official store. Only packages signed with a trusted key can be installed.</div>
+          <button type="button" class="connection-button" data-role="save-sources">Save</button>
+        </details>
         <div class="plugins-store-status hidden" data-role="status"></div>
         <div class="plugins-store-list" data-role="list"></div>
       </div>
@@ -302,6 +342,8 @@ export function createPluginsTabs({ invoke, getPluginHost, reloadPlugins }) {
     const qEl = container.querySelector('[data-role="q"]');
     const statusEl = container.querySelector('[data-role="status"]');
     const listEl = container.querySelector('[data-role="list"]');
+    const sourcesEl = container.querySelector('[data-role="sources"]');
+    const saveSourcesBtn = container.querySelector('[data-role="save-sources"]');
 
     const setStatus = (text, kind = "") => {
       if (!statusEl) return;
@@ -341,6 +383,15 @@ export function createPluginsTabs({ invoke, getPluginHost, reloadPlugins }) {
         const author = p.author ? String(p.author) : "";
         const desc = p.description ? String(p.description) : "";
         const latestV = String(p.latest?.version || "");
+        let source = "";
+        if (!p.official && p.source) {
+          try {
+            source = new URL(p.source).host;
+          } catch {
+            source = String(p.source);
+          }
+        }
+        const conflicts = Array.isArray(p.conflicts) ? p.conflicts : [];
 
         const inst = installedMap.get(id);
         const installedV = inst ? String(inst.version || "") : "";
@@ -352,7 +403,8 @@ export function createPluginsTabs({ invoke, getPluginHost, reloadPlugins }) {
           <div class="plugins-store-row-left">
             <div class="plugins-store-row-text">
               <div class="plugins-store-row-name">${name}</div>
-              <div class="plugins-store-row-meta">${id}${author ? ` - ${author}` : ""}${latestV ? ` - v${latestV}` : ""}</div>
+              <div class="plugins-store-row-meta">${id}${author ? ` - ${author}` : ""}${latestV ? ` - v${latestV}` : ""}${source ? ` - from ${source}` : ""}</div>
+              ${conflicts.length ? `<div class="plugins-store-row-meta" title="${conflicts.join("\n")}">Also listed by ${conflicts.length} other catalog${conflicts.length === 1 ? "" : "s"}</div>` : ""}
               ${desc ? `<div class=\"plugins-store-row-desc\">${desc}</div>` : ""}
             </div>
           </div>
@@ -407,6 +459,10 @@ export function createPluginsTabs({ invoke, getPluginHost, reloadPlugins }) {
       }
       try {
         catalog = await invoke("fetch_store_catalog");
+        const failed = Array.isArray(catalog?.failed_sources) ? catalog.failed_sources : [];
+        if (failed.length) {
+          setStatus(`Could not load: ${failed.join(", ")}`, "error");
+        }
       } catch (e) {
         console.error("Failed to fetch store catalog", e);
         catalog = { plugins: [] };
@@ -418,7 +474,34 @@ export function createPluginsTabs({ invoke, getPluginHost, reloadPlugins }) {
       qEl.addEventListener("input", () => render());
     }
 
+    async function loadSources() {
+      if (!sourcesEl) return;
+      try {
+        const settings = await invoke("get_app_settings");
+        const urls = Array.isArray(settings?.store_catalog_urls) ? settings.store_catalog_urls : [];
+        sourcesEl.value = urls.join("\n");
+      } catch {
+        sourcesEl.value = "";
+      }
+    }
+
+    if (saveSourcesBtn && sourcesEl) {
+      saveSourcesBtn.addEventListener("click", async () => {
+        const urls = sourcesEl.value.split(/\r?\n/).map((u) => u.trim()).filter(Boolean);
+        try {
+          const saved = await invoke("set_store_catalog_urls", { urls });
+          sourcesEl.value = (Array.isArray(saved) ? saved : urls).join("\n");
+        } catch (e) {
+          setStatus(String(e), "error");
+          return;
+        }
+        await loadCatalog();
+        render();
+      });
+    }
+
     Promise.resolve()
+      .then(loadSources)
       .then(loadCatalog)
       .then(loadInstalled)
       .then(render)
@@ -1,3 +1,33 @@
+let profileFileInput = null;
+function pickProfileFile() {
+  return new Promise((resolve) => {
+    if (!profileFileInput) {
+      profileFileInput = document.createElement("input");
+      profileFileInput.type = "file";
+      profileFileInput.accept = ".json";
+      profileFileInput.style.display = "none";
+      document.body.appendChild(profileFileInput);
+    }
+    profileFileInput.value = "";
+    profileFileInput.onchange = () => {
+      const f = profileFileInput.files && profileFileInput.files[0];
+      resolve(f || null);
+    };
+    profileFileInput.click();
+  });
+}
+
+function downloadText(fileName, text) {
+  const url = URL.createObjectURL(new Blob([text], { type: "application/json" }));
+  const link = document.createElement("a");
+  link.href = url;
+  link.download = fileName;
+  document.body.appendChild(link);
+  link.click();
+  link.remove();
+  URL.revokeObjectURL(url);
+}
+
 export function createProfilesFeature({
   invoke,
   dom,
@@ -15,6 +45,7 @@ export function createProfilesFeature({
   getOsdSettings,
   setOsdSettings,
   applyOsdSettings,
+  showAlert,
 }) {
   if (typeof invoke !== "function") {
     throw new Error("createProfilesFeature: invoke is required");
@@ -26,8 +57,14 @@ export function createProfilesFeature({
     anchor: "top-right",
   };
 
+  const alertUser = (typeof showAlert === "function") ? showAlert : ((message) => window.alert(message));
+
   let pendingProfileDeleteName = null;
   let saveProfileTimer = null;
+  let targetAliases = {};
+  let autoSwitchProcesses = [];
+  let midiInputDeviceId = null;
+  let midiOutputDeviceIds = [];
 
   function setProfileSelection(name) {
     if (!d.profileCurrent) return;
@@ -59,6 +96,12 @@ export function createProfilesFeature({
     if (typeof setProfilePluginSettings === "function") {
       setProfilePluginSettings(pps);
     }
+    targetAliases = (profile.target_aliases && typeof profile.target_aliases === "object")
+      ? profile.target_aliases
+      : {};
+    autoSwitchProcesses = Array.isArray(profile.auto_switch_processes) ? profile.auto_switch_processes : [];
+    midiInputDeviceId = profile.midi_input_device_id || null;
+    midiOutputDeviceIds = Array.isArray(profile.midi_output_device_ids) ? profile.midi_output_device_ids : [];
 
     const nextBindings = (profile.bindings || []).map((binding, index) => ({
       ...binding,
@@ -83,7 +126,14 @@ export function createProfilesFeature({
         monitorIndex: Number(profile.osd_settings.monitor_index ?? 0),
         monitorName: profile.osd_settings.monitor_name || null,
         monitorId: profile.osd_settings.monitor_id || null,
+        monitorEdid: profile.osd_settings.monitor_edid || null,
+        monitorGeometry: profile.osd_settings.monitor_geometry || null,
         anchor: profile.osd_settings.anchor || "top-right",
+        instances: Array.isArray(profile.osd_settings.instances) ? profile.osd_settings.instances : [],
+        width: Number(profile.osd_settings.width ?? 320),
+        height: Number(profile.osd_settings.height ?? 800),
+        opacity: Number(profile.osd_settings.opacity ?? 0.94),
+        accentColor: profile.osd_settings.accent_color || null,
       };
       if (typeof setOsdSettings === "function") {
         setOsdSettings(nextOsd);
@@ -120,6 +170,55 @@ export function createProfilesFeature({
     await refreshProfiles((typeof getActiveProfileName === "function") ? (getActiveProfileName() || "Default") : "Default");
   }
 
+  async function editAutoSwitch(profile) {
+    const current = Array.isArray(profile.auto_switch_processes) ? profile.auto_switch_processes : [];
+    const input = window.prompt(
+      `Load "${profile.name}" when one of these apps is focused (comma-separated, e.g. obs64.exe)`,
+      current.join(", ")
+    );
+    if (input === null) return;
+    const processes = input.split(",").map((value) => value.trim()).filter(Boolean);
+    const saved = await invoke("set_profile_auto_switch", { name: profile.name, processes });
+    const active = (typeof getActiveProfileName === "function") ? (getActiveProfileName() || "") : "";
+    if (profile.name === active) {
+      autoSwitchProcesses = Array.isArray(saved) ? saved : processes;
+    }
+  }
+
+  async function duplicateProfileByName(name) {
+    const input = window.prompt(`Name for the copy of "${name}"`, `${name} copy`);
+    const newName = input ? input.trim() : "";
+    if (!newName) return;
+    try {
+      const copy = await invoke("duplicate_profile", { sourceName: name, newName });
+      await loadProfileByName(copy.name);
+      await refreshProfiles(copy.name);
+      closeProfileDropdown();
+    } catch (error) {
+      alertUser(`Could not duplicate profile: ${error}`, "Duplicate failed");
+    }
+  }
+
+  async function exportProfileByName(name) {
+    const json = await invoke("export_profile", { name });
+    const safeName = String(name).replace(/[\\/:*?"<>|]+/g, "_");
+    downloadText(`${safeName}.midimaster-profile.json`, json);
+  }
+
+  async function importProfileFromFile() {
+    const file = await pickProfileFile();
+    if (!file) return;
+    try {
+      const json = await file.text();
+      const imported = await invoke("import_profile", { json, renameIfConflict: true });
+      await loadProfileByName(imported.name);
+      await refreshProfiles(imported.name);
+      closeProfileDropdown();
+    } catch (error) {
+      alertUser(`Could not import profile: ${error}`, "Import failed");
+    }
+  }
+
   async function refreshProfiles(preferredName = "") {
     let profiles = [];
     try {
@@ -195,6 +294,18 @@ export function createProfilesFeature({
     createItem.appendChild(createButton);
     d.profileList.appendChild(createItem);
 
+    const importItem = document.createElement("div");
+    importItem.className = "dropdown-item create";
+    const importButton = document.createElement("button");
+    importButton.type = "button";
+    importButton.textContent = "Import profile…";
+    importButton.addEventListener("click", (event) => {
+      event.stopPropagation();
+      importProfileFromFile();
+    });
+    importItem.appendChild(importButton);
+    d.profileList.appendChild(importItem);
+
     profiles.forEach((profile) => {
       const item = document.createElement("div");
       item.className = "dropdown-item";
@@ -252,6 +363,48 @@ export function createProfilesFeature({
         item.appendChild(cancelButton);
         item.appendChild(confirmButton);
       } else {
+        const rules = Array.isArray(profile.auto_switch_processes) ? profile.auto_switch_processes : [];
+        const autoButton = document.createElement("button");
+        autoButton.type = "button";
+        autoButton.className = "auto-switch";
+        autoButton.classList.toggle("active", rules.length > 0);
+        autoButton.textContent = "⇄";
+        autoButton.title = rules.length > 0
+          ? `Auto-loads for: ${rules.join(", ")}`
+          : "Auto-load when an app is focused";
+        autoButton.addEventListener("click", async (event) => {
+          event.stopPropagation();
+          await editAutoSwitch(profile);
+          await refreshProfiles(currentSelection || "Default");
+        });
+
+        const duplicateButton = document.createElement("button");
+        duplicateButton.type = "button";
+        duplicateButton.className = "duplicate";
+        duplicateButton.textContent = "⧉";
+        duplicateButton.title = "Duplicate";
+        duplicateButton.addEventListener("click", (event) => {
+          event.stopPropagation();
+          duplicateProfileByName(profile.name);
+        });
+
+        const exportButton = document.createElement("button");
+        exportButton.type = "button";
+        exportButton.className = "export";
+        exportButton.textContent = "⤓";
+        exportButton.title = "Export to file";
+        exportButton.addEventListener("click", async (event) => {
+          event.stopPropagation();
+          try {
+            await exportProfileByName(profile.name);
+          } catch (error) {
+            alertUser(`Could not export profile: ${error}`, "Export failed");
+          }
+        });
+
+        item.appendChild(autoButton);
+        item.appendChild(duplicateButton);
+        item.appendChild(exportButton);
         item.appendChild(deleteButton);
       }
 
@@ -299,14 +452,50 @@ export function createProfilesFeature({
           osd_settings: {
             enabled: Boolean(osd.enabled),
             monitor_index: Number(osd.monitorIndex ?? 0),
+            monitor_name: osd.monitorName || null,
+            monitor_id: osd.monitorId || null,
+            monitor_edid: osd.monitorEdid || null,
+            monitor_geometry: osd.monitorGeometry || null,
             anchor: osd.anchor || "top-right",
+            instances: Array.isArray(osd.instances) ? osd.instances : [],
+            width: Number(osd.width ?? 320),
+            height: Number(osd.height ?? 800),
+            opacity: Number(osd.opacity ?? 0.94),
+            accent_color: osd.accentColor || null,
           },
           plugin_settings,
+          target_aliases: targetAliases,
+          auto_switch_processes: autoSwitchProcesses,
+          midi_input_device_id: midiInputDeviceId,
+          midi_output_device_ids: midiOutputDeviceIds,
         },
       });
     }, 500);
   }
 
+  // Called after the user connects a device by hand, so the active profile starts it when loaded.
+  async function rememberMidiDevices(inputId, outputIds) {
+    const name = (typeof getActiveProfileName === "function") ? (getActiveProfileName() || "") : "";
+    if (!name || !inputId) return;
+    midiInputDeviceId = inputId;
+    midiOutputDeviceIds = (outputIds || []).filter(Boolean);
+    try {
+      await invoke("set_profile_midi_devices", {
+        name,
+        inputDeviceId: midiInputDeviceId,
+        outputDeviceIds: midiOutputDeviceIds,
+      });
+    } catch (error) {
+      console.error("Failed to remember MIDI devices for profile", error);
+    }
+  }
+
+  async function setTargetAlias(target, alias) {
+    const next = await invoke("set_target_alias", { target, alias: alias || null });
+    targetAliases = (next && typeof next === "object") ? next : {};
+    return targetAliases;
+  }
+
   async function updateProfilePluginSettings(pluginId, nextSettings) {
     if (!pluginId || typeof pluginId !== "string") return;
     const safe = (nextSettings && typeof nextSettings === "object") ? nextSettings : {};
@@ -354,9 +543,14 @@ export function createProfilesFeature({
     refreshProfiles,
     loadProfileByName,
     deleteProfileByName,
+    duplicateProfileByName,
+    exportProfileByName,
+    importProfileFromFile,
     setProfileSelection,
     closeProfileDropdown,
     saveBindingsForProfile,
     updateProfilePluginSettings,
+    setTargetAlias,
+    rememberMidiDevices,
   };
 }
@@ -44,21 +44,62 @@ export function createSettingsFeature({
       d.osdMonitorSelect.value = String(merged.monitorIndex ?? 0);
     }
     updateOsdPositionSelection(merged.anchor);
+    syncOsdAppearanceUI(merged);
     document.body.setAttribute("data-anchor", merged.anchor || "top-right");
 
     try {
       await invoke("update_osd_settings", {
-        enabled: merged.enabled,
-        monitorIndex: merged.monitorIndex,
-        monitorName: merged.monitorName || null,
-        monitorId: merged.monitorId || null,
-        anchor: merged.anchor,
+        placement: {
+          enabled: Boolean(merged.enabled),
+          monitor_index: Number(merged.monitorIndex ?? 0),
+          monitor_name: merged.monitorName || null,
+          monitor_id: merged.monitorId || null,
+          monitor_edid: merged.monitorEdid || null,
+          monitor_geometry: merged.monitorGeometry || null,
+          anchor: merged.anchor || "top-right",
+        },
       });
     } catch (error) {
       console.error("Failed to update OSD settings", error);
     }
   }
 
+  function syncOsdAppearanceUI(settings) {
+    if (d.osdWidthInput) {
+      d.osdWidthInput.value = String(Number(settings.width) || 320);
+    }
+    if (d.osdHeightInput) {
+      d.osdHeightInput.value = String(Number(settings.height) || 800);
+    }
+    if (d.osdOpacityInput) {
+      d.osdOpacityInput.value = String(Math.round(Number(settings.opacity ?? 0.94) * 100));
+    }
+    if (d.osdAccentInput) {
+      d.osdAccentInput.value = settings.accentColor || "#5aa9ff";
+    }
+  }
+
+  async function applyOsdAppearance(nextAppearance) {
+    const current = (typeof getOsdSettings === "function") ? (getOsdSettings() || {}) : {};
+    const merged = { ...current, ...(nextAppearance || {}) };
+    if (typeof setOsdSettings === "function") {
+      setOsdSettings(merged);
+    }
+    syncOsdAppearanceUI(merged);
+
+    try {
+      await invoke("update_osd_appearance", {
+        width: Number(merged.width) || 320,
+        height: Number(merged.height) || 800,
+        opacity: Number(merged.opacity ?? 0.94),
+        // An empty string tells the backend to go back to the default colour.
+        accentColor: merged.accentColor || "",
+      });
+    } catch (error) {
+      console.error("Failed to update OSD appearance", error);
+    }
+  }
+
   async function loadOsdSettings() {
     try {
       const settings = await invoke("get_osd_settings");
@@ -68,7 +109,14 @@ export function createSettingsFeature({
           monitorIndex: Number(settings.monitor_index ?? settings.monitorIndex ?? 0),
           monitorName: settings.monitor_name ?? settings.monitorName ?? null,
           monitorId: settings.monitor_id ?? settings.monitorId ?? null,
+          monitorEdid: settings.monitor_edid ?? settings.monitorEdid ?? null,
+          monitorGeometry: settings.monitor_geometry ?? settings.monitorGeometry ?? null,
           anchor: settings.anchor || "top-right",
+          instances: Array.isArray(settings.instances) ? settings.instances : [],
+          width: Number(settings.width ?? 320),
+          height: Number(settings.height ?? 800),
+          opacity: Number(settings.opacity ?? 0.94),
+          accentColor: settings.accent_color ?? settings.accentColor ?? null,
         };
         if (typeof setOsdSettings === "function") {
           setOsdSettings(next);
@@ -106,6 +154,8 @@ export function createSettingsFeature({
         option.value = String(monitor.index ?? index);
         option.dataset.rawName = monitor.name || "";
         option.dataset.stableId = monitor.stable_id || "";
+        option.dataset.edidId = monitor.edid_id || "";
+        option.dataset.geometry = monitor.geometry || "";
         const label = formatMonitorName(monitor.name) || `Monitor ${index + 1}`;
         option.textContent = monitor.is_primary ? `${label} (Main)` : label;
         d.osdMonitorSelect.appendChild(option);
@@ -123,9 +173,19 @@ export function createSettingsFeature({
   function reconcileMonitorSelection() {
     const options = (typeof getMonitorOptions === "function") ? (getMonitorOptions() || []) : [];
     const current = (typeof getOsdSettings === "function") ? (getOsdSettings() || {}) : {};
-    if (!options.length || !current.monitorId) return false;
+    if (!options.length) return false;
 
-    const matchIndex = options.findIndex((m) => m && m.stable_id === current.monitorId);
+    // Same order the backend uses to place the OSD: device id, EDID id (only
+    // when no other connected monitor shares it), geometry.
+    const matchBy = (key, value) => (value ? options.findIndex((m) => m && m[key] === value) : -1);
+    const matchUnique = (key, value) => {
+      if (!value) return -1;
+      const matches = options.filter((m) => m && m[key] === value);
+      return matches.length === 1 ? options.indexOf(matches[0]) : -1;
+    };
+    let matchIndex = matchBy("stable_id", current.monitorId);
+    if (matchIndex === -1) matchIndex = matchUnique("edid_id", current.monitorEdid);
+    if (matchIndex === -1) matchIndex = matchBy("geometry", current.monitorGeometry);
     if (matchIndex !== -1 && matchIndex !== current.monitorIndex) {
       const next = { ...current, monitorIndex: matchIndex };
       if (typeof setOsdSettings === "function") {
@@ -157,20 +217,60 @@ export function createSettingsFeature({
     if (d.exitToTraySelect) {
       d.exitToTraySelect.value = merged.exitToTray ? "enabled" : "disabled";
     }
+    if (d.focusSessionScopeSelect) {
+      d.focusSessionScopeSelect.value = merged.focusPrimarySessionOnly ? "primary" : "all";
+    }
+    if (d.startupGraceSelect) {
+      d.startupGraceSelect.value = String(Number(merged.startupGraceMs) || 0);
+    }
+    if (d.doubleTapSelect) {
+      d.doubleTapSelect.value = String(Number(merged.doubleTapMs) || 300);
+    }
+    if (d.learnWindowSelect) {
+      d.learnWindowSelect.value = String(Number(merged.learnWindowMs) || 150);
+    }
+    if (d.hiddenSessionsInput) {
+      d.hiddenSessionsInput.value = (merged.hiddenSessions || []).join("\n");
+    }
+    if (d.showHiddenSessionsSelect) {
+      d.showHiddenSessionsSelect.value = merged.showHiddenSessions ? "enabled" : "disabled";
+    }
+    if (d.logLevelSelect) {
+      d.logLevelSelect.value = merged.logLevel || "info";
+    }
   }
 
   function persistAppSettings() {
     const s = (typeof getAppSettings === "function") ? (getAppSettings() || {}) : {};
     return invoke("update_app_settings", {
-      startWithWindows: Boolean(s.startWithWindows),
-      startInTray: Boolean(s.startInTray),
-      minimizeToTray: Boolean(s.minimizeToTray),
-      exitToTray: Boolean(s.exitToTray),
+      update: {
+        start_with_windows: Boolean(s.startWithWindows),
+        start_in_tray: Boolean(s.startInTray),
+        minimize_to_tray: Boolean(s.minimizeToTray),
+        exit_to_tray: Boolean(s.exitToTray),
+        focus_primary_session_only: Boolean(s.focusPrimarySessionOnly),
+        startup_grace_ms: Math.max(0, Math.round(Number(s.startupGraceMs) || 0)),
+        double_tap_ms: Math.max(0, Math.round(Number(s.doubleTapMs) || 300)),
+        learn_window_ms: Math.max(0, Math.round(Number(s.learnWindowMs) || 150)),
+      },
     }).catch((error) => {
       console.error("Failed to update app settings", error);
     });
   }
 
+  async function persistSessionFilter() {
+    const s = (typeof getAppSettings === "function") ? (getAppSettings() || {}) : {};
+    try {
+      const saved = await invoke("set_session_filter", {
+        hiddenSessions: Array.isArray(s.hiddenSessions) ? s.hiddenSessions : [],
+        showHidden: Boolean(s.showHiddenSessions),
+      });
+      syncAppSettingsUI({ hiddenSessions: Array.isArray(saved) ? saved : [] });
+    } catch (error) {
+      console.error("Failed to update session filter", error);
+    }
+  }
+
   async function loadAppSettings() {
     try {
       const settings = await invoke("get_app_settings");
@@ -180,6 +280,15 @@ export function createSettingsFeature({
           startInTray: Boolean(settings.start_in_tray ?? settings.startInTray),
           minimizeToTray: Boolean(settings.minimize_to_tray ?? settings.minimizeToTray),
           exitToTray: Boolean(settings.exit_to_tray ?? settings.exitToTray),
+          focusPrimarySessionOnly: Boolean(
+            settings.focus_primary_session_only ?? settings.focusPrimarySessionOnly
+          ),
+          startupGraceMs: Number(settings.startup_grace_ms ?? settings.startupGraceMs) || 0,
+          doubleTapMs: Number(settings.double_tap_ms ?? settings.doubleTapMs) || 300,
+          learnWindowMs: Number(settings.learn_window_ms ?? settings.learnWindowMs) || 150,
+          hiddenSessions: Array.isArray(settings.hidden_sessions) ? settings.hidden_sessions : [],
+          showHiddenSessions: Boolean(settings.show_hidden_sessions ?? settings.showHiddenSessions),
+          logLevel: settings.log_level ?? settings.logLevel ?? "info",
         };
         if (typeof setAppSettings === "function") {
           setAppSettings(next);
@@ -226,7 +335,39 @@ export function createSettingsFeature({
         const selectedOption = d.osdMonitorSelect.options[d.osdMonitorSelect.selectedIndex];
         const monitorName = selectedOption?.dataset?.rawName || null;
         const monitorId = selectedOption?.dataset?.stableId || null;
-        applyOsdSettings({ monitorIndex: nextIndex, monitorName, monitorId });
+        const monitorEdid = selectedOption?.dataset?.edidId || null;
+        const monitorGeometry = selectedOption?.dataset?.geometry || null;
+        applyOsdSettings({ monitorIndex: nextIndex, monitorName, monitorId, monitorEdid, monitorGeometry });
+      });
+    }
+
+    if (d.osdWidthInput) {
+      d.osdWidthInput.addEventListener("change", () => {
+        applyOsdAppearance({ width: Number(d.osdWidthInput.value) });
+      });
+    }
+
+    if (d.osdHeightInput) {
+      d.osdHeightInput.addEventListener("change", () => {
+        applyOsdAppearance({ height: Number(d.osdHeightInput.value) });
+      });
+    }
+
+    if (d.osdOpacityInput) {
+      d.osdOpacityInput.addEventListener("change", () => {
+        applyOsdAppearance({ opacity: Number(d.osdOpacityInput.value) / 100 });
+      });
+    }
+
+    if (d.osdAccentInput) {
+      d.osdAccentInput.addEventListener("change", () => {
+        applyOsdAppearance({ accentColor: d.osdAccentInput.value });
+      });
+    }
+
+    if (d.osdAccentReset) {
+      d.osdAccentReset.addEventListener("click", () => {
+        applyOsdAppearance({ accentColor: null });
       });
     }
 
@@ -263,6 +404,55 @@ export function createSettingsFeature({
         persistAppSettings();
       });
     }
+    if (d.focusSessionScopeSelect) {
+      d.focusSessionScopeSelect.addEventListener("change", () => {
+        syncAppSettingsUI({ focusPrimarySessionOnly: d.focusSessionScopeSelect.value === "primary" });
+        persistAppSettings();
+      });
+    }
+    if (d.startupGraceSelect) {
+      d.startupGraceSelect.addEventListener("change", () => {
+        syncAppSettingsUI({ startupGraceMs: Number(d.startupGraceSelect.value) || 0 });
+        persistAppSettings();
+      });
+    }
+    if (d.doubleTapSelect) {
+      d.doubleTapSelect.addEventListener("change", () => {
+        syncAppSettingsUI({ doubleTapMs: Number(d.doubleTapSelect.value) || 300 });
+        persistAppSettings();
+      });
+    }
+    if (d.learnWindowSelect) {
+      d.learnWindowSelect.addEventListener("change", () => {
+        syncAppSettingsUI({ learnWindowMs: Number(d.learnWindowSelect.value) || 150 });
+        persistAppSettings();
+      });
+    }
+    if (d.hiddenSessionsInput) {
+      d.hiddenSessionsInput.addEventListener("change", () => {
+        const names = d.hiddenSessionsInput.value
+          .split(/\r?\n/)
+          .map((name) => name.trim())
+          .filter(Boolean);
+        syncAppSettingsUI({ hiddenSessions: names });
+        persistSessionFilter();
+      });
+    }
+    if (d.showHiddenSessionsSelect) {
+      d.showHiddenSessionsSelect.addEventListener("change", () => {
+        syncAppSettingsUI({ showHiddenSessions: d.showHiddenSessionsSelect.value === "enabled" });
+        persistSessionFilter();
+      });
+    }
+    if (d.logLevelSelect) {
+      d.logLevelSelect.addEventListener("change", () => {
+        const level = d.logLevelSelect.value || "info";
+        syncAppSettingsUI({ logLevel: level });
+        invoke("set_log_level", { level }).catch((error) => {
+          console.error("Failed to update log level", error);
+        });
+      });
+    }
   }
 
   return {
@@ -1,3 +1,5 @@
+import { COMMUNICATIONS_DEVICE_ID, findSession } from "../../core/target_core.js";
+
 export function createTargetsFeature({
   dom,
   masterIconData,
@@ -143,13 +145,14 @@ export function createTargetsFeature({
 
     const integration = currentTarget?.Integration || currentTarget?.integration;
     const selectedAppName = currentTarget?.Application?.name || currentTarget?.application?.name;
+    const selectedAppPath = currentTarget?.Application?.path || currentTarget?.application?.path || null;
     const sessionContainer = currentTarget?.Session || currentTarget?.session;
     const selectedSessionId = (sessionContainer && typeof sessionContainer === "object")
       ? (sessionContainer.session_id ?? sessionContainer.sessionId)
       : (sessionContainer != null ? sessionContainer : null);
     const selectedSessionKey = (selectedSessionId != null)
       ? (() => {
-        const s = sessions.find((x) => String(x.id) === String(selectedSessionId));
+        const s = findSession(sessions, selectedSessionId, sessionContainer?.path);
         return s ? normalizeKey(s) : null;
       })()
       : null;
@@ -163,14 +166,17 @@ export function createTargetsFeature({
           : (currentTarget?.Device || currentTarget?.device) ? "device"
             : (currentTarget === "Master" || currentTarget?.Master != null) ? "master"
               : (currentTarget === "Focus" || currentTarget?.Focus != null) ? "focus"
-                : "placeholder"
+                : (currentTarget === "SystemSounds" || currentTarget?.SystemSounds != null) ? "system-sounds"
+                  : (currentTarget === "DefaultMicrophone" || currentTarget?.DefaultMicrophone != null) ? "default-microphone"
+                    : (currentTarget === "DefaultSpeakers" || currentTarget?.DefaultSpeakers != null) ? "default-speakers"
+                      : "placeholder"
       );
 
     let selectedValue = "";
     if (selectedKind === "integration-target") selectedValue = targetKey(integration);
     else if (selectedKind === "session") selectedValue = selectedAppName || selectedSessionKey || "";
     else if (selectedKind === "device") selectedValue = selectedDeviceId || "";
-    else if (selectedKind === "master" || selectedKind === "focus") selectedValue = selectedKind;
+    else if (["master", "focus", "system-sounds", "default-microphone", "default-speakers"].includes(selectedKind)) selectedValue = selectedKind;
     else if (selectedKind === "placeholder") selectedValue = "placeholder";
 
     const options = [
@@ -186,6 +192,24 @@ export function createTargetsFeature({
         icon_data: focusIconData,
         kind: "focus",
       },
+      {
+        value: "system-sounds",
+        label: "System Sounds",
+        icon_data: masterIconData,
+        kind: "system-sounds",
+      },
+      {
+        value: "default-speakers",
+        label: "Default Speakers",
+        icon_data: masterIconData,
+        kind: "default-speakers",
+      },
+      {
+        value: "default-microphone",
+        label: "Default Microphone",
+        icon_data: masterIconData,
+        kind: "default-microphone",
+      },
     ];
 
     if (pluginHost) {
@@ -206,26 +230,47 @@ export function createTargetsFeature({
 
     const seen = new Set();
     const sessionsAdded = sessions.filter((session) => !session.is_master && session.id !== "master");
+    // Apps running from more than one install (e.g. two Chrome channels) get an entry per path.
+    const pathsByKey = new Map();
+    sessionsAdded.forEach((session) => {
+      const key = normalizeKey(session);
+      if (!key || !session.process_path) return;
+      if (!pathsByKey.has(key)) pathsByKey.set(key, new Set());
+      pathsByKey.get(key).add(session.process_path.toLowerCase());
+    });
     if (sessionsAdded.length > 0) {
       options.push({ kind: "divider", label: "Applications" });
       sessionsAdded.forEach((session) => {
         const key = normalizeKey(session);
         if (!key) return;
 
-        if (seen.has(key)) {
+        const path = session.process_path || null;
+        const ambiguous = (pathsByKey.get(key)?.size || 0) > 1;
+        const value = ambiguous ? `${key}|${path.toLowerCase()}` : key;
+        if (seen.has(value)) {
           return;
         }
 
         seen.add(key);
+        seen.add(value);
         options.push({
-          value: key,
-          label: session.display_name,
+          value,
+          label: ambiguous ? `${session.display_name} (${path})` : session.display_name,
           icon_data: session.icon_data,
           kind: "session",
+          appName: key,
+          path,
         });
       });
     }
 
+    if (selectedKind === "session" && selectedAppName && selectedAppPath) {
+      const exact = options.find((option) => option.kind === "session"
+        && option.appName === selectedAppName
+        && String(option.path || "").toLowerCase() === selectedAppPath.toLowerCase());
+      if (exact) selectedValue = exact.value;
+    }
+
     if (selectedAppName && !seen.has(selectedAppName)) {
       if (sessionsAdded.length === 0) {
         options.push({ kind: "divider", label: "Applications" });
@@ -235,12 +280,20 @@ export function createTargetsFeature({
         value: selectedAppName,
         label: `${label} (Unavailable)`,
         kind: "session",
+        appName: selectedAppName,
+        path: selectedAppPath,
         ghost: true,
       });
     }
 
     if (playbackDevices.length > 0) {
       options.push({ kind: "divider", label: "Playback Devices" });
+      options.push({
+        value: `playback:${COMMUNICATIONS_DEVICE_ID}`,
+        label: "Communications Device",
+        icon_data: playbackDevices.find((device) => device.is_comms_default)?.icon_data ?? null,
+        kind: "device",
+      });
       playbackDevices.forEach((device) => {
         options.push({
           value: `playback:${device.id}`,
@@ -253,6 +306,12 @@ export function createTargetsFeature({
 
     if (recordingDevices.length > 0) {
       options.push({ kind: "divider", label: "Recording Devices" });
+      options.push({
+        value: `recording:${COMMUNICATIONS_DEVICE_ID}`,
+        label: "Communications Device",
+        icon_data: recordingDevices.find((device) => device.is_comms_default)?.icon_data ?? null,
+        kind: "device",
+      });
       recordingDevices.forEach((device) => {
         options.push({
           value: `recording:${device.id}`,
@@ -342,7 +401,9 @@ export function createTargetsFeature({
     button.appendChild(caret);
 
     let selectedTarget = currentTarget;
-    let selectedAction = isBindingButton ? (currentAction || "ToggleMute") : "Volume";
+    let selectedAction = isBindingButton
+      ? (currentAction || "ToggleMute")
+      : (currentAction === "Balance" ? "Balance" : "Volume");
 
     const { options, selectedValue, selectedKind, activeIntegrationOption } = buildTargetOptions(currentTarget);
     const placeholderOption = {
@@ -354,6 +415,22 @@ export function createTargetsFeature({
 
     const actionLabel = (action) => {
       if (action === "ToggleMute") return "Toggle Mute";
+      if (action === "PushToTalk") return "Push to Talk";
+      if (action?.VolumeStep) {
+        const percent = Math.round(Number(action.VolumeStep.delta || 0) * 100);
+        return `Volume ${percent >= 0 ? "+" : ""}${percent}%`;
+      }
+      if (action?.Shift) {
+        return `${action.Shift.latch ? "Latch" : "Shift"} to layer ${action.Shift.layer}`;
+      }
+      if (action?.SetVolume) {
+        return `Set ${Math.round(Number(action.SetVolume.value || 0) * 100)}%`;
+      }
+      if (action?.Duck) {
+        return `Duck to ${Math.round(Number(action.Duck.level || 0) * 100)}%`;
+      }
+      if (action === "Balance") return "Balance";
+      if (action === "CycleDefaultDevice") return "Cycle Default Output";
       if (action === "Volume" && isBindingButton) return "Trigger";
       return action;
     };
@@ -372,7 +449,7 @@ export function createTargetsFeature({
       label.className = "target-label";
 
       let text = option.label;
-      if (action && isBindingButton) {
+      if (action && (isBindingButton || action === "Balance")) {
         text += ` (${actionLabel(action)})`;
       }
       label.textContent = text;
@@ -405,11 +482,21 @@ export function createTargetsFeature({
       if (option.kind === "focus") {
         return "Focus";
       }
+      if (option.kind === "system-sounds") {
+        return "SystemSounds";
+      }
+      if (option.kind === "default-speakers") {
+        return "DefaultSpeakers";
+      }
+      if (option.kind === "default-microphone") {
+        return "DefaultMicrophone";
+      }
       if (option.kind === "device") {
         return { Device: { device_id: option.value } };
       }
       if (option.kind === "session") {
-        return { Application: { name: option.value } };
+        const name = option.appName || option.value;
+        return { Application: option.path ? { name, path: option.path } : { name } };
       }
       if (option.kind === "placeholder") {
         return "Unset";
@@ -475,6 +562,21 @@ export function createTargetsFeature({
             if (isBindingButton) {
               const actionOptions = [
                 { label: "Toggle Mute", value: "ToggleMute", kind: "action" },
+                { label: "Push to Talk", value: "PushToTalk", kind: "action" },
+              ];
+              setTimeout(() => {
+                openTargetPanel(actionOptions, selectedAction, "action", (actionOption) => {
+                  selectOption(targetOption, actionOption.value);
+                }, "Select Action");
+              }, 10);
+              return false;
+            }
+
+            // Master and devices can be panned instead of leveled.
+            if (targetOption.kind === "master" || targetOption.kind === "device") {
+              const actionOptions = [
+                { label: "Volume", value: "Volume", kind: "action" },
+                { label: "Balance", value: "Balance", kind: "action" },
               ];
               setTimeout(() => {
                 openTargetPanel(actionOptions, selectedAction, "action", (actionOption) => {
@@ -484,7 +586,7 @@ export function createTargetsFeature({
               return false;
             }
 
-            selectOption(targetOption);
+            selectOption(targetOption, "Volume");
             return true;
           },
         );
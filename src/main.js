@@ -6,6 +6,7 @@ import { createBindingsFeature } from "./features/bindings/bindings.js";
 import { createTargetsFeature } from "./features/targets/targets.js";
 import { createOsdFeature } from "./features/osd/osd.js";
 import { createMidiFeature } from "./features/midi/midi.js";
+import { createHotkeysFeature } from "./features/hotkeys/hotkeys.js";
 import { createTargetCore } from "./core/target_core.js";
 
 let coreApi = null;
@@ -33,6 +34,7 @@ let bindingsFeature = null;
 let targetsFeature = null;
 let osdFeature = null;
 let midiFeature = null;
+let hotkeysFeature = null;
 
 async function startPluginHostIfNeeded() {
   if (isOsdWindow) return;
@@ -266,10 +268,24 @@ const connectionsContent = document.getElementById("connections-content");
 const osdEnabledToggle = document.getElementById("osd-enabled");
 const osdMonitorSelect = document.getElementById("osd-monitor");
 const osdPositionPicker = document.getElementById("osd-position-picker");
+const osdWidthInput = document.getElementById("osd-width");
+const osdHeightInput = document.getElementById("osd-height");
+const osdOpacityInput = document.getElementById("osd-opacity");
+const osdAccentInput = document.getElementById("osd-accent");
+const osdAccentReset = document.getElementById("osd-accent-reset");
 const startWithWindowsSelect = document.getElementById("start-with-windows");
 const startInTraySelect = document.getElementById("start-in-tray");
 const minimizeToTraySelect = document.getElementById("minimize-to-tray");
 const exitToTraySelect = document.getElementById("exit-to-tray");
+const focusSessionScopeSelect = document.getElementById("focus-session-scope");
+const startupGraceSelect = document.getElementById("startup-grace");
+const doubleTapSelect = document.getElementById("double-tap-window");
+const learnWindowSelect = document.getElementById("learn-window");
+const hiddenSessionsInput = document.getElementById("hidden-sessions");
+const showHiddenSessionsSelect = document.getElementById("show-hidden-sessions");
+const logLevelSelect = document.getElementById("log-level");
+const hotkeyList = document.getElementById("hotkey-list");
+const hotkeyAdd = document.getElementById("hotkey-add");
 const resetAppDataButton = document.getElementById("reset-app-data");
 const osd = document.getElementById("volume-osd");
 // OSD elements are now dynamic
@@ -325,6 +341,10 @@ const defaultOsdSettings = {
   monitorIndex: 0,
   monitorName: null,
   anchor: "top-right",
+  width: 320,
+  height: 800,
+  opacity: 0.94,
+  accentColor: null,
 };
 
 // Integration connectivity is plugin-owned.
@@ -343,11 +363,41 @@ function showSetup(statusText) {
   }
 }
 
+let connectedInputName = null;
+let connectedOutputName = null;
+let outputHealth = new Map();
+let activeLayer = 0;
+
 function showMain(inputName, outputName) {
   setupScreen.classList.add("hidden");
   mainScreen.classList.remove("hidden");
+  connectedInputName = inputName || null;
+  connectedDevice.classList.remove("disconnected");
   connectedDevice.textContent = "Input: " + (inputName || "Connected");
-  connectedOutputDevice.textContent = "Output: " + (outputName || "Connected");
+  connectedOutputName = outputName || null;
+  outputHealth = new Map();
+  renderOutputStatus();
+  invoke("midi_output_status")
+    .then((statuses) => {
+      (statuses || []).forEach((status) => {
+        outputHealth.set(status.output_device_id, status.health);
+      });
+      renderOutputStatus();
+    })
+    .catch(() => {});
+}
+
+function renderOutputStatus() {
+  const states = Array.from(outputHealth.values());
+  const label = "Output: " + (connectedOutputName || "Connected");
+  let suffix = "";
+  if (states.includes("failed")) {
+    suffix = " (failed, reconnect the device)";
+  } else if (states.includes("reconnecting")) {
+    suffix = " (reconnecting)";
+  }
+  connectedOutputDevice.textContent = label + suffix;
+  connectedOutputDevice.classList.toggle("disconnected", Boolean(suffix));
 }
 
 function startSessionRefresh() {
@@ -499,6 +549,13 @@ let appSettings = {
   startInTray: false,
   minimizeToTray: false,
   exitToTray: false,
+  focusPrimarySessionOnly: false,
+  startupGraceMs: 0,
+  doubleTapMs: 300,
+  learnWindowMs: 150,
+  hiddenSessions: [],
+  showHiddenSessions: false,
+  logLevel: "info",
 };
 let appStarted = false;
 
@@ -512,10 +569,22 @@ settingsFeature = createSettingsFeature({
     osdEnabledToggle,
     osdMonitorSelect,
     osdPositionPicker,
+    osdWidthInput,
+    osdHeightInput,
+    osdOpacityInput,
+    osdAccentInput,
+    osdAccentReset,
     startWithWindowsSelect,
     startInTraySelect,
     minimizeToTraySelect,
     exitToTraySelect,
+    focusSessionScopeSelect,
+    startupGraceSelect,
+    doubleTapSelect,
+    learnWindowSelect,
+    hiddenSessionsInput,
+    showHiddenSessionsSelect,
+    logLevelSelect,
   },
   getOsdSettings: () => osdSettings,
   setOsdSettings: (next) => { osdSettings = next; },
@@ -526,6 +595,16 @@ settingsFeature = createSettingsFeature({
 });
 settingsFeature.bindUi();
 
+hotkeysFeature = createHotkeysFeature({
+  invoke,
+  dom: {
+    settingsButton,
+    hotkeyList,
+    hotkeyAdd,
+  },
+});
+hotkeysFeature.bindUi();
+
 profilesFeature = createProfilesFeature({
   invoke,
   dom: {
@@ -548,6 +627,7 @@ profilesFeature = createProfilesFeature({
   getOsdSettings: () => osdSettings,
   setOsdSettings: (next) => { osdSettings = next; },
   applyOsdSettings,
+  showAlert,
 });
 profilesFeature.bindUi();
 
@@ -579,6 +659,9 @@ osdFeature = createOsdFeature({
   resolveOsdTarget,
   createTargetIcon,
   resolveTargetKey,
+  reportContentHeight: (height) => {
+    invoke("report_osd_height", { height }).catch(() => {});
+  },
 });
 
 bindingsFeature = createBindingsFeature({
@@ -627,12 +710,26 @@ midiFeature = createMidiFeature({
     disconnectMidiButton: document.getElementById("disconnect-midi"),
     learnBindingButton: document.getElementById("learn-binding"),
     bindingAddFooterButton: document.getElementById("binding-add-footer-button"),
+    bindingUnlearnFooterButton: document.getElementById("binding-unlearn-footer-button"),
   },
   showSetup,
   showMain,
   refreshSessions,
+  onConnected: ({ inputId, outputId, auto }) => {
+    if (!auto) {
+      profilesFeature?.rememberMidiDevices?.(inputId, [outputId]);
+    }
+  },
   addBindingFromLearn: async (learned) => {
     const binding = createBindingFromLearn(learned);
+    const conflict = await invoke("find_binding_conflict", { binding }).catch(() => null);
+    if (conflict) {
+      const name = conflict.name || "another binding";
+      if (!confirm(`This control is already bound to "${name}". Replace it?`)) {
+        return;
+      }
+      bindings = bindings.filter((existing) => existing.id !== conflict.id);
+    }
     bindings.push(binding);
     editingBindingId = binding.id;
     pendingFocusBindingId = binding.id;
@@ -672,9 +769,21 @@ function cancelBindingDrag() {
 }
 
 function controlLabel(control) {
+  if (control.any_channel) {
+    return controlLabel({ ...control, any_channel: false, channel: "Any" });
+  }
   if (control.controller === 224) {
     return `Ch ${control.channel} Pitch Bend`;
   }
+  if (control.msg_type === "ProgramChange") {
+    return `Ch ${control.channel} Program ${control.controller}`;
+  }
+  if (control.msg_type === "ChannelPressure") {
+    return `Ch ${control.channel} Pressure`;
+  }
+  if (control.msg_type === "PolyAftertouch") {
+    return `Ch ${control.channel} Note ${control.controller} Pressure`;
+  }
   return `Ch ${control.channel} CC ${control.controller}`;
 }
 
@@ -686,17 +795,23 @@ function createTargetIcon(option) {
   return targetsFeature?.createTargetIcon?.(option) || document.createElement("span");
 }
 
-function relativeDelta(value) {
+function relativeDelta(value, relativeMode = "SignedBit") {
+  if (value < 0 || value > 127) {
+    return null;
+  }
+  if (relativeMode === "TwosComplement") {
+    return value >= 64 ? value - 128 : value;
+  }
+  if (relativeMode === "BinaryOffset") {
+    return value - 64;
+  }
   if (value === 0 || value === 64) {
     return 0;
   }
   if (value >= 1 && value <= 63) {
     return value;
   }
-  if (value >= 65 && value <= 127) {
-    return -(value - 64);
-  }
-  return null;
+  return -(value - 64);
 }
 
 function findBindingForEvent(payload) {
@@ -707,6 +822,10 @@ function findBindingForEvent(payload) {
     binding.device_id === payload.device_id
     && binding.control?.channel === payload.channel
     && binding.control?.controller === payload.controller,
+  ) || bindings.find((binding) =>
+    binding.device_id === payload.device_id
+    && binding.control?.any_channel
+    && binding.control?.controller === payload.controller,
   );
 }
 
@@ -715,7 +834,7 @@ function resolveOsdVolume(binding, payload) {
     return null;
   }
   if (binding.mode === "Relative") {
-    const delta = relativeDelta(payload.value);
+    const delta = relativeDelta(payload.value, binding.relative_mode);
     if (delta == null) {
       return null;
     }
@@ -730,10 +849,35 @@ function resolveOsdVolume(binding, payload) {
     osdBindingValues.set(binding.id, next);
     return next;
   }
-  if (binding.control?.controller === 224 && payload.value_14 != null) {
-    return payload.value_14 / 16383;
+  const wide = payload.value_14 != null
+    && (binding.control?.controller === 224 || binding.control?.lsb_controller != null);
+  let position = wide ? payload.value_14 / 16383 : payload.value / 127;
+  if (wide && binding.control?.controller === 224 && binding.pitch_bend_centered) {
+    position = Math.max(0, (payload.value_14 - 8192) / (16383 - 8192));
+  }
+  return positionToVolume(binding, binding.invert ? 1 - position : position);
+}
+
+// Mirrors bindings.rs `position_to_volume`.
+function positionToVolume(binding, position) {
+  let min = Math.min(1, Math.max(0, Number(binding.min_volume ?? 0)));
+  let max = Math.min(1, Math.max(0, Number(binding.max_volume ?? 1)));
+  if (max < min) {
+    [min, max] = [max, min];
+  }
+  const clamped = Math.min(1, Math.max(0, position));
+  return min + applyVolumeCurve(binding.volume_curve, clamped) * (max - min);
+}
+
+function applyVolumeCurve(curve, position) {
+  if (curve === "Logarithmic") {
+    return position <= 0 ? 0 : Math.pow(10, ((1 - position) * -60) / 20);
+  }
+  const exponent = Number(curve?.Power);
+  if (exponent > 0) {
+    return Math.pow(position, exponent);
   }
-  return payload.value / 127;
+  return position;
 }
 
 function showVolumeOsd(target, volume, focusSession) {
@@ -752,6 +896,14 @@ window.__OSD_UPDATE__ = (payload) => {
   osdFeature?.handleOsdUpdate?.(payload);
 };
 
+window.__OSD_METER__ = (entries) => {
+  osdFeature?.showMeters?.(entries);
+};
+
+window.__OSD_APPEARANCE__ = (appearance) => {
+  osdFeature?.applyAppearance?.(appearance);
+};
+
 function closeTargetPanel() {
   targetsFeature?.closeTargetPanel?.();
 }
@@ -1039,7 +1191,10 @@ function buildTargetSelect(currentTarget, isBindingButton = false, currentAction
 
 function createBindingFromLearn(payload) {
   const msgType = payload.msg_type || "ControlChange";
-  const isButton = msgType === "Note";
+  // Prefer the backend's inferred classification; fall back to the message type.
+  const isButton = payload.control_kind
+    ? payload.control_kind === "Button"
+    : msgType === "Note" || msgType === "ProgramChange";
   const control = {
     channel: payload.channel,
     controller: payload.controller,
@@ -1053,6 +1208,8 @@ function createBindingFromLearn(payload) {
     control,
     target: "Unset",
     action: isButton ? "ToggleMute" : "Volume",
+    // Learning while a shift layer is active puts the control on that layer.
+    layer: activeLayer,
     mode: "Absolute",
     deadzone: 0,
     debounce_ms: 0,
@@ -1107,6 +1264,96 @@ document.addEventListener("pointercancel", () => {
 
 
 async function setupListeners() {
+  await listen("profile_device_missing", (event) => {
+    if (isOsdWindow) return;
+    const payload = event.payload ?? {};
+    const devices = Array.isArray(payload.missing_devices) ? payload.missing_devices : [];
+    if (devices.length === 0) return;
+    showAlert(
+      `Profile "${payload.profile}" uses MIDI devices that are not connected: ${devices.join(", ")}`,
+      "Device not connected"
+    );
+  });
+
+  await listen("profile_midi_device_missing", (event) => {
+    if (isOsdWindow) return;
+    const payload = event.payload ?? {};
+    showAlert(
+      `Profile "${payload.profile}" uses MIDI device ${payload.device_id}, which is not connected. The current device stays in use.`,
+      "Device not connected"
+    );
+  });
+
+  // Re-sent on every OSD settings change; only alert when the set of missing monitors changes.
+  let lastMissingOsdMonitors = "[]";
+  await listen("osd_monitor_missing", (event) => {
+    if (isOsdWindow) return;
+    const missing = Array.isArray(event.payload?.missing) ? event.payload.missing : [];
+    const key = JSON.stringify(missing);
+    if (key === lastMissingOsdMonitors) return;
+    lastMissingOsdMonitors = key;
+    if (missing.length === 0) return;
+    const lines = missing.map((entry) => {
+      const monitor = String(entry.monitor ?? "").replace(/^\\\\\.\\/, "");
+      return `${monitor} is not connected; the OSD is shown on monitor ${Number(entry.fallback_index ?? 0) + 1} instead.`;
+    });
+    showAlert(lines.join(" "), "Monitor not connected");
+  });
+
+  await listen("midi_device_switched", (event) => {
+    if (isOsdWindow || !event.payload?.device_id) return;
+    midiFeature?.applySwitchedDevice?.(event.payload);
+  });
+
+  await listen("binding_unlearned", (event) => {
+    if (isOsdWindow) return;
+    const removed = event.payload;
+    midiFeature?.finishUnlearn?.();
+    if (!removed || !removed.id) return;
+    bindings = bindings.filter((binding) => binding.id !== removed.id);
+    renderBindings();
+  });
+
+  await listen("midi_device_status", (event) => {
+    if (isOsdWindow) return;
+    const connected = Boolean(event.payload?.connected);
+    const name = connectedInputName || "Connected";
+    connectedDevice.textContent = connected
+      ? `Input: ${name}`
+      : `Input: ${name} (disconnected, waiting for device)`;
+    connectedDevice.classList.toggle("disconnected", !connected);
+  });
+
+  await listen("midi_output_status", (event) => {
+    if (isOsdWindow) return;
+    const status = event.payload;
+    if (!status?.output_device_id) return;
+    outputHealth.set(status.output_device_id, status.health);
+    renderOutputStatus();
+  });
+
+  await listen("profile_switched", async (event) => {
+    if (isOsdWindow) return;
+    const name = event.payload?.name;
+    if (!name) return;
+    await loadProfileByName(name).catch(() => { });
+  });
+
+  await listen("layer_changed", (event) => {
+    activeLayer = Number(event.payload?.layer ?? 0);
+  });
+
+  await listen("bindings_migrated", async () => {
+    if (isOsdWindow) return;
+    const profile = await invoke("get_active_profile");
+    if (!profile) return;
+    bindings = (profile.bindings || []).map((binding, index) => ({
+      ...binding,
+      name: binding.name?.trim() || bindingFallbackName(binding, index),
+    }));
+    renderBindings();
+  });
+
   await listen("midi_event", (event) => {
     if (mainScreen.classList.contains("hidden")) {
       midiStatus.textContent = `MIDI: ${JSON.stringify(event.payload)}`;
@@ -1158,7 +1405,8 @@ async function setupListeners() {
   });
 
   await listen("mute_update", (event) => {
-    if (!osdSettings.enabled && isOsdWindow) {
+    // OSD windows are driven by __OSD_UPDATE__, routed per window by the backend.
+    if (isOsdWindow) {
       return;
     }
     let payload = event.payload;
@@ -1192,7 +1440,7 @@ async function setupListeners() {
   });
 
   await listen("volume_update", (event) => {
-    if (!osdSettings.enabled && isOsdWindow) {
+    if (isOsdWindow) {
       return;
     }
     let payload = event.payload ?? {};
@@ -1298,7 +1546,14 @@ async function startMainApp() {
         monitorIndex: Number(profile.osd_settings.monitor_index ?? 0),
         monitorName: profile.osd_settings.monitor_name || null,
         monitorId: profile.osd_settings.monitor_id || null,
+        monitorEdid: profile.osd_settings.monitor_edid || null,
+        monitorGeometry: profile.osd_settings.monitor_geometry || null,
         anchor: profile.osd_settings.anchor || "top-right",
+        instances: Array.isArray(profile.osd_settings.instances) ? profile.osd_settings.instances : [],
+        width: Number(profile.osd_settings.width ?? 320),
+        height: Number(profile.osd_settings.height ?? 800),
+        opacity: Number(profile.osd_settings.opacity ?? 0.94),
+        accentColor: profile.osd_settings.accent_color || null,
       };
       
       // Reconcile index if ID matches a different monitor (e.g. after cable swap/reboot)
@@ -1332,6 +1587,10 @@ async function init() {
   await setupListeners().catch(() => { });
   if (isOsdWindow) {
     await loadOsdSettings();
+    osdFeature?.applyAppearance?.({
+      opacity: osdSettings.opacity,
+      accent_color: osdSettings.accentColor,
+    });
     await refreshSessions().catch(() => { });
     setInterval(() => {
       refreshSessions().catch(() => { });
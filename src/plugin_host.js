@@ -34,9 +34,12 @@ export function createPluginHost({ invoke, listen, onUpdatePluginSettings, onInv
 
   // WebSocket bridge helpers
   const wsMessageHandlers = new Map(); // id -> Set(fn)
+  const wsStatusHandlers = new Map(); // id -> Set(fn)
   let wsListenersBound = false;
   let wsMessageUnlisten = null;
   let wsClosedUnlisten = null;
+  let wsReconnectingUnlisten = null;
+  let wsReconnectedUnlisten = null;
   let triggerListenerUnlisten = null;
 
   function registerIntegration(integration) {
@@ -158,6 +161,17 @@ export function createPluginHost({ invoke, listen, onUpdatePluginSettings, onInv
                 setProfileState({ name: profileState.name, plugin_settings: copy });
               } catch { }
             },
+            // Validated against the manifest's `settings` schema; throws a readable message if rejected.
+            setSetting: async (key, value) => {
+              const next = await invoke("set_plugin_setting", { pluginId, key, value: value ?? null });
+              if (typeof onUpdatePluginSettings === "function") {
+                await onUpdatePluginSettings(pluginId, next);
+              }
+              const copy = { ...(profileState.plugin_settings || {}) };
+              copy[pluginId] = next;
+              setProfileState({ name: profileState.name, plugin_settings: copy });
+              return next;
+            },
             onChanged: (handler) => {
               if (typeof handler !== "function") return () => { };
               const wrapped = (state) => {
@@ -212,16 +226,30 @@ export function createPluginHost({ invoke, listen, onUpdatePluginSettings, onInv
               });
             },
           },
+          midi: {
+            sendRaw: (deviceId, bytes) => invoke("send_raw_midi", {
+              deviceId,
+              bytes: Array.from(bytes),
+            }),
+            setPassthrough: (enabled) => invoke("set_midi_passthrough", { enabled: Boolean(enabled) }),
+            onRaw: (handler) => listen("midi_raw", (event) => {
+              try { handler(event?.payload); } catch (e) { }
+            }),
+          },
           ws: {
-            open: (url, headers = {}, connectTimeoutMs = 500) => invoke("ws_open", {
+            open: (url, headers = {}, connectTimeoutMs = 500, options = {}) => invoke("ws_open", {
               url,
-              headers,
-              connectTimeoutMs,
-              // Compatibility
-              connect_timeout_ms: connectTimeoutMs,
+              options: {
+                headers: headers ?? {},
+                connect_timeout_ms: connectTimeoutMs ?? null,
+                ping_interval_ms: options?.pingIntervalMs ?? null,
+                auto_reconnect: Boolean(options?.autoReconnect),
+                max_reconnect_attempts: options?.maxReconnectAttempts ?? null,
+              },
             }),
             send: (id, text) => invoke("ws_send", { id, text }),
-            close: (id) => invoke("ws_close", { id }),
+            sendBinary: (id, base64) => invoke("ws_send_binary", { id, base64 }),
+            close: (id, code, reason) => invoke("ws_close", { id, code: code ?? null, reason: reason ?? null }),
             onMessage: (id, handler) => {
               if (!wsMessageHandlers.has(id)) {
                 wsMessageHandlers.set(id, new Set());
@@ -229,6 +257,13 @@ export function createPluginHost({ invoke, listen, onUpdatePluginSettings, onInv
               wsMessageHandlers.get(id).add(handler);
               return () => wsMessageHandlers.get(id)?.delete(handler);
             },
+            onStatus: (id, handler) => {
+              if (!wsStatusHandlers.has(id)) {
+                wsStatusHandlers.set(id, new Set());
+              }
+              wsStatusHandlers.get(id).add(handler);
+              return () => wsStatusHandlers.get(id)?.delete(handler);
+            },
           },
         };
 
@@ -262,14 +297,31 @@ export function createPluginHost({ invoke, listen, onUpdatePluginSettings, onInv
       });
     });
 
-    wsClosedUnlisten = await listen("ws_closed", (event) => {
+    const notifyStatus = (event, status) => {
       let payload = event?.payload;
       if (typeof payload === "string") {
         try { payload = JSON.parse(payload); } catch { payload = null; }
       }
       const id = payload?.id;
+      if (id == null) return null;
+      wsStatusHandlers.get(id)?.forEach((fn) => {
+        try { fn({ ...payload, status }); } catch (e) { }
+      });
+      return id;
+    };
+
+    wsReconnectingUnlisten = await listen("ws_reconnecting", (event) => {
+      notifyStatus(event, "reconnecting");
+    });
+    wsReconnectedUnlisten = await listen("ws_reconnected", (event) => {
+      notifyStatus(event, "reconnected");
+    });
+
+    wsClosedUnlisten = await listen("ws_closed", (event) => {
+      const id = notifyStatus(event, "closed");
       if (id != null) {
         wsMessageHandlers.delete(id);
+        wsStatusHandlers.delete(id);
       }
     });
   }
@@ -311,6 +363,14 @@ export function createPluginHost({ invoke, listen, onUpdatePluginSettings, onInv
       try { await wsClosedUnlisten(); } catch { }
       wsClosedUnlisten = null;
     }
+    if (wsReconnectingUnlisten) {
+      try { await wsReconnectingUnlisten(); } catch { }
+      wsReconnectingUnlisten = null;
+    }
+    if (wsReconnectedUnlisten) {
+      try { await wsReconnectedUnlisten(); } catch { }
+      wsReconnectedUnlisten = null;
+    }
     wsListenersBound = false;
   }
 